@@ -0,0 +1,199 @@
+use serde_json::Value;
+use std::fmt;
+
+use crate::models::RawResponse;
+
+/// Why X refused to return a user or tweet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnavailableKind {
+    UserProtected,
+    UserSuspended,
+    NotFound,
+    TweetDeleted,
+}
+
+impl UnavailableKind {
+    /// Stable error code surfaced in MCP tool error payloads.
+    pub fn code(self) -> &'static str {
+        match self {
+            UnavailableKind::UserProtected => "USER_PROTECTED",
+            UnavailableKind::UserSuspended => "USER_SUSPENDED",
+            UnavailableKind::NotFound => "NOT_FOUND",
+            UnavailableKind::TweetDeleted => "TWEET_DELETED",
+        }
+    }
+}
+
+/// A user or tweet X reported as protected, suspended, deleted or missing.
+#[derive(Debug, Clone)]
+pub struct ResourceUnavailable {
+    pub kind: UnavailableKind,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+    pub detail: String,
+}
+
+impl ResourceUnavailable {
+    pub fn not_found(resource_type: &str, resource_id: &str, detail: String) -> Self {
+        Self {
+            kind: UnavailableKind::NotFound,
+            resource_type: Some(resource_type.to_string()),
+            resource_id: Some(resource_id.to_string()),
+            detail,
+        }
+    }
+}
+
+impl fmt::Display for ResourceUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.detail)
+    }
+}
+
+impl std::error::Error for ResourceUnavailable {}
+
+/// Classify one entry of an X API `errors` array (or a top-level problem
+/// body). Returns `None` for errors unrelated to resource availability.
+pub fn classify_error(error: &Value) -> Option<ResourceUnavailable> {
+    let field = |key: &str| error.get(key).and_then(|v| v.as_str()).unwrap_or("");
+    let detail = field("detail").to_string();
+    let detail_lower = detail.to_lowercase();
+    let title = field("title").to_lowercase();
+    let problem_type = field("type");
+    let resource_type = Some(field("resource_type"))
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+    let is_tweet = resource_type.as_deref() == Some("tweet") || detail_lower.contains("tweet");
+
+    let kind = if detail_lower.contains("suspended") {
+        UnavailableKind::UserSuspended
+    } else if problem_type.ends_with("/not-authorized-for-resource")
+        || title == "authorization error"
+        || detail_lower.contains("not authorized to see")
+    {
+        UnavailableKind::UserProtected
+    } else if problem_type.ends_with("/resource-not-found")
+        || title == "not found error"
+        || detail_lower.contains("could not find")
+    {
+        if is_tweet {
+            // X reports deleted tweets as missing; a well-formed id that
+            // cannot be found has almost always been deleted.
+            UnavailableKind::TweetDeleted
+        } else {
+            UnavailableKind::NotFound
+        }
+    } else {
+        return None;
+    };
+
+    let resource_id = ["resource_id", "value"]
+        .iter()
+        .find_map(|key| error.get(*key).and_then(|v| v.as_str()))
+        .map(str::to_string);
+
+    Some(ResourceUnavailable {
+        kind,
+        resource_type,
+        resource_id,
+        detail: if detail.is_empty() {
+            kind.code().to_string()
+        } else {
+            detail
+        },
+    })
+}
+
+/// First availability problem in a response's partial `errors` array.
+pub fn classify_response(raw: &RawResponse) -> Option<ResourceUnavailable> {
+    raw.errors.as_ref()?.iter().find_map(classify_error)
+}
+
+/// Classify a non-2xx X API response body.
+pub fn classify_body(body: &str) -> Option<ResourceUnavailable> {
+    let value: Value = serde_json::from_str(body).ok()?;
+    value
+        .get("errors")
+        .and_then(|v| v.as_array())
+        .and_then(|errors| errors.iter().find_map(classify_error))
+        .or_else(|| classify_error(&value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind_of(error: Value) -> Option<UnavailableKind> {
+        classify_error(&error).map(|u| u.kind)
+    }
+
+    #[test]
+    fn maps_representative_x_error_shapes() {
+        assert_eq!(
+            kind_of(serde_json::json!({
+                "value": "spammer",
+                "detail": "User has been suspended: [spammer].",
+                "title": "Forbidden",
+                "resource_type": "user",
+                "parameter": "username",
+                "resource_id": "spammer",
+                "type": "https://api.twitter.com/2/problems/resource-not-found"
+            })),
+            Some(UnavailableKind::UserSuspended)
+        );
+        assert_eq!(
+            kind_of(serde_json::json!({
+                "value": "1900100012345678901",
+                "detail": "Sorry, you are not authorized to see the Tweet with id: [1900100012345678901].",
+                "title": "Authorization Error",
+                "resource_type": "tweet",
+                "parameter": "id",
+                "resource_id": "1900100012345678901",
+                "type": "https://api.twitter.com/2/problems/not-authorized-for-resource"
+            })),
+            Some(UnavailableKind::UserProtected)
+        );
+        assert_eq!(
+            kind_of(serde_json::json!({
+                "value": "1900100012345678901",
+                "detail": "Could not find tweet with id: [1900100012345678901].",
+                "title": "Not Found Error",
+                "resource_type": "tweet",
+                "parameter": "id",
+                "resource_id": "1900100012345678901",
+                "type": "https://api.twitter.com/2/problems/resource-not-found"
+            })),
+            Some(UnavailableKind::TweetDeleted)
+        );
+        assert_eq!(
+            kind_of(serde_json::json!({
+                "value": "nobody_here",
+                "detail": "Could not find user with username: [nobody_here].",
+                "title": "Not Found Error",
+                "resource_type": "user",
+                "parameter": "username",
+                "resource_id": "nobody_here",
+                "type": "https://api.twitter.com/2/problems/resource-not-found"
+            })),
+            Some(UnavailableKind::NotFound)
+        );
+        assert_eq!(
+            kind_of(serde_json::json!({
+                "title": "Too Many Requests",
+                "detail": "Too Many Requests",
+                "type": "about:blank",
+                "status": 429
+            })),
+            None
+        );
+    }
+
+    #[test]
+    fn classifies_error_bodies_and_keeps_resource_id() {
+        let body = r#"{"errors":[{"parameters":{"id":["123"]},"message":"The `id` query parameter value [123] is not valid"},{"value":"123","detail":"Could not find tweet with id: [123].","title":"Not Found Error","resource_type":"tweet","resource_id":"123","type":"https://api.twitter.com/2/problems/resource-not-found"}]}"#;
+        let unavailable = classify_body(body).expect("classified");
+        assert_eq!(unavailable.kind.code(), "TWEET_DELETED");
+        assert_eq!(unavailable.resource_id.as_deref(), Some("123"));
+        assert!(classify_body("not json").is_none());
+    }
+}
@@ -3,22 +3,17 @@ use anyhow::{bail, Result};
 
 const XAI_ENDPOINT: &str = "https://api.x.ai/v1/chat/completions";
 
-// Rough pricing per 1M tokens (USD)
-fn model_pricing(model: &str) -> (f64, f64) {
-    match model {
-        "grok-3" => (3.00, 15.00),
-        "grok-3-mini" => (0.10, 0.40),
-        "grok-2" => (2.00, 10.00),
-        _ => (0.10, 0.40),
-    }
+/// Cost in USD of a completion with the given token usage, at the
+/// configured rate for `model`; `None` when the model is unpriced.
+pub fn usage_cost_usd(model: &str, prompt_tokens: u64, completion_tokens: u64) -> Option<f64> {
+    crate::costs::token_cost_usd(model, prompt_tokens, completion_tokens)
 }
 
 /// Estimate cost from token usage.
 pub fn estimate_cost(model: &str, prompt_tokens: u64, completion_tokens: u64) -> String {
-    let (input_rate, output_rate) = model_pricing(model);
-    let input_cost = (prompt_tokens as f64 / 1_000_000.0) * input_rate;
-    let output_cost = (completion_tokens as f64 / 1_000_000.0) * output_rate;
-    let total = input_cost + output_cost;
+    let Some(total) = usage_cost_usd(model, prompt_tokens, completion_tokens) else {
+        return format!("unpriced (no rate for {model})");
+    };
 
     if total < 0.0001 {
         "<$0.0001".to_string()
@@ -41,13 +36,13 @@ pub async fn grok_chat(
         "max_tokens": opts.max_tokens,
     });
 
-    let res = http
-        .post(XAI_ENDPOINT)
-        .header("Authorization", format!("Bearer {api_key}"))
-        .header("Content-Type", "application/json")
-        .json(&body)
-        .send()
-        .await?;
+    let res = crate::api::xai::send_with_retry(
+        http.post(XAI_ENDPOINT)
+            .header("Authorization", format!("Bearer {api_key}"))
+            .header("Content-Type", "application/json")
+            .json(&body),
+    )
+    .await?;
 
     let status = res.status().as_u16();
 
@@ -170,6 +165,168 @@ pub async fn analyze_tweets(
     grok_chat(http, api_key, &messages, opts).await
 }
 
+/// Summarize what an account has been posting about in one paragraph,
+/// citing representative tweets as `[n]`.
+pub async fn summarize_user(
+    http: &reqwest::Client,
+    api_key: &str,
+    username: &str,
+    tweets: &[Tweet],
+    opts: &GrokOpts,
+) -> Result<GrokResponse> {
+    if tweets.is_empty() {
+        bail!("No recent tweets from @{username} to summarize");
+    }
+
+    let messages = vec![
+        GrokMessage {
+            role: "system".to_string(),
+            content: TWEET_ANALYST_SYSTEM.to_string(),
+        },
+        GrokMessage {
+            role: "user".to_string(),
+            content: format!(
+                "Here are {} recent tweets from @{username}:\n\n{}\n\nWrite one concise paragraph describing what this account has been posting about. Cite 2-4 representative tweets inline using their bracket numbers, e.g. [3]. No bullet points.",
+                tweets.len(),
+                format_tweets_for_context(tweets)
+            ),
+        },
+    ];
+
+    grok_chat(http, api_key, &messages, opts).await
+}
+
+/// Ask for a report headline and the key claims circulating about `topic`,
+/// returned as a JSON object (see [`parse_report_brief`]).
+pub async fn report_brief(
+    http: &reqwest::Client,
+    api_key: &str,
+    topic: &str,
+    tweets: &[Tweet],
+    opts: &GrokOpts,
+) -> Result<GrokResponse> {
+    if tweets.is_empty() {
+        bail!("No tweets about \"{topic}\" to report on");
+    }
+
+    let messages = vec![
+        GrokMessage {
+            role: "system".to_string(),
+            content: TWEET_ANALYST_SYSTEM.to_string(),
+        },
+        GrokMessage {
+            role: "user".to_string(),
+            content: format!(
+                "Here are {} tweets about \"{topic}\":\n\n{}\n\nReturn ONLY a JSON object, no markdown fences: {{\"headline\": one sentence capturing the state of the conversation, \"key_claims\": 3-5 short strings, each a distinct claim being made, citing supporting tweets inline as [n]}}.",
+                tweets.len(),
+                format_tweets_for_context(tweets)
+            ),
+        },
+    ];
+
+    grok_chat(http, api_key, &messages, opts).await
+}
+
+/// Parse [`report_brief`] output, tolerating code fences and surrounding
+/// prose. Falls back to the first line as headline and any bullet lines as
+/// claims when the model ignored the JSON instruction.
+pub fn parse_report_brief(content: &str) -> ReportBrief {
+    let json = content
+        .find('{')
+        .zip(content.rfind('}'))
+        .filter(|(start, end)| start < end)
+        .and_then(|(start, end)| serde_json::from_str::<ReportBrief>(&content[start..=end]).ok());
+    if let Some(brief) = json {
+        return brief;
+    }
+
+    let mut lines = content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("```"));
+    let headline = lines
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches('#')
+        .trim()
+        .to_string();
+    let key_claims = lines
+        .filter_map(|l| l.strip_prefix("- ").or_else(|| l.strip_prefix("* ")))
+        .map(str::to_string)
+        .collect();
+    ReportBrief {
+        headline,
+        key_claims,
+    }
+}
+
+/// Translate `text` into `target_lang`, asking for the detected source
+/// language alongside (see [`parse_translation`]).
+pub async fn translate(
+    http: &reqwest::Client,
+    api_key: &str,
+    text: &str,
+    target_lang: &str,
+    opts: &GrokOpts,
+) -> Result<GrokResponse> {
+    let messages = vec![
+        GrokMessage {
+            role: "system".to_string(),
+            content: "You are a precise translator of social media posts. Keep @mentions, #hashtags, URLs and emoji unchanged. Preserve tone and slang rather than formalizing it.".to_string(),
+        },
+        GrokMessage {
+            role: "user".to_string(),
+            content: format!(
+                "Translate this post into the language with code \"{target_lang}\". Return ONLY a JSON object, no markdown fences: {{\"source_lang\": ISO 639-1 code of the original, \"translation\": the translated text}}.\n\n{text}"
+            ),
+        },
+    ];
+
+    grok_chat(http, api_key, &messages, opts).await
+}
+
+/// Parse [`translate`] output into `(source_lang, translation)`. When the
+/// model ignored the JSON instruction, the whole reply is the translation
+/// and the source language is unknown.
+pub fn parse_translation(content: &str) -> (Option<String>, String) {
+    let parsed = content
+        .find('{')
+        .zip(content.rfind('}'))
+        .filter(|(start, end)| start < end)
+        .and_then(|(start, end)| {
+            serde_json::from_str::<serde_json::Value>(&content[start..=end]).ok()
+        });
+    let field = |key: &str| {
+        parsed
+            .as_ref()
+            .and_then(|v| v.get(key))
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+    match field("translation") {
+        Some(translation) => (field("source_lang"), translation),
+        None => (None, content.trim().to_string()),
+    }
+}
+
+/// Extract distinct 1-based `[n]` citation markers from model output, in
+/// order of first appearance, ignoring numbers above `max`.
+pub fn cited_indices(content: &str, max: usize) -> Vec<usize> {
+    let mut out = Vec::new();
+    for part in content.split('[').skip(1) {
+        let Some((inner, _)) = part.split_once(']') else {
+            continue;
+        };
+        if let Ok(n) = inner.trim().parse::<usize>() {
+            if (1..=max).contains(&n) && !out.contains(&n) {
+                out.push(n);
+            }
+        }
+    }
+    out
+}
+
 /// General-purpose query with optional context.
 pub async fn analyze_query(
     http: &reqwest::Client,
@@ -197,6 +354,42 @@ pub async fn analyze_query(
     grok_chat(http, api_key, &messages, opts).await
 }
 
+/// Ask which numbered trends relate to `topic`; the reply lists relevant
+/// trends as `[n]` markers, most relevant first (see [`cited_indices`]).
+pub async fn classify_trends(
+    http: &reqwest::Client,
+    api_key: &str,
+    topic: &str,
+    trends: &[String],
+    opts: &GrokOpts,
+) -> Result<GrokResponse> {
+    if trends.is_empty() {
+        bail!("No trends to classify");
+    }
+
+    let trend_list: String = trends
+        .iter()
+        .enumerate()
+        .map(|(i, t)| format!("[{}] {}", i + 1, t))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let messages = vec![
+        GrokMessage {
+            role: "system".to_string(),
+            content: "You are a trend analyst. Judge relevance strictly; omit anything only loosely connected.".to_string(),
+        },
+        GrokMessage {
+            role: "user".to_string(),
+            content: format!(
+                "Topic: {topic}\n\nTrending on X:\n{trend_list}\n\nReply with only the bracket numbers of trends relevant to the topic, most relevant first, e.g. [4] [1]. Reply \"none\" if nothing is relevant."
+            ),
+        },
+    ];
+
+    grok_chat(http, api_key, &messages, opts).await
+}
+
 /// Summarize trending topics.
 #[allow(dead_code)]
 pub async fn summarize_trends(
@@ -231,3 +424,52 @@ pub async fn summarize_trends(
 
     grok_chat(http, api_key, &messages, opts).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{cited_indices, parse_report_brief, parse_translation, usage_cost_usd};
+
+    #[test]
+    fn extracts_distinct_in_range_citations() {
+        let content =
+            "Mostly ships Rust tooling [2], with asides on AI [5] and [2]; see [x] and [9].";
+        assert_eq!(cited_indices(content, 5), vec![2, 5]);
+    }
+
+    #[test]
+    fn usage_cost_follows_model_pricing() {
+        let mini = usage_cost_usd("grok-3-mini", 1_000_000, 1_000_000).unwrap();
+        assert!((mini - 0.50).abs() < 1e-9);
+        let full = usage_cost_usd("grok-3", 2_000, 1_000).unwrap();
+        assert!((full - 0.021).abs() < 1e-9);
+        assert_eq!(usage_cost_usd("grok-99-unpriced", 2_000, 1_000), None);
+    }
+
+    #[test]
+    fn translation_parses_json_or_uses_whole_reply() {
+        let (lang, text) =
+            parse_translation("{\"source_lang\": \"ja\", \"translation\": \"Good morning\"}");
+        assert_eq!(lang.as_deref(), Some("ja"));
+        assert_eq!(text, "Good morning");
+
+        let (lang, text) = parse_translation("  Good morning  ");
+        assert_eq!(lang, None);
+        assert_eq!(text, "Good morning");
+    }
+
+    #[test]
+    fn report_brief_parses_fenced_json_and_falls_back_to_lines() {
+        let fenced = "```json\n{\"headline\": \"Launch lands well\", \"key_claims\": [\"Faster builds [1]\"]}\n```";
+        let brief = parse_report_brief(fenced);
+        assert_eq!(brief.headline, "Launch lands well");
+        assert_eq!(brief.key_claims, vec!["Faster builds [1]"]);
+
+        let prose =
+            parse_report_brief("# Mixed reception\n\n- Pricing criticized [2]\n- Docs praised [4]");
+        assert_eq!(prose.headline, "Mixed reception");
+        assert_eq!(
+            prose.key_claims,
+            vec!["Pricing criticized [2]", "Docs praised [4]"]
+        );
+    }
+}
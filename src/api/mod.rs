@@ -1,3 +1,5 @@
+pub mod availability;
 pub mod grok;
+pub mod redirect;
 pub mod twitter;
 pub mod xai;
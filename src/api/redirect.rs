@@ -0,0 +1,176 @@
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::time::Duration;
+
+pub const DEFAULT_MAX_HOPS: usize = 10;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedUrl {
+    pub input_url: String,
+    pub final_url: String,
+    /// Every URL visited after the input, in order.
+    pub redirects: Vec<String>,
+    pub hops: usize,
+    pub status_code: Option<u16>,
+}
+
+/// Follow redirects from `url` one hop at a time (at most `max_hops`) and
+/// return the final destination. Fails on redirect loops or when the chain is
+/// longer than `max_hops`.
+pub async fn resolve_url(url: &str, max_hops: usize) -> Result<ResolvedUrl> {
+    let http = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()?;
+
+    let mut current =
+        url::Url::parse(url.trim()).map_err(|_| anyhow::anyhow!("Invalid URL: {url}"))?;
+    if !matches!(current.scheme(), "http" | "https") {
+        bail!("Unsupported URL scheme: {}", current.scheme());
+    }
+
+    let mut seen = HashSet::from([current.to_string()]);
+    let mut redirects = Vec::new();
+
+    loop {
+        let mut res = http.head(current.clone()).send().await?;
+        // Some shorteners reject HEAD; retry the hop with GET.
+        if matches!(res.status().as_u16(), 403 | 405 | 501) {
+            res = http.get(current.clone()).send().await?;
+        }
+
+        let status = res.status();
+        let location = res
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok());
+        let next = match location {
+            Some(location) if status.is_redirection() => current
+                .join(location)
+                .map_err(|_| anyhow::anyhow!("Invalid redirect target: {location}"))?,
+            _ => {
+                return Ok(ResolvedUrl {
+                    input_url: url.to_string(),
+                    final_url: current.to_string(),
+                    hops: redirects.len(),
+                    redirects,
+                    status_code: Some(status.as_u16()),
+                });
+            }
+        };
+
+        if !seen.insert(next.to_string()) {
+            bail!("Redirect loop detected at {next}");
+        }
+        if redirects.len() >= max_hops {
+            bail!("Too many redirects (more than {max_hops}) starting from {url}");
+        }
+        redirects.push(next.to_string());
+        current = next;
+    }
+}
+
+/// Every distinct `http(s)://` URL in `text`, in order of appearance, with
+/// trailing punctuation stripped.
+pub fn extract_urls(text: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        let Some(start) = word.find("https://").or_else(|| word.find("http://")) else {
+            continue;
+        };
+        let url = word[start..].trim_end_matches(|c: char| {
+            matches!(
+                c,
+                '.' | ','
+                    | ';'
+                    | ':'
+                    | '!'
+                    | '?'
+                    | ')'
+                    | ']'
+                    | '"'
+                    | '\''
+                    | '\u{201d}'
+                    | '\u{2026}'
+            )
+        });
+        if url::Url::parse(url).is_ok() && !urls.iter().any(|u| u == url) {
+            urls.push(url.to_string());
+        }
+    }
+    urls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Redirect `/hop/N` to `/hop/N+1` until `final_hop`, which answers 200.
+    /// With `loop_back`, the last hop points at `/hop/0` instead.
+    async fn spawn_redirect_chain(final_hop: usize, loop_back: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut buf = [0u8; 4096];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let hop: usize = request
+                    .split_whitespace()
+                    .nth(1)
+                    .and_then(|path| path.strip_prefix("/hop/"))
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or(0);
+                let response = if hop < final_hop {
+                    format!("HTTP/1.1 301 Moved\r\nLocation: /hop/{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", hop + 1)
+                } else if loop_back {
+                    "HTTP/1.1 302 Found\r\nLocation: /hop/0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+                } else {
+                    "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+                };
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{addr}/hop/0")
+    }
+
+    #[test]
+    fn extracts_distinct_urls_without_trailing_punctuation() {
+        let urls = extract_urls(
+            "Read this (https://t.co/abc123). Also https://example.com/a?b=1, and again https://t.co/abc123 \u{2014} ftp://nope",
+        );
+        assert_eq!(urls, ["https://t.co/abc123", "https://example.com/a?b=1"]);
+        assert!(extract_urls("no links here").is_empty());
+    }
+
+    #[tokio::test]
+    async fn follows_chain_to_final_url() {
+        let start = spawn_redirect_chain(3, false).await;
+        let resolved = resolve_url(&start, DEFAULT_MAX_HOPS)
+            .await
+            .expect("resolved");
+        assert_eq!(resolved.hops, 3);
+        assert!(resolved.final_url.ends_with("/hop/3"));
+        assert_eq!(resolved.status_code, Some(200));
+    }
+
+    #[tokio::test]
+    async fn rejects_loops_and_long_chains() {
+        let looping = spawn_redirect_chain(2, true).await;
+        let err = resolve_url(&looping, DEFAULT_MAX_HOPS)
+            .await
+            .expect_err("loop");
+        assert!(err.to_string().contains("Redirect loop"));
+
+        let long = spawn_redirect_chain(5, false).await;
+        let err = resolve_url(&long, 2).await.expect_err("too many hops");
+        assert!(err.to_string().contains("Too many redirects"));
+    }
+}
@@ -1,6 +1,7 @@
-use anyhow::{bail, Result};
+use anyhow::Result;
 use std::collections::{HashMap, HashSet};
 
+use crate::api::availability::{self, ResourceUnavailable};
 use crate::client::{XClient, FIELDS};
 use crate::models::*;
 
@@ -43,6 +44,16 @@ pub fn parse_tweets(raw: &RawResponse) -> Vec<Tweet> {
                 .and_then(|v| v.as_str())
                 .unwrap_or("")
                 .to_string();
+            let in_reply_to_id = t
+                .get("referenced_tweets")
+                .and_then(|v| v.as_array())
+                .and_then(|refs| {
+                    refs.iter()
+                        .find(|r| r.get("type").and_then(|v| v.as_str()) == Some("replied_to"))
+                })
+                .and_then(|r| r.get("id"))
+                .and_then(|v| v.as_str())
+                .map(String::from);
 
             let (username, name) = users
                 .get(&author_id)
@@ -152,6 +163,7 @@ pub fn parse_tweets(raw: &RawResponse) -> Vec<Tweet> {
                 name,
                 created_at,
                 conversation_id,
+                in_reply_to_id,
                 metrics,
                 urls,
                 mentions,
@@ -219,6 +231,35 @@ pub async fn search(
     until: Option<&str>,
     full_archive: bool,
 ) -> Result<Vec<Tweet>> {
+    let (tweets, _, _) = search_paged(
+        client,
+        token,
+        query,
+        pages,
+        sort_order,
+        since,
+        until,
+        full_archive,
+        None,
+    )
+    .await?;
+    Ok(tweets)
+}
+
+/// Search tweets starting from an optional pagination token. Returns the
+/// tweets, the token for the next page (if any), and how many pages were read.
+#[allow(clippy::too_many_arguments)]
+pub async fn search_paged(
+    client: &XClient,
+    token: &str,
+    query: &str,
+    pages: u32,
+    sort_order: &str,
+    since: Option<&str>,
+    until: Option<&str>,
+    full_archive: bool,
+    start_token: Option<&str>,
+) -> Result<(Vec<Tweet>, Option<String>, u32)> {
     let max_per_page = if full_archive { 500 } else { 100 };
     let encoded = urlencoding::encode(query);
     let endpoint = if full_archive {
@@ -240,7 +281,11 @@ pub async fn search(
     }
 
     let mut all_tweets = Vec::new();
-    let mut next_token: Option<String> = None;
+    let mut next_token: Option<String> = start_token
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string);
+    let mut fetched_pages = 0u32;
 
     for page in 0..pages {
         let pagination = match &next_token {
@@ -254,6 +299,7 @@ pub async fn search(
         let raw = client.bearer_get(&path, token).await?;
         let tweets = parse_tweets(&raw);
         all_tweets.extend(tweets);
+        fetched_pages += 1;
 
         next_token = raw.meta.and_then(|m| m.next_token);
         if next_token.is_none() {
@@ -264,7 +310,83 @@ pub async fn search(
         }
     }
 
-    Ok(all_tweets)
+    Ok((all_tweets, next_token, fetched_pages))
+}
+
+const MEDIA_FIELDS: &str = "tweet.fields=created_at,public_metrics,author_id,conversation_id,entities,referenced_tweets,attachments&expansions=author_id,attachments.media_keys&user.fields=username,name,public_metrics&media.fields=type,url,preview_image_url,variants,alt_text";
+
+/// Search recent tweets with media attached, returning up to `limit` tweets
+/// that carry media, each with its raw media objects.
+pub async fn search_media(
+    client: &XClient,
+    token: &str,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<(Tweet, Vec<serde_json::Value>)>> {
+    let encoded = urlencoding::encode(&format!("{query} has:media -is:retweet"));
+    let max_results = limit.clamp(10, 100);
+    let pages = limit.div_ceil(100).max(1);
+    let mut out = Vec::new();
+    let mut next_token: Option<String> = None;
+
+    for page in 0..pages {
+        let pagination = match &next_token {
+            Some(t) => format!("&next_token={t}"),
+            None => String::new(),
+        };
+        let path = format!(
+            "tweets/search/recent?query={encoded}&max_results={max_results}&{MEDIA_FIELDS}{pagination}"
+        );
+        let raw = client.bearer_get(&path, token).await?;
+
+        let media_by_key: HashMap<&str, &serde_json::Value> = raw
+            .includes
+            .as_ref()
+            .and_then(|i| i.media.as_ref())
+            .into_iter()
+            .flatten()
+            .filter_map(|m| Some((m.get("media_key")?.as_str()?, m)))
+            .collect();
+        let keys_by_tweet: HashMap<&str, Vec<&str>> = raw
+            .data
+            .as_ref()
+            .and_then(|d| d.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|t| {
+                let keys = t
+                    .get("attachments")?
+                    .get("media_keys")?
+                    .as_array()?
+                    .iter()
+                    .filter_map(|k| k.as_str())
+                    .collect();
+                Some((t.get("id")?.as_str()?, keys))
+            })
+            .collect();
+
+        for tweet in parse_tweets(&raw) {
+            let media: Vec<serde_json::Value> = keys_by_tweet
+                .get(tweet.id.as_str())
+                .into_iter()
+                .flatten()
+                .filter_map(|k| media_by_key.get(k).map(|m| (*m).clone()))
+                .collect();
+            if !media.is_empty() && out.len() < limit {
+                out.push((tweet, media));
+            }
+        }
+
+        next_token = raw.meta.and_then(|m| m.next_token);
+        if next_token.is_none() || out.len() >= limit {
+            break;
+        }
+        if page + 1 < pages {
+            crate::client::rate_delay().await;
+        }
+    }
+
+    Ok(out)
 }
 
 /// Get a single tweet by ID.
@@ -272,27 +394,279 @@ pub async fn get_tweet(client: &XClient, token: &str, tweet_id: &str) -> Result<
     let path = format!("tweets/{tweet_id}?{FIELDS}");
     let raw = client.bearer_get(&path, token).await?;
     let tweets = parse_tweets(&raw);
+    if tweets.is_empty() {
+        if let Some(unavailable) = availability::classify_response(&raw) {
+            return Err(unavailable.into());
+        }
+    }
     Ok(tweets.into_iter().next())
 }
 
-/// Fetch a full thread by conversation ID.
+/// The user object from a `users/by/username` lookup, or a classified
+/// protected/suspended/not-found error.
+fn user_from_lookup(raw: &RawResponse, username: &str) -> Result<serde_json::Value> {
+    if let Some(data) = &raw.data {
+        return Ok(data.clone());
+    }
+    let unavailable = availability::classify_response(raw).unwrap_or_else(|| {
+        ResourceUnavailable::not_found("user", username, format!("User @{username} not found"))
+    });
+    Err(unavailable.into())
+}
+
+/// Fetch one page of quote-tweets for a tweet. Returns the tweets plus the
+/// pagination token for the next page, if any.
+pub async fn get_quote_tweets(
+    client: &XClient,
+    token: &str,
+    tweet_id: &str,
+    limit: usize,
+    cursor: Option<&str>,
+) -> Result<(Vec<Tweet>, Option<String>)> {
+    let max_results = limit.clamp(10, 100);
+    let pagination = match cursor {
+        Some(c) if !c.trim().is_empty() => format!("&pagination_token={}", c.trim()),
+        _ => String::new(),
+    };
+    let path =
+        format!("tweets/{tweet_id}/quote_tweets?max_results={max_results}&{FIELDS}{pagination}");
+    let raw = client.bearer_get(&path, token).await?;
+    if raw.data.is_none() {
+        if let Some(unavailable) = availability::classify_response(&raw) {
+            return Err(unavailable.into());
+        }
+    }
+    let tweets = parse_tweets(&raw).into_iter().take(limit).collect();
+    let next_token = raw.meta.and_then(|m| m.next_token);
+    Ok((tweets, next_token))
+}
+
+/// Fetch up to `limit` accounts that retweeted `tweet_id`, following
+/// pagination. A protected tweet fails with a classified error explaining
+/// that its retweeters are only visible to approved followers.
+pub async fn get_retweeters(
+    client: &XClient,
+    token: &str,
+    tweet_id: &str,
+    limit: usize,
+) -> Result<Vec<UserInfo>> {
+    let per_page = limit.clamp(1, 100);
+    let max_pages = limit.div_ceil(per_page).clamp(1, 10);
+    let mut users = Vec::new();
+    let mut next_token: Option<String> = None;
+
+    for page in 0..max_pages {
+        let pagination = match &next_token {
+            Some(t) => format!("&pagination_token={t}"),
+            None => String::new(),
+        };
+        let path = format!(
+            "tweets/{tweet_id}/retweeted_by?max_results={per_page}&{USER_INFO_FIELDS}{pagination}"
+        );
+        let raw = client
+            .bearer_get(&path, token)
+            .await
+            .map_err(|e| retweeters_error(e, tweet_id))?;
+        if raw.data.is_none() && page == 0 {
+            if let Some(unavailable) = availability::classify_response(&raw) {
+                return Err(retweeters_error(unavailable.into(), tweet_id));
+            }
+        }
+
+        if let Some(data) = raw.data.as_ref().and_then(|d| d.as_array()) {
+            users.extend(data.iter().map(parse_user_info));
+        }
+        next_token = raw.meta.and_then(|m| m.next_token);
+        if next_token.is_none() || users.len() >= limit {
+            break;
+        }
+        crate::client::rate_delay().await;
+    }
+
+    users.truncate(limit);
+    Ok(users)
+}
+
+fn retweeters_error(err: anyhow::Error, tweet_id: &str) -> anyhow::Error {
+    match err.downcast::<ResourceUnavailable>() {
+        Ok(mut unavailable) if unavailable.kind == availability::UnavailableKind::UserProtected => {
+            unavailable.resource_type = Some("tweet".to_string());
+            unavailable.resource_id = Some(tweet_id.to_string());
+            unavailable.detail = format!(
+                "Tweet {tweet_id} is from a protected account; its retweeters are only visible to approved followers"
+            );
+            unavailable.into()
+        }
+        Ok(unavailable) => unavailable.into(),
+        Err(err) => err,
+    }
+}
+
+/// Fetch up to `limit` of the newest tweets on a List's timeline. A private
+/// List the caller cannot see fails with a classified error.
+pub async fn get_list_tweets(
+    client: &XClient,
+    token: &str,
+    list_id: &str,
+    limit: usize,
+) -> Result<Vec<Tweet>> {
+    let per_page = limit.clamp(1, 100);
+    let max_pages = limit.div_ceil(per_page).clamp(1, 8);
+    let mut tweets = Vec::new();
+    let mut next_token: Option<String> = None;
+
+    for page in 0..max_pages {
+        let pagination = match &next_token {
+            Some(t) => format!("&pagination_token={t}"),
+            None => String::new(),
+        };
+        let path = format!("lists/{list_id}/tweets?max_results={per_page}&{FIELDS}{pagination}");
+        let raw = client
+            .bearer_get(&path, token)
+            .await
+            .map_err(|e| list_error(e, list_id))?;
+        if raw.data.is_none() && page == 0 {
+            if let Some(unavailable) = availability::classify_response(&raw) {
+                return Err(list_error(unavailable.into(), list_id));
+            }
+        }
+
+        tweets.extend(parse_tweets(&raw));
+        next_token = raw.meta.and_then(|m| m.next_token);
+        if next_token.is_none() || tweets.len() >= limit {
+            break;
+        }
+        crate::client::rate_delay().await;
+    }
+
+    tweets.truncate(limit);
+    Ok(tweets)
+}
+
+fn list_error(err: anyhow::Error, list_id: &str) -> anyhow::Error {
+    let private = err.to_string().starts_with("X API 403")
+        || err
+            .downcast_ref::<ResourceUnavailable>()
+            .is_some_and(|u| u.kind == availability::UnavailableKind::UserProtected);
+    if !private {
+        return err;
+    }
+    ResourceUnavailable {
+        kind: availability::UnavailableKind::UserProtected,
+        resource_type: Some("list".to_string()),
+        resource_id: Some(list_id.to_string()),
+        detail: format!("List {list_id} is private or not visible to this account"),
+    }
+    .into()
+}
+
+/// Fetch a full thread from any tweet in it.
 pub async fn get_thread(
     client: &XClient,
     token: &str,
     conversation_id: &str,
     pages: u32,
 ) -> Result<Vec<Tweet>> {
+    Ok(get_thread_page(client, token, conversation_id, pages, None)
+        .await?
+        .tweets)
+}
+
+/// Fetch up to `pages` pages of the conversation containing `tweet_id`,
+/// resuming from `cursor`. `tweet_id` may be any tweet in the thread; a
+/// deleted or protected one fails before any search is billed. The root
+/// tweet is prepended on the first page and its reply count is used as an
+/// estimate of the thread size.
+pub async fn get_thread_page(
+    client: &XClient,
+    token: &str,
+    tweet_id: &str,
+    pages: u32,
+    cursor: Option<&str>,
+) -> Result<ThreadPage> {
+    let anchor = get_tweet(client, token, tweet_id).await?.ok_or_else(|| {
+        ResourceUnavailable::not_found("tweet", tweet_id, format!("Tweet {tweet_id} not found"))
+    })?;
+    let conversation_id = if anchor.conversation_id.is_empty() {
+        anchor.id.clone()
+    } else {
+        anchor.conversation_id.clone()
+    };
+    let root = if conversation_id == anchor.id {
+        Some(anchor)
+    } else {
+        get_tweet(client, token, &conversation_id)
+            .await
+            .ok()
+            .flatten()
+    };
+
     let query = format!("conversation_id:{conversation_id}");
-    let mut tweets = search(client, token, &query, pages, "recency", None, None, false).await?;
+    let (tweets, next_cursor, fetched_pages) = search_paged(
+        client, token, &query, pages, "recency", None, None, false, cursor,
+    )
+    .await?;
 
-    // Try to fetch root tweet
-    if let Ok(Some(root)) = get_tweet(client, token, conversation_id).await {
-        if !tweets.iter().any(|t| t.id == root.id) {
+    Ok(thread_page(
+        root,
+        tweets,
+        fetched_pages,
+        next_cursor,
+        cursor.is_none(),
+    ))
+}
+
+/// Assemble one fetch of a thread. The root is prepended on the first page
+/// when search did not return it, and the thread is complete once search
+/// has no further cursor.
+fn thread_page(
+    root: Option<Tweet>,
+    mut tweets: Vec<Tweet>,
+    fetched_pages: u32,
+    next_cursor: Option<String>,
+    first_page: bool,
+) -> ThreadPage {
+    let mut estimated_total = None;
+    if let Some(root) = root {
+        estimated_total = Some(root.metrics.replies + 1);
+        if first_page && !tweets.iter().any(|t| t.id == root.id) {
             tweets.insert(0, root);
         }
     }
 
-    Ok(tweets)
+    ThreadPage {
+        tweets,
+        fetched_pages,
+        complete: next_cursor.is_none(),
+        next_cursor,
+        estimated_total,
+    }
+}
+
+/// Fetch the direct replies to `tweet_id` (not replies to replies), up to
+/// `limit`, most engaged first.
+pub async fn get_replies(
+    client: &XClient,
+    token: &str,
+    tweet_id: &str,
+    limit: usize,
+) -> Result<Vec<Tweet>> {
+    let query = format!("in_reply_to_tweet_id:{tweet_id}");
+    let pages = (limit as u32).div_ceil(100).max(1);
+    let tweets = search(client, token, &query, pages, "recency", None, None, false).await?;
+    Ok(direct_replies(tweets, tweet_id, limit))
+}
+
+/// The search results that reply to `tweet_id` itself, deduplicated, most
+/// engaged first and capped at `limit`.
+fn direct_replies(tweets: Vec<Tweet>, tweet_id: &str, limit: usize) -> Vec<Tweet> {
+    let mut replies: Vec<Tweet> = dedupe(tweets)
+        .into_iter()
+        .filter(|t| t.in_reply_to_id.as_deref() == Some(tweet_id))
+        .collect();
+    sort_by(&mut replies, "engagement");
+    replies.truncate(limit);
+    replies
 }
 
 /// Get user profile + recent tweets.
@@ -307,21 +681,289 @@ pub async fn get_profile(
         format!("users/by/username/{username}?user.fields=public_metrics,description,created_at");
     let raw = client.bearer_get(&path, token).await?;
 
-    let user = match &raw.data {
-        Some(data) => data.clone(),
-        None => bail!("User @{username} not found"),
-    };
+    let user = user_from_lookup(&raw, username)?;
 
     crate::client::rate_delay().await;
 
     let reply_filter = if include_replies { "" } else { " -is:reply" };
     let query = format!("from:{username} -is:retweet{reply_filter}");
-    let tweets = search(client, token, &query, 1, "recency", None, None, false).await?;
+    // Recent search returns at most 100 tweets per page.
+    let pages = count.div_ceil(100).max(1);
+    let tweets = search(client, token, &query, pages, "recency", None, None, false).await?;
 
     let tweets = tweets.into_iter().take(count as usize).collect();
     Ok((user, tweets))
 }
 
+/// Fetch a user and their pinned tweet, if they have one.
+pub async fn get_pinned_tweet(
+    client: &XClient,
+    token: &str,
+    username: &str,
+) -> Result<(serde_json::Value, Option<Tweet>)> {
+    let path = format!(
+        "users/by/username/{username}?user.fields=pinned_tweet_id,public_metrics,description"
+    );
+    let raw = client.bearer_get(&path, token).await?;
+
+    let user = user_from_lookup(&raw, username)?;
+
+    let Some(pinned_id) = user.get("pinned_tweet_id").and_then(|v| v.as_str()) else {
+        return Ok((user, None));
+    };
+
+    crate::client::rate_delay().await;
+    let tweet = get_tweet(client, token, pinned_id).await?;
+    Ok((user, tweet))
+}
+
+const SPACE_FIELDS: &str = "space.fields=title,state,host_ids,participant_count,scheduled_start,started_at,ended_at&expansions=host_ids&user.fields=username";
+
+/// Search live and scheduled Spaces whose title matches `query`.
+pub async fn search_spaces(
+    client: &XClient,
+    token: &str,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<Space>> {
+    let encoded = urlencoding::encode(query);
+    let max_results = limit.clamp(1, 100);
+    let path =
+        format!("spaces/search?query={encoded}&state=all&max_results={max_results}&{SPACE_FIELDS}");
+    let raw = client.bearer_get(&path, token).await?;
+
+    let hosts: HashMap<String, String> = raw
+        .includes
+        .as_ref()
+        .and_then(|i| i.users.as_ref())
+        .map(|users| {
+            users
+                .iter()
+                .filter_map(|u| Some((u.id.clone(), u.username.clone()?)))
+                .collect()
+        })
+        .unwrap_or_default();
+    let spaces = raw
+        .data
+        .as_ref()
+        .and_then(|d| d.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let text =
+                        |key: &str| item.get(key).and_then(|v| v.as_str()).map(str::to_string);
+                    let id = text("id")?;
+                    let host = item
+                        .get("host_ids")
+                        .and_then(|v| v.as_array())
+                        .and_then(|ids| ids.first())
+                        .and_then(|v| v.as_str())
+                        .and_then(|host_id| hosts.get(host_id).cloned());
+                    Some(Space {
+                        title: text("title").unwrap_or_default(),
+                        state: text("state").unwrap_or_else(|| "unknown".to_string()),
+                        host,
+                        participant_count: item
+                            .get("participant_count")
+                            .and_then(|v| v.as_u64())
+                            .unwrap_or(0),
+                        scheduled_start: text("scheduled_start"),
+                        started_at: text("started_at"),
+                        ended_at: text("ended_at"),
+                        space_url: format!("https://x.com/i/spaces/{id}"),
+                        id,
+                    })
+                })
+                .take(limit)
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(spaces)
+}
+
+const POLL_FIELDS: &str = "tweet.fields=created_at,public_metrics,author_id,conversation_id,entities,referenced_tweets,attachments&expansions=author_id,attachments.poll_ids&user.fields=username,name,public_metrics&poll.fields=duration_minutes,end_datetime,options,voting_status";
+
+/// Fetch a tweet together with its poll, if it has one.
+pub async fn get_poll(
+    client: &XClient,
+    token: &str,
+    tweet_id: &str,
+) -> Result<(Tweet, Option<Poll>)> {
+    let path = format!("tweets/{tweet_id}?{POLL_FIELDS}");
+    let raw = client.bearer_get(&path, token).await?;
+    let Some(tweet) = parse_tweets(&raw).into_iter().next() else {
+        let unavailable = availability::classify_response(&raw).unwrap_or_else(|| {
+            ResourceUnavailable::not_found("tweet", tweet_id, format!("Tweet {tweet_id} not found"))
+        });
+        return Err(unavailable.into());
+    };
+    let poll = raw
+        .includes
+        .as_ref()
+        .and_then(|i| i.polls.as_ref())
+        .and_then(|polls| polls.first())
+        .map(parse_poll);
+    Ok((tweet, poll))
+}
+
+fn parse_poll(poll: &serde_json::Value) -> Poll {
+    let mut options: Vec<PollOption> = poll
+        .get("options")
+        .and_then(|v| v.as_array())
+        .map(|opts| {
+            opts.iter()
+                .map(|o| PollOption {
+                    position: o.get("position").and_then(|v| v.as_u64()).unwrap_or(0),
+                    label: o
+                        .get("label")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    votes: o.get("votes").and_then(|v| v.as_u64()).unwrap_or(0),
+                    percentage: 0.0,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    options.sort_by_key(|o| o.position);
+    let total_votes: u64 = options.iter().map(|o| o.votes).sum();
+    for option in &mut options {
+        if total_votes > 0 {
+            option.percentage = (option.votes as f64 / total_votes as f64 * 1000.0).round() / 10.0;
+        }
+    }
+    let voting_status = poll
+        .get("voting_status")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    Poll {
+        id: poll
+            .get("id")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        open: voting_status == "open",
+        voting_status,
+        end_datetime: poll
+            .get("end_datetime")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        duration_minutes: poll.get("duration_minutes").and_then(|v| v.as_u64()),
+        total_votes,
+        options,
+    }
+}
+
+const USER_INFO_FIELDS: &str = "user.fields=created_at,description,location,pinned_tweet_id,profile_image_url,protected,public_metrics,url,verified,verified_type";
+
+/// Fetch a user's profile metadata (bio, counts, verification) without
+/// their tweets.
+pub async fn get_user_info(client: &XClient, token: &str, username: &str) -> Result<UserInfo> {
+    let path = format!("users/by/username/{username}?{USER_INFO_FIELDS}");
+    let raw = client.bearer_get(&path, token).await?;
+    let user = user_from_lookup(&raw, username)?;
+    Ok(parse_user_info(&user))
+}
+
+fn parse_user_info(user: &serde_json::Value) -> UserInfo {
+    let text = |key: &str| user.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let metric = |key: &str| {
+        user.get("public_metrics")
+            .and_then(|m| m.get(key))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0)
+    };
+    let username = text("username").unwrap_or_default();
+    let verified_type = text("verified_type").filter(|t| t != "none");
+
+    UserInfo {
+        id: text("id").unwrap_or_default(),
+        name: text("name").unwrap_or_default(),
+        description: text("description").unwrap_or_default(),
+        location: text("location").filter(|l| !l.is_empty()),
+        url: text("url").filter(|u| !u.is_empty()),
+        created_at: text("created_at"),
+        verified: user
+            .get("verified")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+            || verified_type.is_some(),
+        verified_type,
+        protected: user
+            .get("protected")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        followers: metric("followers_count"),
+        following: metric("following_count"),
+        tweet_count: metric("tweet_count"),
+        listed_count: metric("listed_count"),
+        profile_image_url: text("profile_image_url"),
+        pinned_tweet_id: text("pinned_tweet_id"),
+        profile_url: format!("https://x.com/{username}"),
+        username,
+    }
+}
+
+/// Fetch up to `fetch_count` of the authorized user's bookmarks, newest
+/// first, following pagination for at most eight pages.
+pub async fn get_bookmarks(
+    client: &XClient,
+    access_token: &str,
+    user_id: &str,
+    fetch_count: usize,
+) -> Result<Vec<Tweet>> {
+    let mut all = Vec::new();
+    let mut next_token: Option<String> = None;
+    let per_page = fetch_count.clamp(1, 100);
+    let max_pages = fetch_count.div_ceil(per_page).clamp(1, 8);
+
+    for page in 0..max_pages {
+        let pagination = match &next_token {
+            Some(t) => format!("&pagination_token={t}"),
+            None => String::new(),
+        };
+        let path = format!("users/{user_id}/bookmarks?max_results={per_page}&{FIELDS}{pagination}");
+
+        let raw = client.oauth_get(&path, access_token).await?;
+        all.extend(parse_tweets(&raw));
+
+        if all.len() >= fetch_count {
+            break;
+        }
+        next_token = raw.meta.and_then(|m| m.next_token);
+        if next_token.is_none() {
+            break;
+        }
+        if page < max_pages - 1 {
+            crate::client::rate_delay().await;
+        }
+    }
+
+    Ok(all)
+}
+
+/// Keep tweets created within the `since` window (`1h`, `1d`, `7d` or an
+/// ISO timestamp). Tweets with unparseable timestamps are kept.
+pub fn filter_since(tweets: Vec<Tweet>, since: &str) -> Vec<Tweet> {
+    let Some(since_ms) = parse_since(since)
+        .and_then(|ts| chrono::DateTime::parse_from_rfc3339(&ts).ok())
+        .map(|dt| dt.timestamp_millis())
+    else {
+        return tweets;
+    };
+    tweets
+        .into_iter()
+        .filter(|t| {
+            chrono::DateTime::parse_from_rfc3339(&t.created_at)
+                .map(|d| d.timestamp_millis() >= since_ms)
+                .unwrap_or(true)
+        })
+        .collect()
+}
+
 /// Sort tweets by engagement metric.
 pub fn sort_by(tweets: &mut [Tweet], metric: &str) {
     tweets.sort_by(|a, b| {
@@ -331,6 +973,9 @@ pub fn sort_by(tweets: &mut [Tweet], metric: &str) {
                 "impressions" => t.metrics.impressions,
                 "retweets" => t.metrics.retweets,
                 "replies" => t.metrics.replies,
+                "engagement" => {
+                    t.metrics.likes + t.metrics.retweets + t.metrics.replies + t.metrics.quotes
+                }
                 _ => t.metrics.likes,
             }
         };
@@ -376,3 +1021,103 @@ mod urlencoding {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{direct_replies, parse_user_info, thread_page};
+    use crate::models::{Tweet, TweetMetrics};
+
+    fn tweet(id: &str, replies: u64) -> Tweet {
+        Tweet {
+            id: id.to_string(),
+            metrics: TweetMetrics {
+                replies,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn thread_page_reports_partial_and_complete_fetches() {
+        let partial = thread_page(
+            Some(tweet("1", 4)),
+            vec![tweet("3", 0), tweet("2", 0)],
+            2,
+            Some("cursor-2".to_string()),
+            true,
+        );
+        let ids: Vec<_> = partial.tweets.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, ["1", "3", "2"]);
+        assert!(!partial.complete);
+        assert_eq!(partial.fetched_pages, 2);
+        assert_eq!(partial.next_cursor.as_deref(), Some("cursor-2"));
+        assert_eq!(partial.estimated_total, Some(5));
+
+        // Continuation pages never repeat the root; search already found it
+        // here, so it is not added twice either.
+        let rest = thread_page(Some(tweet("1", 4)), vec![tweet("4", 0)], 1, None, false);
+        assert_eq!(rest.tweets.len(), 1);
+        assert!(rest.complete);
+        assert_eq!(rest.next_cursor, None);
+        let found = thread_page(Some(tweet("1", 0)), vec![tweet("1", 0)], 1, None, true);
+        assert_eq!(found.tweets.len(), 1);
+
+        let orphan = thread_page(None, vec![tweet("5", 0)], 1, None, true);
+        assert_eq!(orphan.estimated_total, None);
+    }
+
+    #[test]
+    fn user_info_maps_lookup_fields_and_drops_empty_values() {
+        let info = parse_user_info(&serde_json::json!({
+            "id": "12",
+            "username": "jack",
+            "name": "jack",
+            "location": "",
+            "url": "https://example.com",
+            "verified": false,
+            "verified_type": "blue",
+            "public_metrics": { "followers_count": 10, "tweet_count": 3 }
+        }));
+        assert_eq!(info.id, "12");
+        assert_eq!(info.location, None);
+        assert_eq!(info.url.as_deref(), Some("https://example.com"));
+        assert!(info.verified, "a verified_type implies verified");
+        assert_eq!(info.verified_type.as_deref(), Some("blue"));
+        assert_eq!(info.followers, 10);
+        assert_eq!(info.following, 0);
+        assert_eq!(info.tweet_count, 3);
+        assert_eq!(info.profile_url, "https://x.com/jack");
+
+        let plain =
+            parse_user_info(&serde_json::json!({ "username": "a", "verified_type": "none" }));
+        assert!(!plain.verified);
+        assert_eq!(plain.verified_type, None);
+    }
+
+    #[test]
+    fn direct_replies_skip_nested_replies_and_rank_by_engagement() {
+        let reply = |id: &str, parent: Option<&str>, likes: u64| Tweet {
+            in_reply_to_id: parent.map(str::to_string),
+            metrics: TweetMetrics {
+                likes,
+                ..Default::default()
+            },
+            ..tweet(id, 0)
+        };
+        let tweets = vec![
+            reply("2", Some("1"), 5),
+            reply("3", Some("2"), 50),
+            reply("4", Some("1"), 9),
+            reply("4", Some("1"), 9),
+            reply("5", None, 100),
+            reply("6", Some("1"), 1),
+        ];
+        let ids = |replies: &[Tweet]| replies.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            ids(&direct_replies(tweets.clone(), "1", 10)),
+            ["4", "2", "6"]
+        );
+        assert_eq!(ids(&direct_replies(tweets, "1", 2)), ["4", "2"]);
+    }
+}
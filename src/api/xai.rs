@@ -117,14 +117,14 @@ pub async fn x_search(
 
     let url = format!("{API_BASE}/responses");
 
-    let res = http
-        .post(&url)
-        .header("Authorization", format!("Bearer {api_key}"))
-        .header("Content-Type", "application/json")
-        .timeout(std::time::Duration::from_secs(timeout_secs))
-        .json(&body)
-        .send()
-        .await?;
+    let res = send_with_retry(
+        http.post(&url)
+            .header("Authorization", format!("Bearer {api_key}"))
+            .header("Content-Type", "application/json")
+            .timeout(std::time::Duration::from_secs(timeout_secs))
+            .json(&body),
+    )
+    .await?;
 
     let status = res.status().as_u16();
     if status == 401 {
@@ -226,16 +226,19 @@ pub async fn web_search_article(
 
     let api_url = format!("{API_BASE}/responses");
 
-    let res = http
-        .post(&api_url)
-        .header("Authorization", format!("Bearer {api_key}"))
-        .header("Content-Type", "application/json")
-        .timeout(std::time::Duration::from_secs(timeout_secs))
-        .json(&body)
-        .send()
-        .await
-        .map_err(|err| {
-            if err.is_timeout() {
+    let res = send_with_retry(
+        http.post(&api_url)
+            .header("Authorization", format!("Bearer {api_key}"))
+            .header("Content-Type", "application/json")
+            .timeout(std::time::Duration::from_secs(timeout_secs))
+            .json(&body),
+    )
+    .await
+    .map_err(|err| {
+            if err
+                .downcast_ref::<reqwest::Error>()
+                .is_some_and(reqwest::Error::is_timeout)
+            {
                 anyhow::anyhow!(
                     "Article fetch timed out after {timeout_secs}s for {url}. Set XINT_ARTICLE_TIMEOUT_SEC (5-120) to tune this."
                 )
@@ -301,6 +304,7 @@ pub async fn collections_list(http: &reqwest::Client, mgmt_key: &str) -> Result<
         .send()
         .await?;
 
+    ensure_collections_enabled(&res, "GET /collections", true)?;
     handle_xai_response(res, "GET /collections").await
 }
 
@@ -438,13 +442,178 @@ pub async fn documents_search(
         .send()
         .await?;
 
+    ensure_collections_enabled(&res, "POST /documents/search", false)?;
     handle_xai_response(res, "POST /documents/search").await
 }
 
+/// One collection from [`collections_list`], with the fields tools report.
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionSummary {
+    pub id: String,
+    pub name: String,
+    pub documents: Option<u64>,
+    pub size_bytes: Option<u64>,
+    pub created_at: Option<String>,
+}
+
+/// A chunk matched by [`documents_search`] and where it came from.
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentMatch {
+    pub text: String,
+    pub score: Option<f64>,
+    pub citation: DocumentCitation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DocumentCitation {
+    pub collection_id: Option<String>,
+    pub document_id: Option<String>,
+    pub name: Option<String>,
+    pub chunk_id: Option<String>,
+}
+
+fn first_str(item: &serde_json::Value, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|k| match item.get(*k)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+fn first_u64(item: &serde_json::Value, keys: &[&str]) -> Option<u64> {
+    keys.iter().find_map(|k| item.get(*k)?.as_u64())
+}
+
+fn first_array<'a>(body: &'a serde_json::Value, keys: &[&str]) -> &'a [serde_json::Value] {
+    keys.iter()
+        .find_map(|k| body.get(*k)?.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Normalize a `GET /collections` body. The Management API has used both
+/// `collections` and `data` envelopes and prefixed and plain field names.
+pub fn parse_collections(body: &serde_json::Value) -> Vec<CollectionSummary> {
+    first_array(body, &["collections", "data"])
+        .iter()
+        .filter_map(|item| {
+            Some(CollectionSummary {
+                id: first_str(item, &["collection_id", "id"])?,
+                name: first_str(item, &["collection_name", "name"]).unwrap_or_default(),
+                documents: first_u64(item, &["documents_count", "document_count"]),
+                size_bytes: first_u64(item, &["total_size_bytes", "size_bytes", "bytes"]),
+                created_at: first_str(item, &["created_at"]),
+            })
+        })
+        .collect()
+}
+
+/// Normalize a `POST /documents/search` body into matched chunks, keeping at
+/// most `limit`.
+pub fn parse_document_matches(
+    body: &serde_json::Value,
+    collection_id: &str,
+    limit: usize,
+) -> Vec<DocumentMatch> {
+    first_array(body, &["matches", "results", "data"])
+        .iter()
+        .filter_map(|item| {
+            let text = first_str(item, &["chunk_content", "content", "text"])?;
+            let collection_id = item
+                .get("collection_ids")
+                .and_then(|v| v.as_array())
+                .and_then(|ids| ids.first())
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+                .or_else(|| first_str(item, &["collection_id"]))
+                .or_else(|| Some(collection_id.to_string()));
+            Some(DocumentMatch {
+                text,
+                score: item.get("score").and_then(|v| v.as_f64()),
+                citation: DocumentCitation {
+                    collection_id,
+                    document_id: first_str(item, &["file_id", "document_id"]),
+                    name: first_str(item, &["file_name", "name", "title"]),
+                    chunk_id: first_str(item, &["chunk_id"]),
+                },
+            })
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Collections is gated per account: xAI answers 403 (and 404 for the
+/// management listing) when it is not enabled, which otherwise surfaces as
+/// an opaque API error.
+fn ensure_collections_enabled(
+    res: &reqwest::Response,
+    context: &str,
+    not_found_means_disabled: bool,
+) -> Result<()> {
+    let status = res.status().as_u16();
+    if status == 403 || (status == 404 && not_found_means_disabled) {
+        bail!(
+            "xAI Collections is not enabled for this account ({status} on {context}). Enable Collections in the xAI console or use a key with Collections access."
+        );
+    }
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
 
+const OVERLOAD_MAX_RETRIES: u32 = 3;
+const OVERLOAD_BASE_DELAY_MS: u64 = 500;
+const OVERLOAD_MAX_DELAY_MS: u64 = 10_000;
+
+fn is_overload_status(status: u16) -> bool {
+    matches!(status, 429 | 503 | 529)
+}
+
+/// Send a Grok-backed request, retrying with exponential backoff while xAI
+/// reports rate limiting or overload (429/503/529). Every AI tool goes
+/// through here so the retry policy stays consistent.
+pub async fn send_with_retry(request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+    send_with_retry_using(request, OVERLOAD_MAX_RETRIES, OVERLOAD_BASE_DELAY_MS).await
+}
+
+async fn send_with_retry_using(
+    request: reqwest::RequestBuilder,
+    max_retries: u32,
+    base_delay_ms: u64,
+) -> Result<reqwest::Response> {
+    let mut attempt = 0u32;
+    loop {
+        let Some(next) = request.try_clone() else {
+            return Ok(request.send().await?);
+        };
+        let res = next.send().await?;
+        let status = res.status().as_u16();
+        if !is_overload_status(status) {
+            return Ok(res);
+        }
+        if attempt >= max_retries {
+            bail!(
+                "xAI model temporarily overloaded ({status}); retried {attempt} times. Try again in a moment."
+            );
+        }
+
+        let retry_after_ms = res
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(|secs| secs * 1000);
+        let backoff_ms = base_delay_ms.saturating_mul(1 << attempt);
+        let delay_ms = retry_after_ms
+            .unwrap_or(backoff_ms)
+            .min(OVERLOAD_MAX_DELAY_MS);
+        tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+        attempt += 1;
+    }
+}
+
 async fn handle_xai_response(res: reqwest::Response, context: &str) -> Result<serde_json::Value> {
     let status = res.status().as_u16();
     if status == 401 {
@@ -486,3 +655,75 @@ fn mime_from_path(path: &std::path::Path) -> String {
     }
     .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve one canned status per connection, in order.
+    async fn spawn_status_sequence(statuses: Vec<u16>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        tokio::spawn(async move {
+            for status in statuses {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                let body = "{}";
+                let response = format!(
+                    "HTTP/1.1 {status} Test\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{addr}/v1/responses")
+    }
+
+    #[tokio::test]
+    async fn retries_overloaded_responses_until_success() {
+        let url = spawn_status_sequence(vec![529, 503, 200]).await;
+        let http = reqwest::Client::new();
+        let res = send_with_retry_using(http.post(&url).json(&serde_json::json!({})), 3, 1)
+            .await
+            .expect("eventual success");
+        assert_eq!(res.status().as_u16(), 200);
+    }
+
+    #[tokio::test]
+    async fn reports_retry_count_when_still_overloaded() {
+        let url = spawn_status_sequence(vec![529, 529, 529]).await;
+        let http = reqwest::Client::new();
+        let err = send_with_retry_using(http.post(&url).json(&serde_json::json!({})), 2, 1)
+            .await
+            .expect_err("overloaded error");
+        assert!(err.to_string().contains("retried 2 times"));
+    }
+
+    #[test]
+    fn normalizes_collections_and_search_matches() {
+        let list = serde_json::json!({ "collections": [
+            { "collection_id": "col_1", "collection_name": "notes", "documents_count": 3 },
+            { "name": "no id" }
+        ]});
+        let collections = parse_collections(&list);
+        assert_eq!(collections.len(), 1);
+        assert_eq!(collections[0].id, "col_1");
+        assert_eq!(collections[0].documents, Some(3));
+
+        let search = serde_json::json!({ "matches": [
+            { "chunk_content": "first", "score": 0.9, "file_id": "file_a", "chunk_id": "c1" },
+            { "chunk_content": "second", "collection_ids": ["col_2"] },
+            { "chunk_content": "third" }
+        ]});
+        let matches = parse_document_matches(&search, "col_1", 2);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].citation.document_id.as_deref(), Some("file_a"));
+        assert_eq!(matches[0].citation.collection_id.as_deref(), Some("col_1"));
+        assert_eq!(matches[1].citation.collection_id.as_deref(), Some("col_2"));
+    }
+}
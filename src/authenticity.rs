@@ -0,0 +1,160 @@
+use crate::models::UserInfo;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Heuristic authenticity signals for one account.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthenticitySignals {
+    pub username: String,
+    pub account_age_days: Option<i64>,
+    pub verified: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_type: Option<String>,
+    pub follower_following_ratio: Option<f64>,
+    pub default_avatar: bool,
+    pub empty_bio: bool,
+    pub tweets_per_day: Option<f64>,
+    /// 0.0 (looks organic) to 1.0 (looks automated).
+    pub bot_score: f64,
+    /// `low`, `medium` or `high`.
+    pub bot_likelihood: &'static str,
+    /// Human-readable reasons that raised or lowered the score.
+    pub reasons: Vec<String>,
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+/// Score an account's profile metadata as of `now`. Each red flag adds a
+/// fixed weight; verification subtracts one. The weights are deliberately
+/// coarse: the score is a triage hint, not a verdict.
+pub fn assess(info: &UserInfo, now: DateTime<Utc>) -> AuthenticitySignals {
+    let account_age_days = info
+        .created_at
+        .as_deref()
+        .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+        .map(|created| (now - created.with_timezone(&Utc)).num_days().max(0));
+    let follower_following_ratio =
+        (info.following > 0).then(|| round2(info.followers as f64 / info.following as f64));
+    let default_avatar = info
+        .profile_image_url
+        .as_deref()
+        .is_none_or(|url| url.contains("default_profile_images"));
+    let empty_bio = info.description.trim().is_empty();
+    let tweets_per_day =
+        account_age_days.map(|days| round2(info.tweet_count as f64 / days.max(1) as f64));
+
+    let mut score: f64 = 0.0;
+    let mut reasons = Vec::new();
+    let mut flag = |weight: f64, reason: String| {
+        score += weight;
+        reasons.push(reason);
+    };
+
+    match account_age_days {
+        Some(days) if days < 30 => flag(0.25, format!("account is only {days} days old")),
+        Some(days) if days < 180 => flag(0.1, format!("account is {days} days old")),
+        _ => {}
+    }
+    if default_avatar {
+        flag(0.2, "uses the default avatar".to_string());
+    }
+    if empty_bio {
+        flag(0.1, "has an empty bio".to_string());
+    }
+    if info.following >= 100 && follower_following_ratio.is_some_and(|r| r < 0.1) {
+        flag(
+            0.2,
+            format!(
+                "follows {} accounts but has {} followers",
+                info.following, info.followers
+            ),
+        );
+    }
+    match tweets_per_day {
+        Some(rate) if rate > 50.0 => flag(0.25, format!("posts {rate} times a day")),
+        Some(rate) if rate > 20.0 => flag(0.1, format!("posts {rate} times a day")),
+        _ => {}
+    }
+    if info.verified {
+        flag(-0.2, "account is verified".to_string());
+    }
+
+    let bot_score = round2(score.clamp(0.0, 1.0));
+    let bot_likelihood = if bot_score >= 0.6 {
+        "high"
+    } else if bot_score >= 0.3 {
+        "medium"
+    } else {
+        "low"
+    };
+
+    AuthenticitySignals {
+        username: info.username.clone(),
+        account_age_days,
+        verified: info.verified,
+        verified_type: info.verified_type.clone(),
+        follower_following_ratio,
+        default_avatar,
+        empty_bio,
+        tweets_per_day,
+        bot_score,
+        bot_likelihood,
+        reasons,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .expect("timestamp")
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn established_verified_account_scores_low() {
+        let info = UserInfo {
+            username: "jack".to_string(),
+            description: "just setting up my twttr".to_string(),
+            created_at: Some("2006-03-21T20:50:14.000Z".to_string()),
+            verified: true,
+            followers: 6_000_000,
+            following: 4_000,
+            tweet_count: 30_000,
+            profile_image_url: Some("https://pbs.twimg.com/profile_images/1/a.jpg".to_string()),
+            ..Default::default()
+        };
+        let signals = assess(&info, now());
+        assert_eq!(signals.bot_score, 0.0);
+        assert_eq!(signals.bot_likelihood, "low");
+        assert_eq!(signals.follower_following_ratio, Some(1500.0));
+        assert!(!signals.default_avatar);
+    }
+
+    #[test]
+    fn new_faceless_high_volume_account_scores_high() {
+        let info = UserInfo {
+            username: "deal_bot_4821".to_string(),
+            created_at: Some("2025-12-22T00:00:00.000Z".to_string()),
+            followers: 3,
+            following: 900,
+            tweet_count: 1_200,
+            profile_image_url: Some(
+                "https://abs.twimg.com/sticky/default_profile_images/default_profile.png"
+                    .to_string(),
+            ),
+            ..Default::default()
+        };
+        let signals = assess(&info, now());
+        assert_eq!(signals.account_age_days, Some(10));
+        assert_eq!(signals.tweets_per_day, Some(120.0));
+        assert!(signals.default_avatar && signals.empty_bio);
+        assert_eq!(signals.bot_score, 1.0);
+        assert_eq!(signals.bot_likelihood, "high");
+        assert_eq!(signals.reasons.len(), 5);
+    }
+}
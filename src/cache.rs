@@ -19,7 +19,7 @@ fn now_ms() -> u64 {
         .as_millis() as u64
 }
 
-fn cache_key(query: &str, params: &str) -> String {
+pub fn cache_key(query: &str, params: &str) -> String {
     let mut hasher = Md5::new();
     hasher.update(format!("{query}|{params}"));
     let hash = hasher.finalize();
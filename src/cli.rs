@@ -11,12 +11,15 @@ pub struct Cli {
     pub command: Option<Commands>,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum, serde::Deserialize)]
 #[value(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum PolicyMode {
     ReadOnly,
     Engagement,
     Moderation,
+    /// Everything, including irreversible publishing actions.
+    Full,
 }
 
 #[derive(Subcommand)]
@@ -450,7 +453,7 @@ pub struct ArticleArgs {
     pub full: bool,
 
     /// Grok model (default: grok-4 for article fetching)
-    #[arg(long, default_value = "grok-4")]
+    #[arg(long, default_value = crate::commands::article::DEFAULT_MODEL)]
     pub model: String,
 
     /// Analyze article with Grok AI - ask a question about the content
@@ -831,4 +834,30 @@ pub struct McpArgs {
     /// Disable budget guard for MCP tool calls
     #[arg(long)]
     pub no_budget_guard: bool,
+
+    /// Load MCP server settings from a TOML file (env vars override file values)
+    #[arg(long)]
+    pub config: Option<std::path::PathBuf>,
+
+    /// Directory that file-writing MCP tools may write into (repeatable; writes are disabled when unset)
+    #[arg(long = "allow-write-dir")]
+    pub allow_write_dirs: Vec<std::path::PathBuf>,
+
+    /// Browser Origin allowed to call the SSE server, e.g. `https://app.example.com`
+    /// (repeatable; localhost origins are always allowed)
+    #[arg(long = "allow-origin")]
+    pub allow_origins: Vec<String>,
+
+    /// Maximum `limit`/`count` accepted by MCP tools
+    #[arg(long, default_value_t = crate::mcp::DEFAULT_MAX_TWEETS)]
+    pub max_tweets: u64,
+
+    /// Maximum number of MCP tool calls executed concurrently
+    #[arg(long, default_value_t = crate::mcp::DEFAULT_MAX_CONCURRENCY)]
+    pub max_concurrency: usize,
+
+    /// Raise the daily budget to this many USD for this server process only
+    /// (env: XINT_MCP_BUDGET_OVERRIDE_USD); nothing is persisted
+    #[arg(long)]
+    pub budget_override_usd: Option<f64>,
 }
@@ -2,12 +2,13 @@ use anyhow::{bail, Result};
 use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
 use std::time::Duration;
 
+use crate::api::availability;
 use crate::models::RawResponse;
 
 const BASE_URL: &str = "https://api.x.com/2";
 const RATE_DELAY_MS: u64 = 350;
 
-pub const FIELDS: &str = "tweet.fields=created_at,public_metrics,author_id,conversation_id,entities&expansions=author_id&user.fields=username,name,public_metrics";
+pub const FIELDS: &str = "tweet.fields=created_at,public_metrics,author_id,conversation_id,entities,referenced_tweets&expansions=author_id&user.fields=username,name,public_metrics";
 
 /// Shared HTTP client for X API calls.
 pub struct XClient {
@@ -259,6 +260,9 @@ async fn handle_response(res: reqwest::Response) -> Result<RawResponse> {
 
     if !status.is_success() {
         let text = res.text().await.unwrap_or_default();
+        if let Some(unavailable) = availability::classify_body(&text) {
+            return Err(unavailable.into());
+        }
         bail!(
             "X API {}: {}",
             status.as_u16(),
@@ -318,6 +322,9 @@ async fn handle_json_response(
 
     if !status.is_success() {
         let text = res.text().await.unwrap_or_default();
+        if let Some(unavailable) = availability::classify_body(&text) {
+            return Err(unavailable.into());
+        }
         bail!(
             "X API {}: {}",
             status.as_u16(),
@@ -1,6 +1,7 @@
 use anyhow::Result;
 
 use crate::api::grok;
+use crate::api::redirect;
 use crate::api::twitter;
 use crate::api::xai;
 use crate::cli::ArticleArgs;
@@ -8,6 +9,9 @@ use crate::client::XClient;
 use crate::config::Config;
 use crate::models::{Article, Tweet};
 
+/// Grok model used to read articles through web search.
+pub const DEFAULT_MODEL: &str = "grok-4";
+
 pub async fn run(args: &ArticleArgs, config: &Config) -> Result<()> {
     let xai_api_key = config.require_xai_key()?;
 
@@ -29,15 +33,17 @@ pub async fn run(args: &ArticleArgs, config: &Config) -> Result<()> {
         }
     }
 
-    let parsed = url::Url::parse(&url).map_err(|_| anyhow::anyhow!("Invalid URL: {url}"))?;
-    let domain = parsed.host_str().unwrap_or("").to_string();
-    let timeout_secs = resolve_article_timeout_secs();
+    // Unwrap t.co and other shorteners so extraction and citations use the
+    // real destination; keep the original URL if resolution fails.
+    if let Ok(resolved) = redirect::resolve_url(&url, redirect::DEFAULT_MAX_HOPS).await {
+        if resolved.hops > 0 {
+            println!("🔗 Resolved {} → {}", url, resolved.final_url);
+            url = resolved.final_url;
+        }
+    }
 
     let http = reqwest::Client::new();
-    let raw = xai::web_search_article(&http, xai_api_key, &url, &domain, &args.model, timeout_secs)
-        .await?;
-
-    let article = parse_article_json(&raw, &url, &domain, args.full);
+    let article = extract_article(&http, xai_api_key, &url, &args.model, args.full).await?;
 
     // If AI prompt provided, analyze the article
     if let Some(ai_prompt) = &args.ai {
@@ -66,7 +72,24 @@ pub async fn run(args: &ArticleArgs, config: &Config) -> Result<()> {
     Ok(())
 }
 
-fn is_x_tweet_like_url(value: &str) -> bool {
+/// Extract the article at `url` through xAI web search. Without `full`,
+/// content is truncated to about 5000 characters.
+pub async fn extract_article(
+    http: &reqwest::Client,
+    xai_api_key: &str,
+    url: &str,
+    model: &str,
+    full: bool,
+) -> Result<Article> {
+    let parsed = url::Url::parse(url).map_err(|_| anyhow::anyhow!("Invalid URL: {url}"))?;
+    let domain = parsed.host_str().unwrap_or("").to_string();
+    let timeout_secs = resolve_article_timeout_secs();
+
+    let raw = xai::web_search_article(http, xai_api_key, url, &domain, model, timeout_secs).await?;
+    Ok(parse_article_json(&raw, url, &domain, full))
+}
+
+pub fn is_x_tweet_like_url(value: &str) -> bool {
     extract_tweet_id(value).is_some()
 }
 
@@ -139,7 +162,7 @@ fn pick_article_url_from_tweet(tweet: &Tweet) -> Option<String> {
     candidates.into_iter().next()
 }
 
-async fn fetch_tweet_for_article(
+pub async fn fetch_tweet_for_article(
     client: &XClient,
     token: &str,
     tweet_url: &str,
@@ -270,6 +293,7 @@ mod tests {
             name: "Alice".to_string(),
             created_at: "2026-02-19T00:00:00Z".to_string(),
             conversation_id: "1900100012345678901".to_string(),
+            in_reply_to_id: None,
             metrics: TweetMetrics {
                 likes: 0,
                 retweets: 0,
@@ -36,39 +36,8 @@ pub async fn run(args: &BookmarksArgs, config: &Config, client: &XClient) -> Res
 
         eprintln!("Fetching bookmarks for @{}...", tokens.username);
 
-        let mut all = Vec::new();
-        let mut next_token: Option<String> = None;
-        let per_page = fetch_count.min(100);
-        let max_pages = fetch_count.div_ceil(per_page).min(8);
-
-        for page in 0..max_pages {
-            let pagination = match &next_token {
-                Some(t) => format!("&pagination_token={t}"),
-                None => String::new(),
-            };
-            let path = format!(
-                "users/{}/bookmarks?max_results={}&{}{}",
-                tokens.user_id,
-                per_page,
-                crate::client::FIELDS,
-                pagination
-            );
-
-            let raw = client.oauth_get(&path, &access_token).await?;
-            let tweets = twitter::parse_tweets(&raw);
-            all.extend(tweets);
-
-            if all.len() >= fetch_count {
-                break;
-            }
-            next_token = raw.meta.and_then(|m| m.next_token);
-            if next_token.is_none() {
-                break;
-            }
-            if page < max_pages - 1 {
-                crate::client::rate_delay().await;
-            }
-        }
+        let all =
+            twitter::get_bookmarks(client, &access_token, &tokens.user_id, fetch_count).await?;
 
         costs::track_cost(
             &config.costs_path(),
@@ -85,16 +54,7 @@ pub async fn run(args: &BookmarksArgs, config: &Config, client: &XClient) -> Res
     let mut filtered = all_tweets;
 
     if let Some(ref since) = args.since {
-        if let Some(ts) = twitter::parse_since(since) {
-            if let Ok(since_dt) = chrono::DateTime::parse_from_rfc3339(&ts) {
-                let since_ms = since_dt.timestamp_millis();
-                filtered.retain(|t| {
-                    chrono::DateTime::parse_from_rfc3339(&t.created_at)
-                        .map(|d| d.timestamp_millis() >= since_ms)
-                        .unwrap_or(true)
-                });
-            }
-        }
+        filtered = twitter::filter_since(filtered, since);
     }
 
     if let Some(ref q) = args.query {
@@ -97,6 +97,10 @@ pub fn manifest() -> Value {
         {
           "mode": "moderation",
           "description": "Safety actions (block/mute) via OAuth scopes."
+        },
+        {
+          "mode": "full",
+          "description": "Everything above plus irreversible publishing actions (package publish)."
         }
       ],
       "telemetry": {
@@ -45,8 +45,15 @@ pub fn run(args: &CostsArgs, config: &Config) -> Result<()> {
             }
         }
         "reset" => {
-            costs::reset_today(&config.costs_path());
-            println!("Today's cost data has been reset.");
+            let period = parts.get(1).map(|s| s.as_str()).unwrap_or("today");
+            if !matches!(period, "today" | "week" | "month" | "all") {
+                anyhow::bail!("Invalid reset period: {period} (use today, week, month or all)");
+            }
+            let cleared = costs::reset_period(&config.costs_path(), period, true);
+            println!(
+                "Cleared ${:.4} across {} calls ({period}).",
+                cleared.total_cost, cleared.calls
+            );
         }
         _ => {
             println!("Usage: xint costs [today|week|month|all|budget|reset]");
@@ -56,7 +63,7 @@ pub fn run(args: &CostsArgs, config: &Config) -> Result<()> {
             println!("  month          Show last 30 days");
             println!("  all            Show all-time costs");
             println!("  budget [amt]   View or set daily budget");
-            println!("  reset          Reset today's tracking");
+            println!("  reset [period] Clear tracked spend (today, week, month, all)");
         }
     }
 
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::auth::oauth;
 use crate::cli::DiffArgs;
@@ -100,15 +100,7 @@ pub async fn run(args: &DiffArgs, config: &Config, client: &XClient) -> Result<(
     let previous = load_latest_snapshot(&snapshots_dir, &username, snap_type);
 
     // Save current
-    let date = &current.timestamp[..10];
-    let snap_path = snapshots_dir.join(format!(
-        "{}-{}-{}.json",
-        username.to_lowercase(),
-        snap_type,
-        date
-    ));
-    let json = serde_json::to_string_pretty(&current)?;
-    fs::write(&snap_path, &json)?;
+    let snap_path = save_snapshot(&snapshots_dir, &current)?;
     eprintln!("Snapshot saved to {}", snap_path.display());
 
     // Compute and display diff
@@ -153,6 +145,27 @@ pub async fn run(args: &DiffArgs, config: &Config, client: &XClient) -> Result<(
     Ok(())
 }
 
+/// Snapshot entry for one user object from the followers/following API.
+pub fn user_snapshot(u: &serde_json::Value) -> UserSnapshot {
+    let field = |key: &str| {
+        u.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or("?")
+            .to_string()
+    };
+    UserSnapshot {
+        id: field("id"),
+        username: field("username"),
+        name: field("name"),
+        followers_count: u
+            .pointer("/public_metrics/followers_count")
+            .and_then(|v| v.as_u64()),
+        following_count: u
+            .pointer("/public_metrics/following_count")
+            .and_then(|v| v.as_u64()),
+    }
+}
+
 async fn fetch_user_list(
     client: &XClient,
     access_token: &str,
@@ -175,31 +188,7 @@ async fn fetch_user_list(
 
         if let Some(data) = &raw.data {
             if let Some(arr) = data.as_array() {
-                for u in arr {
-                    users.push(UserSnapshot {
-                        id: u
-                            .get("id")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("?")
-                            .to_string(),
-                        username: u
-                            .get("username")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("?")
-                            .to_string(),
-                        name: u
-                            .get("name")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("?")
-                            .to_string(),
-                        followers_count: u
-                            .pointer("/public_metrics/followers_count")
-                            .and_then(|v| v.as_u64()),
-                        following_count: u
-                            .pointer("/public_metrics/following_count")
-                            .and_then(|v| v.as_u64()),
-                    });
-                }
+                users.extend(arr.iter().map(user_snapshot));
             }
         }
 
@@ -238,14 +227,32 @@ fn list_snapshots(dir: &Path, username: &str, snap_type: &str) -> Vec<String> {
     files
 }
 
-fn load_latest_snapshot(dir: &Path, username: &str, snap_type: &str) -> Option<Snapshot> {
+pub fn load_latest_snapshot(dir: &Path, username: &str, snap_type: &str) -> Option<Snapshot> {
     let files = list_snapshots(dir, username, snap_type);
     let first = files.first()?;
     let content = fs::read_to_string(dir.join(first)).ok()?;
     serde_json::from_str(&content).ok()
 }
 
-fn compute_diff(previous: &Snapshot, current: &Snapshot) -> FollowerDiff {
+/// Write `snapshot` as the day's snapshot for its user and type. The file is
+/// written to a temporary path and renamed so a crash never leaves a
+/// truncated snapshot behind.
+pub fn save_snapshot(dir: &Path, snapshot: &Snapshot) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let date = &snapshot.timestamp[..10];
+    let path = dir.join(format!(
+        "{}-{}-{}.json",
+        snapshot.username.to_lowercase(),
+        snapshot.snap_type,
+        date
+    ));
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(snapshot)?)?;
+    fs::rename(&tmp, &path)?;
+    Ok(path)
+}
+
+pub fn compute_diff(previous: &Snapshot, current: &Snapshot) -> FollowerDiff {
     let prev_ids: HashSet<&str> = previous.users.iter().map(|u| u.id.as_str()).collect();
     let curr_ids: HashSet<&str> = current.users.iter().map(|u| u.id.as_str()).collect();
 
@@ -329,3 +336,49 @@ fn format_diff(diff: &FollowerDiff, snap_type: &str) -> String {
 
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_diff, load_latest_snapshot, save_snapshot, user_snapshot};
+    use crate::models::Snapshot;
+
+    fn snapshot(timestamp: &str, ids: &[&str]) -> Snapshot {
+        let users: Vec<_> = ids
+            .iter()
+            .map(|id| {
+                user_snapshot(
+                    &serde_json::json!({ "id": id, "username": format!("u{id}"), "name": id }),
+                )
+            })
+            .collect();
+        Snapshot {
+            username: "Alice".to_string(),
+            snap_type: "followers".to_string(),
+            timestamp: timestamp.to_string(),
+            count: users.len(),
+            users,
+        }
+    }
+
+    #[test]
+    fn saved_snapshots_reload_and_diff_against_the_latest() {
+        let dir = std::env::temp_dir().join(format!("xint-rs-diff-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(load_latest_snapshot(&dir, "alice", "followers").is_none());
+
+        save_snapshot(&dir, &snapshot("2026-02-18T00:00:00Z", &["1", "2"])).expect("save");
+        let path =
+            save_snapshot(&dir, &snapshot("2026-02-19T00:00:00Z", &["2", "3"])).expect("save");
+        assert!(path.ends_with("alice-followers-2026-02-19.json"));
+        assert!(!path.with_extension("json.tmp").exists());
+
+        let latest = load_latest_snapshot(&dir, "alice", "followers").expect("latest");
+        assert_eq!(latest.timestamp, "2026-02-19T00:00:00Z");
+
+        let diff = compute_diff(&latest, &snapshot("2026-02-20T00:00:00Z", &["3", "4"]));
+        assert_eq!(diff.added[0].id, "4");
+        assert_eq!(diff.removed[0].username, "u2");
+        assert_eq!(diff.unchanged, 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
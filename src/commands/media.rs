@@ -461,7 +461,7 @@ fn infer_extension(source_url: &str, media_type: &str) -> String {
     }
 }
 
-fn select_download_url(media: &serde_json::Value) -> Option<String> {
+pub fn select_download_url(media: &serde_json::Value) -> Option<String> {
     let media_type = media
         .get("type")
         .and_then(|v| v.as_str())
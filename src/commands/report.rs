@@ -87,7 +87,7 @@ pub async fn run(args: &ReportArgs, config: &Config, client: &XClient) -> Result
             let http = reqwest::Client::new();
             match sentiment::analyze_sentiment(&http, api_key, &top_tweets, Some(&args.model)).await
             {
-                Ok(sentiments) => {
+                Ok((sentiments, _)) => {
                     let stats = sentiment::compute_stats(&sentiments);
                     let total = top_tweets.len();
                     let pct = |n: u32| -> u32 {
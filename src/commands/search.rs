@@ -115,7 +115,7 @@ pub async fn run(args: &SearchArgs, config: &Config, client: &XClient) -> Result
             let http = reqwest::Client::new();
             eprintln!("Running sentiment analysis...");
             match sentiment::analyze_sentiment(&http, api_key, &tweets, None).await {
-                Ok(sentiments) => {
+                Ok((sentiments, _)) => {
                     let stats = sentiment::compute_stats(&sentiments);
                     eprint!("{}", sentiment::format_stats(&stats, tweets.len()));
                 }
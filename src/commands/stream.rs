@@ -74,6 +74,7 @@ pub async fn run_stream(args: &StreamArgs, config: &Config, client: &XClient) ->
     let mut buffer = String::new();
     let start_time = std::time::Instant::now();
     let mut events_seen: usize = 0;
+    let mut budget_exceeded = None;
 
     while running.load(Ordering::SeqCst) {
         let chunk = response.chunk().await?;
@@ -179,12 +180,9 @@ pub async fn run_stream(args: &StreamArgs, config: &Config, client: &XClient) ->
                     }
                 }
 
-                let budget = costs::check_budget(&config.costs_path());
-                if !budget.allowed {
-                    eprintln!(
-                        "\n!! Budget exceeded (${:.2}/${:.2}). Stopping stream.",
-                        budget.spent, budget.limit
-                    );
+                if let Err(exceeded) = costs::ensure_within_budget(&config.costs_path()) {
+                    eprintln!("\n!! {exceeded}. Stopping stream.");
+                    budget_exceeded = Some(exceeded);
                     running.store(false, Ordering::SeqCst);
                     break;
                 }
@@ -210,6 +208,9 @@ pub async fn run_stream(args: &StreamArgs, config: &Config, client: &XClient) ->
         );
     }
 
+    if let Some(exceeded) = budget_exceeded {
+        return Err(exceeded.into());
+    }
     Ok(())
 }
 
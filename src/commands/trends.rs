@@ -227,7 +227,7 @@ pub async fn run(args: &TrendsArgs, config: &Config, client: &XClient) -> Result
                 .into_iter()
                 .filter(|(_, count)| *count >= 2)
                 .collect();
-            sorted.sort_by(|a, b| b.1.cmp(&a.1));
+            sorted.sort_by_key(|b| std::cmp::Reverse(b.1));
 
             let trends: Vec<Trend> = sorted
                 .into_iter()
@@ -1,6 +1,7 @@
 use std::cmp::max;
 use std::fs;
 use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::sync::mpsc;
 use std::thread;
@@ -18,6 +19,9 @@ use crate::commands::actions::{
     normalize_interactive_choice, score_interactive_action, INTERACTIVE_ACTIONS,
 };
 use crate::commands::tui_adapter::build_tui_execution_plan;
+use crate::config::Config;
+use crate::costs;
+use crate::models::BudgetStatus;
 use crate::policy;
 
 #[derive(Default)]
@@ -37,6 +41,7 @@ enum DashboardTab {
     Commands,
     Output,
     Help,
+    Config,
 }
 
 #[derive(Copy, Clone)]
@@ -54,6 +59,7 @@ impl DashboardTab {
             Self::Commands => "Commands",
             Self::Output => "Output",
             Self::Help => "Help",
+            Self::Config => "Config",
         }
     }
 
@@ -61,7 +67,8 @@ impl DashboardTab {
         match self {
             Self::Commands => Self::Output,
             Self::Output => Self::Help,
-            Self::Help => Self::Commands,
+            Self::Help => Self::Config,
+            Self::Config => Self::Commands,
         }
     }
 }
@@ -71,10 +78,91 @@ struct UiState {
     tab: DashboardTab,
     output_offset: usize,
     output_search: String,
+    show_line_numbers: bool,
+    match_cursor: Option<usize>,
+    center_on_match: bool,
+    menu_filter: String,
+    /// Set while `/` on the Commands tab routes letters into the menu filter
+    /// instead of treating them as hotkeys.
+    menu_filter_typing: bool,
+    theme_name: String,
+    help_context: DashboardTab,
+    policy_mode: PolicyMode,
     inline_prompt_label: Option<String>,
     inline_prompt_value: String,
 }
 
+/// Settings in effect for this TUI session, shown read-only on the Config tab.
+struct EffectiveConfig {
+    policy_mode: PolicyMode,
+    budget: BudgetStatus,
+    theme_name: String,
+    theme_file: Option<String>,
+    package_api_base_url: Option<String>,
+    package_api_key: Option<String>,
+    data_dir: PathBuf,
+}
+
+impl EffectiveConfig {
+    fn load(ui_state: &UiState) -> Result<Self> {
+        let config = Config::load()?;
+        let env = |key: &str| std::env::var(key).ok().filter(|v| !v.trim().is_empty());
+        Ok(Self {
+            policy_mode: ui_state.policy_mode,
+            budget: costs::check_budget(&config.costs_path()),
+            theme_name: ui_state.theme_name.clone(),
+            theme_file: env("XINT_TUI_THEME_FILE"),
+            package_api_base_url: env("XINT_PACKAGE_API_BASE_URL"),
+            package_api_key: env("XINT_PACKAGE_API_KEY"),
+            data_dir: config.data_dir,
+        })
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let budget = if self.budget.enabled {
+            format!(
+                "enforced, ${:.2} of ${:.2}/day spent",
+                self.budget.spent, self.budget.limit
+            )
+        } else {
+            format!("not enforced (${:.2} spent today)", self.budget.spent)
+        };
+        let theme = match &self.theme_file {
+            Some(path) => format!("{} (file: {path})", self.theme_name),
+            None => self.theme_name.clone(),
+        };
+        vec![
+            "Effective configuration".to_string(),
+            String::new(),
+            format!("  policy_mode: {}", policy::as_str(self.policy_mode)),
+            format!("  budget: {budget}"),
+            format!("  theme: {theme}"),
+            format!(
+                "  package_api_base_url: {}",
+                self.package_api_base_url.as_deref().unwrap_or("(unset)")
+            ),
+            format!(
+                "  package_api_key: {}",
+                self.package_api_key
+                    .as_deref()
+                    .map(redact_secret)
+                    .unwrap_or_else(|| "(unset)".to_string())
+            ),
+            format!("  config dir: {}", self.data_dir.display()),
+        ]
+    }
+}
+
+/// Keep a short prefix so users can tell keys apart without exposing them.
+fn redact_secret(value: &str) -> String {
+    let prefix: String = value.chars().take(4).collect();
+    if value.chars().count() <= 8 {
+        "(set, redacted)".to_string()
+    } else {
+        format!("{prefix}… (redacted)")
+    }
+}
+
 struct Theme {
     accent: String,
     border: String,
@@ -118,24 +206,57 @@ impl Drop for TerminalUiGuard {
     }
 }
 
-const HELP_LINES: &[&str] = &[
-    "Hotkeys",
-    "  Up/Down: Move selection",
-    "  Enter: Run selected command",
-    "  Tab: Switch tabs",
-    "  F: Output search (filter)",
-    "  PgUp/PgDn: Scroll output",
-    "  /: Command palette",
-    "  ?: Open Help tab",
-    "  q or Esc: Exit",
-];
-
-fn active_theme() -> Theme {
-    let mut theme = match std::env::var("XINT_TUI_THEME")
-        .unwrap_or_else(|_| "classic".to_string())
-        .to_lowercase()
-        .as_str()
-    {
+/// Switch tabs, remembering which tab Help was opened from so it can show
+/// that tab's keys.
+fn switch_tab(ui_state: &mut UiState, tab: DashboardTab) {
+    if matches!(tab, DashboardTab::Help) && !matches!(ui_state.tab, DashboardTab::Help) {
+        ui_state.help_context = ui_state.tab;
+    }
+    ui_state.tab = tab;
+}
+
+/// Hotkeys that apply to `context`, the tab that was active when Help was
+/// opened, followed by the keys available everywhere.
+fn help_lines(context: DashboardTab) -> Vec<String> {
+    let contextual: &[&str] = match context {
+        DashboardTab::Commands => &[
+            "  /: Filter menu (type to narrow, Backspace to edit)",
+            "  Esc: Clear menu filter (exits when empty)",
+            "  F: Output search (filter)",
+            "  q: Exit",
+        ],
+        DashboardTab::Output => &[
+            "  F: Output search (filter)",
+            "  PgUp/PgDn: Scroll output",
+            "  n/N: Next/previous filter match (toggles line numbers when no filter)",
+            "  q or Esc: Exit",
+        ],
+        DashboardTab::Help | DashboardTab::Config => {
+            &["  F: Output search (filter)", "  q or Esc: Exit"]
+        }
+    };
+    const ALWAYS: &[&str] = &[
+        "",
+        "Always available",
+        "  Up/Down: Move selection",
+        "  Enter: Run selected command",
+        "  Tab or 1/2/3/4: Switch tabs",
+        "  /: Command palette",
+        "  T: Cycle theme",
+        "  ?: Open Help tab",
+    ];
+
+    let mut lines = vec![format!("Hotkeys ({})", context.label())];
+    lines.extend(contextual.iter().chain(ALWAYS).map(|line| line.to_string()));
+    lines
+}
+
+const THEME_NAMES: &[&str] = &["classic", "minimal", "ocean", "amber", "neon"];
+
+const CUSTOM_THEME: &str = "custom";
+
+fn preset_theme(name: &str) -> Theme {
+    match name {
         "minimal" => Theme {
             accent: "\x1b[1m".to_string(),
             border: "".to_string(),
@@ -171,7 +292,52 @@ fn active_theme() -> Theme {
             hero: "\x1b[1;34m".to_string(),
             reset: "\x1b[0m".to_string(),
         },
-    };
+    }
+}
+
+/// Theme selected at startup: `XINT_TUI_THEME`, or `custom` when a
+/// `XINT_TUI_THEME_FILE` overlay is configured.
+fn initial_theme_name() -> String {
+    if std::env::var("XINT_TUI_THEME_FILE").is_ok() {
+        return CUSTOM_THEME.to_string();
+    }
+    std::env::var("XINT_TUI_THEME")
+        .unwrap_or_else(|_| "classic".to_string())
+        .to_lowercase()
+}
+
+/// Theme names `T` cycles through; `custom` is included only when a theme
+/// file is configured.
+fn theme_cycle() -> Vec<&'static str> {
+    let mut names = THEME_NAMES.to_vec();
+    if std::env::var("XINT_TUI_THEME_FILE").is_ok() {
+        names.push(CUSTOM_THEME);
+    }
+    names
+}
+
+fn next_theme_name(current: &str) -> String {
+    let names = theme_cycle();
+    let next = names
+        .iter()
+        .position(|name| *name == current)
+        .map(|pos| (pos + 1) % names.len())
+        .unwrap_or(0);
+    names[next].to_string()
+}
+
+/// Resolve a theme by name. `custom` is the `XINT_TUI_THEME` preset with the
+/// `XINT_TUI_THEME_FILE` overlay applied.
+fn theme_by_name(name: &str) -> Theme {
+    if name != CUSTOM_THEME {
+        return preset_theme(name);
+    }
+
+    let mut theme = preset_theme(
+        &std::env::var("XINT_TUI_THEME")
+            .unwrap_or_else(|_| "classic".to_string())
+            .to_lowercase(),
+    );
 
     if let Ok(path) = std::env::var("XINT_TUI_THEME_FILE") {
         if let Ok(raw) = fs::read_to_string(path) {
@@ -334,6 +500,7 @@ fn build_tabs(ui_state: &UiState) -> String {
         DashboardTab::Commands,
         DashboardTab::Output,
         DashboardTab::Help,
+        DashboardTab::Config,
     ]
     .iter()
     .enumerate()
@@ -344,6 +511,7 @@ fn build_tabs(ui_state: &UiState) -> String {
             (DashboardTab::Commands, DashboardTab::Commands)
                 | (DashboardTab::Output, DashboardTab::Output)
                 | (DashboardTab::Help, DashboardTab::Help)
+                | (DashboardTab::Config, DashboardTab::Config)
         ) {
             format!("‹{label}›")
         } else {
@@ -426,8 +594,50 @@ fn sanitize_output_line(raw: &str) -> String {
     out
 }
 
-fn build_menu_lines(active_index: usize) -> Vec<String> {
-    let mut lines = vec!["Menu".to_string(), String::new()];
+/// Indices of menu entries matching `filter`, in menu order. An empty
+/// filter keeps every entry.
+fn menu_entry_indices(filter: &str) -> Vec<usize> {
+    let trimmed = filter.trim();
+    INTERACTIVE_ACTIONS
+        .iter()
+        .enumerate()
+        .filter(|(_, option)| trimmed.is_empty() || score_interactive_action(option, trimmed) > 0)
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Move the selection within the filtered menu, wrapping at either end.
+fn step_menu_selection(ui_state: &mut UiState, forward: bool) {
+    let visible = menu_entry_indices(&ui_state.menu_filter);
+    if visible.is_empty() {
+        return;
+    }
+    let next = match visible.iter().position(|&i| i == ui_state.active_index) {
+        Some(pos) if forward => (pos + 1) % visible.len(),
+        Some(pos) => (pos + visible.len() - 1) % visible.len(),
+        None => 0,
+    };
+    ui_state.active_index = visible[next];
+}
+
+/// Re-point the selection at the best match after the menu filter changes.
+fn apply_menu_filter(ui_state: &mut UiState) {
+    if let Some(index) = match_palette(&ui_state.menu_filter) {
+        ui_state.active_index = index;
+    }
+}
+
+fn build_menu_lines(active_index: usize, filter: &str, typing: bool) -> Vec<String> {
+    let mut lines = vec![
+        if typing {
+            format!("Menu | filter: {filter}_")
+        } else if filter.trim().is_empty() {
+            "Menu".to_string()
+        } else {
+            format!("Menu | filter: {}", filter.trim())
+        },
+        String::new(),
+    ];
 
     let icon_for_action = |key: &str| match key {
         "1" => "⌕ ",
@@ -439,7 +649,12 @@ fn build_menu_lines(active_index: usize) -> Vec<String> {
         _ => "",
     };
 
-    for (index, option) in INTERACTIVE_ACTIONS.iter().enumerate() {
+    let visible = menu_entry_indices(filter);
+    if visible.is_empty() {
+        lines.push("  (no commands match filter)".to_string());
+    }
+    for index in visible {
+        let option = &INTERACTIVE_ACTIONS[index];
         let pointer = if index == active_index { ">" } else { " " };
         let aliases = if option.aliases.is_empty() {
             String::new()
@@ -510,25 +725,68 @@ fn phase_badge(phase: UiPhase) -> String {
     }
 }
 
+fn output_match_indices(lines: &[String], query: &str) -> Vec<usize> {
+    let query = query.trim().to_ascii_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.to_ascii_lowercase().contains(&query))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+fn step_output_match(session: &mut SessionState, ui_state: &mut UiState, forward: bool) {
+    let count = output_match_indices(&session.last_output_lines, &ui_state.output_search).len();
+    if count == 0 {
+        ui_state.match_cursor = None;
+        session.last_status = Some(format!("no matches: {}", ui_state.output_search.trim()));
+        return;
+    }
+    ui_state.match_cursor = Some(match (ui_state.match_cursor, forward) {
+        (None, true) => 0,
+        (None, false) => count - 1,
+        (Some(current), true) => (current + 1) % count,
+        (Some(current), false) => (current + count - 1) % count,
+    });
+    ui_state.center_on_match = true;
+}
+
 fn output_view_lines(
     session: &SessionState,
     ui_state: &mut UiState,
     viewport: usize,
 ) -> Vec<String> {
-    let query = ui_state.output_search.trim().to_ascii_lowercase();
+    let matches = output_match_indices(&session.last_output_lines, &ui_state.output_search);
+    if ui_state
+        .match_cursor
+        .is_some_and(|cursor| cursor >= matches.len())
+    {
+        ui_state.match_cursor = None;
+    }
+    // Jumping between matches shows the full output with matches marked;
+    // otherwise an active filter hides non-matching lines.
+    let current_match = ui_state.match_cursor.map(|cursor| matches[cursor]);
 
-    let filtered: Vec<&String> = if query.is_empty() {
-        session.last_output_lines.iter().collect()
-    } else {
-        session
-            .last_output_lines
-            .iter()
-            .filter(|line| line.to_ascii_lowercase().contains(&query))
-            .collect()
-    };
+    let filtered: Vec<&String> =
+        if ui_state.output_search.trim().is_empty() || current_match.is_some() {
+            session.last_output_lines.iter().collect()
+        } else {
+            matches
+                .iter()
+                .map(|&index| &session.last_output_lines[index])
+                .collect()
+        };
 
     let visible = max(1usize, viewport);
     let max_offset = filtered.len().saturating_sub(visible);
+    if let Some(target) = current_match.filter(|_| ui_state.center_on_match) {
+        let centered_start = target.saturating_sub(visible / 2).min(max_offset);
+        ui_state.output_offset = max_offset - centered_start;
+        ui_state.center_on_match = false;
+    }
     if ui_state.output_offset > max_offset {
         ui_state.output_offset = max_offset;
     }
@@ -538,6 +796,19 @@ fn output_view_lines(
         .saturating_sub(visible.saturating_add(ui_state.output_offset));
     let end = (start + visible).min(filtered.len());
 
+    let filter_label = if ui_state.output_search.trim().is_empty() {
+        "(none)".to_string()
+    } else if let Some(cursor) = ui_state.match_cursor {
+        format!(
+            "{} | match {}/{}",
+            ui_state.output_search.trim(),
+            cursor + 1,
+            matches.len()
+        )
+    } else {
+        ui_state.output_search.trim().to_string()
+    };
+
     let mut lines = vec![
         "Last run".to_string(),
         String::new(),
@@ -550,14 +821,7 @@ fn output_view_lines(
             session.last_command.as_deref().unwrap_or("-")
         ),
         format!("status: {}", session.last_status.as_deref().unwrap_or("-")),
-        format!(
-            "filter: {}",
-            if ui_state.output_search.trim().is_empty() {
-                "(none)"
-            } else {
-                ui_state.output_search.trim()
-            }
-        ),
+        format!("filter: {filter_label}"),
         String::new(),
         "output:".to_string(),
     ];
@@ -572,8 +836,20 @@ fn output_view_lines(
     if start >= end {
         lines.push("(no output lines for current filter)".to_string());
     } else {
-        for line in filtered[start..end].iter().map(|line| line.as_str()) {
-            lines.push(line.to_string());
+        let gutter_width = filtered.len().to_string().len();
+        for (index, line) in filtered[start..end].iter().enumerate() {
+            let position = start + index;
+            let marked = match current_match {
+                Some(target) if target == position => format!("> {line}"),
+                Some(_) if matches.binary_search(&position).is_ok() => format!("* {line}"),
+                Some(_) => format!("  {line}"),
+                None => line.to_string(),
+            };
+            if ui_state.show_line_numbers {
+                lines.push(format!("{:>gutter_width$} | {}", position + 1, marked));
+            } else {
+                lines.push(marked);
+            }
         }
     }
 
@@ -593,11 +869,15 @@ fn build_tab_lines(session: &SessionState, ui_state: &mut UiState, viewport: usi
     match ui_state.tab {
         DashboardTab::Help => {
             let mut help = vec!["Help".to_string(), String::new()];
-            help.extend(HELP_LINES.iter().map(|line| line.to_string()));
+            help.extend(help_lines(ui_state.help_context));
             help
         }
         DashboardTab::Commands => build_command_drawer(ui_state.active_index),
         DashboardTab::Output => output_view_lines(session, ui_state, viewport),
+        DashboardTab::Config => match EffectiveConfig::load(ui_state) {
+            Ok(config) => config.lines(),
+            Err(error) => vec![format!("Failed to resolve configuration: {error}")],
+        },
     }
 }
 
@@ -628,10 +908,10 @@ fn build_status_line(session: &SessionState, ui_state: &UiState, width: usize) -
 fn render_double_pane(
     ui_state: &mut UiState,
     session: &SessionState,
+    theme: &Theme,
     cols: usize,
     rows: usize,
 ) -> Result<()> {
-    let theme = active_theme();
     let total_rows = max(
         12usize,
         rows.saturating_sub(if is_hero_enabled() { 10 } else { 9 }),
@@ -641,7 +921,11 @@ fn render_double_pane(
     let left_inner = max(20usize, left_box_width.saturating_sub(2));
     let right_inner = max(20usize, right_box_width.saturating_sub(2));
 
-    let left_lines = build_menu_lines(ui_state.active_index);
+    let left_lines = build_menu_lines(
+        ui_state.active_index,
+        &ui_state.menu_filter,
+        ui_state.menu_filter_typing,
+    );
     let mut right_lines = build_tab_lines(session, ui_state, total_rows);
     if right_lines.len() > total_rows {
         right_lines = right_lines[right_lines.len() - total_rows..].to_vec();
@@ -772,10 +1056,10 @@ fn render_double_pane(
 fn render_single_pane(
     ui_state: &mut UiState,
     session: &SessionState,
+    theme: &Theme,
     cols: usize,
     rows: usize,
 ) -> Result<()> {
-    let theme = active_theme();
     let width = max(30usize, cols.saturating_sub(2));
     let total_rows = max(
         10usize,
@@ -786,7 +1070,11 @@ fn render_single_pane(
     let tracker = build_header_tracker(ui_state, 16);
 
     let lines = if matches!(ui_state.tab, DashboardTab::Commands) {
-        let mut merged = build_menu_lines(ui_state.active_index);
+        let mut merged = build_menu_lines(
+            ui_state.active_index,
+            &ui_state.menu_filter,
+            ui_state.menu_filter_typing,
+        );
         merged.push(String::new());
         merged.extend(build_command_drawer(ui_state.active_index));
         merged
@@ -920,10 +1208,11 @@ fn render_single_pane(
 
 fn render_dashboard(ui_state: &mut UiState, session: &SessionState) -> Result<()> {
     let (cols, rows) = terminal::size().unwrap_or((120, 32));
+    let theme = theme_by_name(&ui_state.theme_name);
     if (cols as usize) < 110 {
-        render_single_pane(ui_state, session, cols as usize, rows as usize)
+        render_single_pane(ui_state, session, &theme, cols as usize, rows as usize)
     } else {
-        render_double_pane(ui_state, session, cols as usize, rows as usize)
+        render_double_pane(ui_state, session, &theme, cols as usize, rows as usize)
     }
 }
 
@@ -952,78 +1241,132 @@ fn select_option_interactive(session: &mut SessionState, ui_state: &mut UiState)
             Event::Resize(_, _) => {
                 render_dashboard(ui_state, session)?;
             }
-            Event::Key(key_event) => match key_event.code {
-                KeyCode::Up => {
-                    ui_state.active_index = if ui_state.active_index == 0 {
-                        INTERACTIVE_ACTIONS.len() - 1
-                    } else {
-                        ui_state.active_index - 1
-                    };
-                    render_dashboard(ui_state, session)?;
-                }
-                KeyCode::Down => {
-                    ui_state.active_index = (ui_state.active_index + 1) % INTERACTIVE_ACTIONS.len();
-                    render_dashboard(ui_state, session)?;
+            Event::Key(key_event) => {
+                match handle_dashboard_key(session, ui_state, key_event.code) {
+                    KeyOutcome::Redraw => render_dashboard(ui_state, session)?,
+                    KeyOutcome::Choose(choice) => return Ok(choice),
+                    KeyOutcome::Ignore => {}
                 }
-                KeyCode::Tab => {
-                    ui_state.tab = ui_state.tab.next();
-                    render_dashboard(ui_state, session)?;
-                }
-                KeyCode::PageUp if matches!(ui_state.tab, DashboardTab::Output) => {
-                    ui_state.output_offset = ui_state.output_offset.saturating_add(10);
-                    render_dashboard(ui_state, session)?;
-                }
-                KeyCode::PageDown if matches!(ui_state.tab, DashboardTab::Output) => {
-                    ui_state.output_offset = ui_state.output_offset.saturating_sub(10);
-                    render_dashboard(ui_state, session)?;
-                }
-                KeyCode::Enter => {
-                    ui_state.tab = DashboardTab::Output;
-                    let selected = INTERACTIVE_ACTIONS
-                        .get(ui_state.active_index)
-                        .map(|option| option.key.to_string())
-                        .unwrap_or_else(|| "0".to_string());
-                    return Ok(selected);
-                }
-                KeyCode::Char('q') | KeyCode::Esc => {
-                    return Ok("0".to_string());
-                }
-                KeyCode::Char('?') => {
-                    ui_state.tab = DashboardTab::Help;
-                    render_dashboard(ui_state, session)?;
-                }
-                KeyCode::Char('1') => {
-                    ui_state.tab = DashboardTab::Commands;
-                    render_dashboard(ui_state, session)?;
-                }
-                KeyCode::Char('2') => {
-                    ui_state.tab = DashboardTab::Output;
-                    render_dashboard(ui_state, session)?;
-                }
-                KeyCode::Char('3') => {
-                    ui_state.tab = DashboardTab::Help;
-                    render_dashboard(ui_state, session)?;
-                }
-                KeyCode::Char('f') | KeyCode::Char('F') => {
-                    ui_state.tab = DashboardTab::Output;
-                    return Ok("__filter__".to_string());
-                }
-                KeyCode::Char('/') => {
-                    ui_state.tab = DashboardTab::Output;
-                    return Ok("__palette__".to_string());
-                }
-                KeyCode::Char(ch) => {
-                    if let Some(value) = normalize_interactive_choice(&ch.to_string()) {
-                        return Ok(value.to_string());
-                    }
-                }
-                _ => {}
-            },
+            }
             _ => {}
         }
     }
 }
 
+/// What the dashboard loop should do after a key press.
+#[derive(Debug, PartialEq, Eq)]
+enum KeyOutcome {
+    Redraw,
+    Choose(String),
+    Ignore,
+}
+
+/// Keys typed while the menu filter is being edited.
+fn handle_menu_filter_key(ui_state: &mut UiState, code: KeyCode) -> Option<KeyOutcome> {
+    match code {
+        KeyCode::Esc => {
+            ui_state.menu_filter.clear();
+            ui_state.menu_filter_typing = false;
+        }
+        KeyCode::Backspace => {
+            ui_state.menu_filter.pop();
+            apply_menu_filter(ui_state);
+        }
+        KeyCode::Char(ch) if !ch.is_control() => {
+            ui_state.menu_filter.push(ch);
+            apply_menu_filter(ui_state);
+        }
+        _ => return None,
+    }
+    Some(KeyOutcome::Redraw)
+}
+
+fn handle_dashboard_key(
+    session: &mut SessionState,
+    ui_state: &mut UiState,
+    code: KeyCode,
+) -> KeyOutcome {
+    if ui_state.menu_filter_typing && matches!(ui_state.tab, DashboardTab::Commands) {
+        if let Some(outcome) = handle_menu_filter_key(ui_state, code) {
+            return outcome;
+        }
+    }
+    match code {
+        KeyCode::Up => step_menu_selection(ui_state, false),
+        KeyCode::Down => step_menu_selection(ui_state, true),
+        KeyCode::Tab => {
+            ui_state.menu_filter_typing = false;
+            switch_tab(ui_state, ui_state.tab.next());
+        }
+        KeyCode::PageUp if matches!(ui_state.tab, DashboardTab::Output) => {
+            ui_state.output_offset = ui_state.output_offset.saturating_add(10);
+        }
+        KeyCode::PageDown if matches!(ui_state.tab, DashboardTab::Output) => {
+            ui_state.output_offset = ui_state.output_offset.saturating_sub(10);
+        }
+        KeyCode::Char(ch @ ('n' | 'N')) if matches!(ui_state.tab, DashboardTab::Output) => {
+            if ui_state.output_search.trim().is_empty() {
+                ui_state.show_line_numbers = !ui_state.show_line_numbers;
+            } else {
+                step_output_match(session, ui_state, ch == 'n');
+            }
+        }
+        KeyCode::Enter => {
+            ui_state.menu_filter_typing = false;
+            ui_state.tab = DashboardTab::Output;
+            let selected = INTERACTIVE_ACTIONS
+                .get(ui_state.active_index)
+                .map(|option| option.key.to_string())
+                .unwrap_or_else(|| "0".to_string());
+            return KeyOutcome::Choose(selected);
+        }
+        KeyCode::Char('T') => {
+            ui_state.theme_name = next_theme_name(&ui_state.theme_name);
+            session.last_status = Some(format!("theme: {}", ui_state.theme_name));
+        }
+        KeyCode::Esc if !ui_state.menu_filter.is_empty() => {
+            ui_state.menu_filter.clear();
+        }
+        KeyCode::Char('/') if matches!(ui_state.tab, DashboardTab::Commands) => {
+            ui_state.menu_filter_typing = true;
+        }
+        KeyCode::Char('q') | KeyCode::Esc => {
+            return KeyOutcome::Choose("0".to_string());
+        }
+        KeyCode::Char('?') => {
+            switch_tab(ui_state, DashboardTab::Help);
+        }
+        KeyCode::Char('1') => {
+            ui_state.tab = DashboardTab::Commands;
+        }
+        KeyCode::Char('2') => {
+            ui_state.tab = DashboardTab::Output;
+        }
+        KeyCode::Char('3') => {
+            switch_tab(ui_state, DashboardTab::Help);
+        }
+        KeyCode::Char('4') => {
+            ui_state.tab = DashboardTab::Config;
+        }
+        KeyCode::Char('f') | KeyCode::Char('F') => {
+            ui_state.tab = DashboardTab::Output;
+            return KeyOutcome::Choose("__filter__".to_string());
+        }
+        KeyCode::Char('/') => {
+            ui_state.tab = DashboardTab::Output;
+            return KeyOutcome::Choose("__palette__".to_string());
+        }
+        KeyCode::Char(ch) => {
+            return match normalize_interactive_choice(&ch.to_string()) {
+                Some(value) => KeyOutcome::Choose(value.to_string()),
+                None => KeyOutcome::Ignore,
+            };
+        }
+        _ => return KeyOutcome::Ignore,
+    }
+    KeyOutcome::Redraw
+}
+
 fn append_output(session: &mut SessionState, line: String) {
     let trimmed = sanitize_output_line(&line).trim_end().to_string();
     if trimmed.is_empty() {
@@ -1053,6 +1396,7 @@ fn run_subcommand(
     let mut child = cmd.spawn()?;
     session.last_output_lines.clear();
     ui_state.output_offset = 0;
+    ui_state.match_cursor = None;
 
     let (tx, rx) = mpsc::channel::<String>();
     let mut handles = Vec::new();
@@ -1143,6 +1487,14 @@ pub async fn run(_args: &TuiArgs, policy_mode: PolicyMode) -> Result<()> {
         tab: DashboardTab::Output,
         output_offset: 0,
         output_search: String::new(),
+        show_line_numbers: false,
+        match_cursor: None,
+        center_on_match: false,
+        menu_filter: String::new(),
+        menu_filter_typing: false,
+        theme_name: initial_theme_name(),
+        help_context: DashboardTab::Output,
+        policy_mode,
         inline_prompt_label: None,
         inline_prompt_value: String::new(),
     };
@@ -1158,6 +1510,7 @@ pub async fn run(_args: &TuiArgs, policy_mode: PolicyMode) -> Result<()> {
             )?;
             ui_state.output_search = query.trim().to_string();
             ui_state.output_offset = 0;
+            ui_state.match_cursor = None;
             ui_state.tab = DashboardTab::Output;
             session.last_status = Some(if ui_state.output_search.is_empty() {
                 "output filter cleared".to_string()
@@ -1313,8 +1666,35 @@ pub async fn run(_args: &TuiArgs, policy_mode: PolicyMode) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::match_palette;
+    use super::{
+        build_menu_lines, handle_dashboard_key, help_lines, match_palette, next_theme_name,
+        output_view_lines, redact_secret, step_menu_selection, step_output_match, switch_tab,
+        DashboardTab, EffectiveConfig, KeyOutcome, SessionState, UiState,
+    };
+    use crate::cli::PolicyMode;
     use crate::commands::actions::normalize_interactive_choice;
+    use crate::models::BudgetStatus;
+    use crossterm::event::KeyCode;
+    use std::path::PathBuf;
+
+    fn test_ui_state() -> UiState {
+        UiState {
+            active_index: 0,
+            tab: DashboardTab::Output,
+            output_offset: 0,
+            output_search: String::new(),
+            show_line_numbers: false,
+            match_cursor: None,
+            center_on_match: false,
+            menu_filter: String::new(),
+            menu_filter_typing: false,
+            theme_name: "classic".to_string(),
+            help_context: DashboardTab::Output,
+            policy_mode: PolicyMode::ReadOnly,
+            inline_prompt_label: None,
+            inline_prompt_value: String::new(),
+        }
+    }
 
     #[test]
     fn normalize_choice_supports_numeric_and_alias_inputs() {
@@ -1335,4 +1715,171 @@ mod tests {
         assert_eq!(match_palette("profile"), Some(2));
         assert_eq!(match_palette("zzz"), None);
     }
+
+    #[test]
+    fn line_numbers_follow_filtered_view() {
+        let session = SessionState {
+            last_output_lines: (1..=12).map(|i| format!("line {i}")).collect(),
+            ..SessionState::default()
+        };
+        let mut ui_state = test_ui_state();
+        ui_state.output_search = "line 1".to_string();
+
+        let plain = output_view_lines(&session, &mut ui_state, 10);
+        assert!(plain.iter().any(|line| line == "line 12"));
+
+        ui_state.show_line_numbers = true;
+        let numbered = output_view_lines(&session, &mut ui_state, 10);
+        assert!(numbered.iter().any(|line| line == "1 | line 1"));
+        assert!(numbered.iter().any(|line| line == "4 | line 12"));
+    }
+
+    #[test]
+    fn match_jumps_center_view_and_wrap() {
+        let mut session = SessionState {
+            last_output_lines: (0..40)
+                .map(|i| {
+                    if i % 10 == 5 {
+                        format!("hit {i}")
+                    } else {
+                        format!("row {i}")
+                    }
+                })
+                .collect(),
+            ..SessionState::default()
+        };
+        let mut ui_state = test_ui_state();
+        ui_state.output_search = "hit".to_string();
+
+        step_output_match(&mut session, &mut ui_state, true);
+        let lines = output_view_lines(&session, &mut ui_state, 10);
+        assert!(lines.iter().any(|line| line == "> hit 5"));
+        assert!(lines.iter().any(|line| line == "  row 0"));
+        assert!(lines
+            .iter()
+            .any(|line| line == "view 1-10 of 40 | offset 30"));
+
+        step_output_match(&mut session, &mut ui_state, true);
+        let lines = output_view_lines(&session, &mut ui_state, 10);
+        assert!(lines.iter().any(|line| line == "> hit 15"));
+        assert!(lines
+            .iter()
+            .any(|line| line == "view 11-20 of 40 | offset 20"));
+
+        step_output_match(&mut session, &mut ui_state, false);
+        step_output_match(&mut session, &mut ui_state, false);
+        let lines = output_view_lines(&session, &mut ui_state, 10);
+        assert!(lines.iter().any(|line| line == "> hit 35"));
+        assert!(lines.iter().any(|line| line == "filter: hit | match 4/4"));
+    }
+
+    #[test]
+    fn menu_filter_narrows_entries_and_navigation() {
+        let lines = build_menu_lines(2, "pro", false);
+        assert_eq!(lines[0], "Menu | filter: pro");
+        assert!(lines.iter().any(|line| line.contains("Profile")));
+        assert!(!lines.iter().any(|line| line.contains("Search")));
+
+        let mut ui_state = test_ui_state();
+        ui_state.menu_filter = "th".to_string();
+        ui_state.active_index = 3;
+        step_menu_selection(&mut ui_state, true);
+        let visible = super::menu_entry_indices("th");
+        assert!(visible.contains(&ui_state.active_index));
+    }
+
+    #[test]
+    fn commands_tab_keeps_hotkeys_until_slash_starts_the_filter() {
+        let mut session = SessionState::default();
+        let mut ui_state = test_ui_state();
+        ui_state.tab = DashboardTab::Commands;
+        let mut press =
+            |ui_state: &mut UiState, code| handle_dashboard_key(&mut session, ui_state, code);
+
+        assert_eq!(
+            press(&mut ui_state, KeyCode::Char('q')),
+            KeyOutcome::Choose("0".to_string())
+        );
+        assert_eq!(
+            press(&mut ui_state, KeyCode::Char('f')),
+            KeyOutcome::Choose("__filter__".to_string())
+        );
+
+        ui_state.tab = DashboardTab::Commands;
+        assert_eq!(press(&mut ui_state, KeyCode::Char('/')), KeyOutcome::Redraw);
+        press(&mut ui_state, KeyCode::Char('q'));
+        press(&mut ui_state, KeyCode::Char('u'));
+        assert_eq!(ui_state.menu_filter, "qu");
+        assert_eq!(
+            build_menu_lines(0, &ui_state.menu_filter, true)[0],
+            "Menu | filter: qu_"
+        );
+
+        press(&mut ui_state, KeyCode::Esc);
+        assert!(ui_state.menu_filter.is_empty() && !ui_state.menu_filter_typing);
+        assert_eq!(
+            press(&mut ui_state, KeyCode::Char('q')),
+            KeyOutcome::Choose("0".to_string())
+        );
+    }
+
+    #[test]
+    fn theme_cycle_advances_and_wraps() {
+        assert_eq!(next_theme_name("classic"), "minimal");
+        assert_eq!(next_theme_name("unknown"), "classic");
+        if std::env::var("XINT_TUI_THEME_FILE").is_err() {
+            assert_eq!(next_theme_name("neon"), "classic");
+        }
+    }
+
+    #[test]
+    fn help_lists_keys_for_the_tab_it_was_opened_from() {
+        let mut ui_state = test_ui_state();
+        ui_state.tab = DashboardTab::Commands;
+        switch_tab(&mut ui_state, DashboardTab::Help);
+        assert!(matches!(ui_state.help_context, DashboardTab::Commands));
+
+        let commands_help = help_lines(ui_state.help_context);
+        assert!(commands_help
+            .iter()
+            .any(|line| line.contains("Filter menu")));
+        assert!(!commands_help.iter().any(|line| line.contains("PgUp/PgDn")));
+
+        let output_help = help_lines(DashboardTab::Output);
+        assert!(output_help.iter().any(|line| line.contains("PgUp/PgDn")));
+        assert!(output_help
+            .iter()
+            .any(|line| line.contains("T: Cycle theme")));
+    }
+
+    #[test]
+    fn effective_config_redacts_package_api_key() {
+        let config = EffectiveConfig {
+            policy_mode: PolicyMode::Engagement,
+            budget: BudgetStatus {
+                enabled: true,
+                allowed: true,
+                spent: 0.25,
+                limit: 1.0,
+                remaining: 0.75,
+                warning: false,
+                percent_used: 25.0,
+                warn_threshold: 0.8,
+            },
+            theme_name: "neon".to_string(),
+            theme_file: None,
+            package_api_base_url: Some("http://localhost:8787/v1".to_string()),
+            package_api_key: Some("xck_live_supersecret".to_string()),
+            data_dir: PathBuf::from("/tmp/xint-data"),
+        };
+        let text = config.lines().join("\n");
+        assert!(text.contains("policy_mode: engagement"));
+        assert!(text.contains("budget: enforced, $0.25 of $1.00/day spent"));
+        assert!(text.contains("theme: neon"));
+        assert!(text.contains("package_api_base_url: http://localhost:8787/v1"));
+        assert!(text.contains("package_api_key: xck_… (redacted)"));
+        assert!(!text.contains("supersecret"));
+        assert!(text.contains("config dir: /tmp/xint-data"));
+        assert_eq!(redact_secret("short"), "(set, redacted)");
+    }
 }
@@ -92,6 +92,7 @@ pub async fn run(args: &WatchArgs, config: &Config, client: &XClient) -> Result<
     let mut total_new = 0u64;
     let mut total_cost = 0.0f64;
     let start_time = std::time::Instant::now();
+    let mut budget_exceeded = None;
 
     while running.load(Ordering::SeqCst) {
         let poll_started_at = std::time::Instant::now();
@@ -197,12 +198,9 @@ pub async fn run(args: &WatchArgs, config: &Config, client: &XClient) -> Result<
                 }
 
                 // Budget check
-                let budget = costs::check_budget(&config.costs_path());
-                if !budget.allowed {
-                    eprintln!(
-                        "\n!! Budget exceeded (${:.2}/${:.2}). Stopping watch.",
-                        budget.spent, budget.limit
-                    );
+                if let Err(exceeded) = costs::ensure_within_budget(&config.costs_path()) {
+                    eprintln!("\n!! {exceeded}. Stopping watch.");
+                    budget_exceeded = Some(exceeded);
                     break;
                 }
             }
@@ -235,5 +233,8 @@ pub async fn run(args: &WatchArgs, config: &Config, client: &XClient) -> Result<
         "Duration: {mins}m {secs}s | Polls: {poll_count} | New tweets: {total_new} | Est. cost: ~${total_cost:.3}"
     );
 
+    if let Some(exceeded) = budget_exceeded {
+        return Err(exceeded.into());
+    }
     Ok(())
 }
@@ -1,5 +1,11 @@
-use anyhow::Result;
-use std::path::PathBuf;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::cli::PolicyMode;
+use crate::costs;
+use crate::models::ModelRate;
 
 /// Resolved configuration from env vars and .env file.
 pub struct Config {
@@ -8,6 +14,11 @@ pub struct Config {
     pub xai_api_key: Option<String>,
     pub xai_management_api_key: Option<String>,
     pub data_dir: PathBuf,
+    /// Grok token rates: the built-in table, overlaid by the rates file and
+    /// `XINT_MODEL_RATES`.
+    pub model_rates: HashMap<String, ModelRate>,
+    /// Rate entries that were skipped because they could not be used.
+    model_rate_errors: Vec<String>,
 }
 
 impl Config {
@@ -33,6 +44,8 @@ impl Config {
 
         // Data dir: ./data/ relative to binary, or current dir
         let data_dir = resolve_data_dir();
+        let mut model_rate_errors = Vec::new();
+        let model_rates = load_model_rates(&data_dir, &mut model_rate_errors);
 
         Ok(Self {
             bearer_token,
@@ -40,9 +53,34 @@ impl Config {
             xai_api_key,
             xai_management_api_key,
             data_dir,
+            model_rates,
+            model_rate_errors,
         })
     }
 
+    /// Rate entries that were skipped, then tool default models left without
+    /// a rate; completions on those models are recorded as unpriced.
+    pub fn model_rate_warnings(&self) -> Vec<String> {
+        let unpriced = costs::unpriced_models(&self.model_rates)
+            .into_iter()
+            .map(|model| {
+                format!(
+                    "no token rate configured for model {model}; its calls will be recorded \
+                     as unpriced. Add it to {} or XINT_MODEL_RATES",
+                    self.model_rates_path().display()
+                )
+            });
+        self.model_rate_errors
+            .iter()
+            .cloned()
+            .chain(unpriced)
+            .collect()
+    }
+
+    pub fn model_rates_path(&self) -> PathBuf {
+        model_rates_path(&self.data_dir)
+    }
+
     pub fn require_bearer_token(&self) -> Result<&str> {
         self.bearer_token.as_deref().ok_or_else(|| {
             anyhow::anyhow!("X_BEARER_TOKEN not found. Set it in your environment or in .env")
@@ -81,6 +119,10 @@ impl Config {
         self.data_dir.join("snapshots")
     }
 
+    pub fn watch_state_dir(&self) -> PathBuf {
+        self.data_dir.join("watch-state")
+    }
+
     pub fn tokens_path(&self) -> PathBuf {
         self.data_dir.join("oauth-tokens.json")
     }
@@ -93,11 +135,216 @@ impl Config {
         self.data_dir.join("reliability-metrics.json")
     }
 
+    pub fn policy_audit_path(&self) -> PathBuf {
+        self.data_dir.join("policy-audit.jsonl")
+    }
+
     pub fn watchlist_path(&self) -> PathBuf {
         self.data_dir.join("watchlist.json")
     }
 }
 
+/// MCP server settings loaded from `xint mcp --config <file>`.
+///
+/// Every field is optional; env vars and command-line flags take precedence
+/// over values from the file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct McpConfig {
+    pub budget: McpBudgetConfig,
+    pub policy: McpPolicyConfig,
+    pub package_api: McpPackageApiConfig,
+    pub rate_limits: McpRateLimitConfig,
+    pub tools: McpToolsConfig,
+    pub timeouts: McpTimeoutsConfig,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct McpBudgetConfig {
+    pub enforce: Option<bool>,
+    pub daily_limit_usd: Option<f64>,
+    pub weekly_limit_usd: Option<f64>,
+    pub monthly_limit_usd: Option<f64>,
+    pub session_limit_usd: Option<f64>,
+    /// Fraction of the daily limit (0-1) at which results carry a
+    /// `budget_warning`.
+    pub warn_threshold: Option<f64>,
+    /// Daily sub-limits per tool category, enforced alongside the global
+    /// daily limit.
+    pub categories: McpCategoryLimitsConfig,
+}
+
+/// `[budget.categories]`: daily USD caps for the tool categories in
+/// `costs::TOOL_CATEGORIES`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct McpCategoryLimitsConfig {
+    pub fetch: Option<f64>,
+    pub ai: Option<f64>,
+    pub engagement: Option<f64>,
+}
+
+impl McpCategoryLimitsConfig {
+    pub fn limit_usd(&self, category: &str) -> Option<f64> {
+        match category {
+            "fetch" => self.fetch,
+            "ai" => self.ai,
+            "engagement" => self.engagement,
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct McpPolicyConfig {
+    pub mode: Option<PolicyMode>,
+    /// `[policy.tools]`: policy mode a tool requires, replacing the built-in
+    /// requirement for the tools listed.
+    pub tools: HashMap<String, PolicyMode>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct McpPackageApiConfig {
+    pub base_url: Option<String>,
+    pub api_key: Option<String>,
+    pub workspace_id: Option<String>,
+    pub billing_upgrade_url: Option<String>,
+    pub max_concurrency: Option<usize>,
+    pub max_window_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct McpRateLimitConfig {
+    pub calls_per_minute: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct McpToolsConfig {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct McpTimeoutsConfig {
+    pub tool_seconds: Option<u64>,
+    pub package_api_seconds: Option<u64>,
+}
+
+impl McpConfig {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read MCP config {}", path.display()))?;
+        toml::from_str(&raw).with_context(|| format!("Invalid MCP config {}", path.display()))
+    }
+
+    /// Whether a tool survives the `[tools]` allow/deny lists. An empty allow
+    /// list permits every tool; deny always wins.
+    pub fn tool_enabled(&self, name: &str) -> bool {
+        if self.tools.deny.iter().any(|t| t == name) {
+            return false;
+        }
+        self.tools.allow.is_empty() || self.tools.allow.iter().any(|t| t == name)
+    }
+}
+
+fn model_rates_path(data_dir: &Path) -> PathBuf {
+    std::env::var("XINT_MODEL_RATES_FILE")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| data_dir.join("model-rates.json"))
+}
+
+/// Built-in rates, overlaid by the JSON rates file (if present), then by
+/// `XINT_MODEL_RATES`. A bad entry is reported in `errors` and skipped so
+/// the rest of the configuration still loads.
+fn load_model_rates(data_dir: &Path, errors: &mut Vec<String>) -> HashMap<String, ModelRate> {
+    let mut rates = costs::default_model_rates();
+
+    let path = model_rates_path(data_dir);
+    if path.exists() {
+        let file = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read model rates {}", path.display()))
+            .and_then(|raw| {
+                serde_json::from_str::<HashMap<String, serde_json::Value>>(&raw)
+                    .with_context(|| format!("invalid model rates {}", path.display()))
+            });
+        match file {
+            Ok(entries) => {
+                for (model, value) in entries {
+                    let rate = serde_json::from_value::<ModelRate>(value)
+                        .map_err(|e| e.to_string())
+                        .and_then(|rate| checked_rate(&model, rate));
+                    match rate {
+                        Ok(rate) => {
+                            rates.insert(model, rate);
+                        }
+                        Err(e) => errors.push(format!(
+                            "skipping rate for {model} in {}: {e}",
+                            path.display()
+                        )),
+                    }
+                }
+            }
+            Err(e) => errors.push(format!("{e:#}; using built-in rates")),
+        }
+    }
+    if let Ok(raw) = std::env::var("XINT_MODEL_RATES") {
+        let (parsed, skipped) = parse_model_rates(&raw);
+        rates.extend(parsed);
+        errors.extend(
+            skipped
+                .into_iter()
+                .map(|e| format!("skipping XINT_MODEL_RATES entry: {e}")),
+        );
+    }
+    rates
+}
+
+fn checked_rate(model: &str, rate: ModelRate) -> std::result::Result<ModelRate, String> {
+    let valid = |usd: f64| usd.is_finite() && usd >= 0.0;
+    if valid(rate.input_per_million) && valid(rate.output_per_million) {
+        Ok(rate)
+    } else {
+        Err(format!(
+            "token rates for {model} must be non-negative numbers"
+        ))
+    }
+}
+
+/// Parse `model=input:output` pairs (USD per million tokens), comma-separated.
+/// Returns the usable rates and a message for each entry that was skipped.
+fn parse_model_rates(raw: &str) -> (HashMap<String, ModelRate>, Vec<String>) {
+    let mut rates = HashMap::new();
+    let mut skipped = Vec::new();
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let parsed = entry.split_once('=').and_then(|(model, rates)| {
+            let (input, output) = rates.split_once(':')?;
+            Some((
+                model.trim().to_string(),
+                ModelRate {
+                    input_per_million: input.trim().parse().ok()?,
+                    output_per_million: output.trim().parse().ok()?,
+                },
+            ))
+        });
+        match parsed.ok_or_else(|| format!("expected model=input:output, got {entry:?}")) {
+            Ok((model, rate)) => match checked_rate(&model, rate) {
+                Ok(rate) => {
+                    rates.insert(model, rate);
+                }
+                Err(e) => skipped.push(e),
+            },
+            Err(e) => skipped.push(e),
+        }
+    }
+    (rates, skipped)
+}
+
 fn resolve_data_dir() -> PathBuf {
     // Try relative to binary
     if let Ok(exe) = std::env::current_exe() {
@@ -118,3 +365,120 @@ fn resolve_data_dir() -> PathBuf {
     // Default: create in current directory
     cwd_data
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_mcp_config_sections() {
+        let config: McpConfig = toml::from_str(
+            r#"
+            [budget]
+            enforce = false
+            daily_limit_usd = 2.5
+
+            [budget.categories]
+            ai = 0.5
+
+            [policy]
+            mode = "engagement"
+
+            [policy.tools]
+            xint_report = "engagement"
+
+            [package_api]
+            base_url = "http://localhost:8787/v1"
+
+            [rate_limits]
+            calls_per_minute = 10
+
+            [tools]
+            deny = ["xint_bookmarks"]
+
+            [timeouts]
+            tool_seconds = 20
+            "#,
+        )
+        .expect("parse config");
+
+        assert_eq!(config.budget.enforce, Some(false));
+        assert_eq!(config.budget.daily_limit_usd, Some(2.5));
+        assert_eq!(config.budget.categories.limit_usd("ai"), Some(0.5));
+        assert_eq!(config.budget.categories.limit_usd("fetch"), None);
+        assert_eq!(config.policy.mode, Some(PolicyMode::Engagement));
+        assert_eq!(
+            config.policy.tools.get("xint_report"),
+            Some(&PolicyMode::Engagement)
+        );
+        assert_eq!(
+            config.package_api.base_url.as_deref(),
+            Some("http://localhost:8787/v1")
+        );
+        assert_eq!(config.rate_limits.calls_per_minute, Some(10));
+        assert_eq!(config.timeouts.tool_seconds, Some(20));
+        assert!(config.tool_enabled("xint_search"));
+        assert!(!config.tool_enabled("xint_bookmarks"));
+    }
+
+    #[test]
+    fn parses_model_rate_overrides() {
+        let (rates, skipped) = parse_model_rates("grok-4=3:15, grok-5-mini = 0.2:0.8,");
+        assert_eq!(rates.len(), 2);
+        assert!(skipped.is_empty());
+        assert_eq!(
+            rates["grok-5-mini"],
+            ModelRate {
+                input_per_million: 0.2,
+                output_per_million: 0.8,
+            }
+        );
+        let (rates, skipped) = parse_model_rates("grok-4=3,grok-3=three:15,grok-2=-1:4,grok-5=1:2");
+        assert_eq!(rates.keys().collect::<Vec<_>>(), ["grok-5"]);
+        assert_eq!(skipped.len(), 3);
+        assert!(skipped[2].contains("non-negative"));
+
+        let mut partial = costs::default_model_rates();
+        partial.remove("grok-4");
+        assert_eq!(costs::unpriced_models(&partial), ["grok-4"]);
+        assert!(costs::unpriced_models(&costs::default_model_rates()).is_empty());
+    }
+
+    #[test]
+    fn bad_rate_file_entries_are_skipped_not_fatal() {
+        let data_dir = std::env::temp_dir().join("xint-rs-test-model-rates");
+        std::fs::create_dir_all(&data_dir).expect("data dir");
+        std::fs::write(
+            data_dir.join("model-rates.json"),
+            r#"{"grok-5": {"input_per_million": 1.0, "output_per_million": 2.0},
+                "grok-6": {"input_per_million": "cheap"},
+                "grok-3": {"input_per_million": -1.0, "output_per_million": 2.0}}"#,
+        )
+        .expect("write rates");
+
+        let mut errors = Vec::new();
+        let rates = load_model_rates(&data_dir, &mut errors);
+        assert_eq!(rates["grok-5"].output_per_million, 2.0);
+        assert!(!rates.contains_key("grok-6"));
+        assert_eq!(rates["grok-3"], costs::default_model_rates()["grok-3"]);
+        assert_eq!(errors.len(), 2);
+
+        std::fs::write(data_dir.join("model-rates.json"), "not json").expect("write rates");
+        let mut errors = Vec::new();
+        assert_eq!(
+            load_model_rates(&data_dir, &mut errors),
+            costs::default_model_rates()
+        );
+        assert_eq!(errors.len(), 1);
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+
+    #[test]
+    fn allow_list_restricts_tools() {
+        let config: McpConfig =
+            toml::from_str("[tools]\nallow = [\"xint_search\"]\n").expect("parse config");
+        assert!(config.tool_enabled("xint_search"));
+        assert!(!config.tool_enabled("xint_profile"));
+        assert!(toml::from_str::<McpConfig>("[unknown]\n").is_err());
+    }
+}
@@ -1,10 +1,177 @@
 use crate::models::*;
+use anyhow::{Context, Result};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 
 const RETENTION_DAYS: i64 = 30;
 
+tokio::task_local! {
+    /// X API charges ([`track_cost`]) made by the MCP tool call running on
+    /// this task.
+    /// Concurrent calls share the ledger, so each call sums its own charges
+    /// here instead of diffing the ledger total.
+    pub static CALL_FETCH_USD: std::sync::Arc<std::sync::Mutex<f64>>;
+}
+
+fn charge_current_call(cost_usd: f64) {
+    let _ = CALL_FETCH_USD.try_with(|total| {
+        *total
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) += cost_usd;
+    });
+}
+
+/// Grok model each AI tool uses when the caller does not pick one.
+pub const TOOL_DEFAULT_MODELS: &[(&str, &str)] = &[
+    ("xint_analyze", "grok-3-mini"),
+    ("xint_translate", "grok-3-mini"),
+    ("xint_sentiment", "grok-3-mini"),
+    ("xint_report", "grok-3-mini"),
+    ("xint_user_brief", "grok-3-mini"),
+    ("xint_trends_for", "grok-3-mini"),
+    ("xint_xsearch", "grok-4"),
+    ("xint_article", crate::commands::article::DEFAULT_MODEL),
+];
+
+/// Budget category of every budget-guarded MCP tool: `fetch` (X API reads
+/// with the app bearer token), `ai` (Grok completions) and `engagement`
+/// (calls made as the signed-in user). Category sub-limits apply by this
+/// table.
+pub const TOOL_CATEGORIES: &[(&str, &str)] = &[
+    ("xint_search", "fetch"),
+    ("xint_hashtag", "fetch"),
+    ("xint_profile", "fetch"),
+    ("xint_top", "fetch"),
+    ("xint_mentions", "fetch"),
+    ("xint_similar", "fetch"),
+    ("xint_engagement_rate", "fetch"),
+    ("xint_pinned", "fetch"),
+    ("xint_user_info", "fetch"),
+    ("xint_verify", "fetch"),
+    ("xint_thread", "fetch"),
+    ("xint_replies", "fetch"),
+    ("xint_retweeters", "fetch"),
+    ("xint_quotes", "fetch"),
+    ("xint_poll", "fetch"),
+    ("xint_media", "fetch"),
+    ("xint_tweet", "fetch"),
+    ("xint_spaces", "fetch"),
+    ("xint_list", "fetch"),
+    ("xint_trends", "fetch"),
+    ("xint_collections_list", "fetch"),
+    ("xint_collections_search", "fetch"),
+    ("xint_resolve_url", "fetch"),
+    ("xint_url_expand", "fetch"),
+    ("xint_watch", "fetch"),
+    ("xint_compare", "fetch"),
+    ("xint_package_create", "fetch"),
+    ("xint_package_query", "fetch"),
+    ("xint_package_refresh", "fetch"),
+    ("xint_package_search", "fetch"),
+    ("xint_package_publish", "fetch"),
+    ("xint_xsearch", "ai"),
+    ("xint_analyze", "ai"),
+    ("xint_translate", "ai"),
+    ("xint_article", "ai"),
+    ("xint_user_brief", "ai"),
+    ("xint_report", "ai"),
+    ("xint_sentiment", "ai"),
+    ("xint_trends_for", "ai"),
+    ("xint_bookmarks", "engagement"),
+    ("xint_following", "engagement"),
+    ("xint_followers", "engagement"),
+    ("xint_diff", "engagement"),
+    ("xint_mutuals", "engagement"),
+];
+
+pub fn tool_category(tool: &str) -> Option<&'static str> {
+    TOOL_CATEGORIES
+        .iter()
+        .find(|(name, _)| *name == tool)
+        .map(|(_, category)| *category)
+}
+
+/// Rates installed from `Config` at startup; `None` means the built-in table.
+static MODEL_RATES: RwLock<Option<HashMap<String, ModelRate>>> = RwLock::new(None);
+
+/// Built-in Grok prices, overridable through `Config::model_rates`.
+pub fn default_model_rates() -> HashMap<String, ModelRate> {
+    [
+        ("grok-4", 3.00, 15.00),
+        ("grok-3", 3.00, 15.00),
+        ("grok-3-mini", 0.10, 0.40),
+        ("grok-2", 2.00, 10.00),
+    ]
+    .into_iter()
+    .map(|(model, input, output)| {
+        (
+            model.to_string(),
+            ModelRate {
+                input_per_million: input,
+                output_per_million: output,
+            },
+        )
+    })
+    .collect()
+}
+
+/// Replace the rate table used by [`token_cost_usd`] and [`record`].
+pub fn install_model_rates(rates: HashMap<String, ModelRate>) {
+    *MODEL_RATES
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(rates);
+}
+
+/// Configured rate for `model`, if it has one.
+pub fn model_rate(model: &str) -> Option<ModelRate> {
+    let installed = MODEL_RATES
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match installed.as_ref() {
+        Some(rates) => rates.get(model).copied(),
+        None => default_model_rates().get(model).copied(),
+    }
+}
+
+/// Cost in USD of a completion with the given token usage, or `None` when
+/// `model` has no configured rate.
+pub fn token_cost_usd(model: &str, prompt_tokens: u64, completion_tokens: u64) -> Option<f64> {
+    let rate = model_rate(model)?;
+    Some(
+        (prompt_tokens as f64 / 1_000_000.0) * rate.input_per_million
+            + (completion_tokens as f64 / 1_000_000.0) * rate.output_per_million,
+    )
+}
+
+/// Grok model a tool call will use: the caller's `model` argument, else the
+/// tool's default. `None` for tools that make no Grok calls.
+pub fn tool_model(tool: &str, args: &serde_json::Value) -> Option<String> {
+    let default = TOOL_DEFAULT_MODELS
+        .iter()
+        .find(|(name, _)| *name == tool)
+        .map(|(_, model)| *model)?;
+    Some(
+        args.get("model")
+            .and_then(|v| v.as_str())
+            .unwrap_or(default)
+            .to_string(),
+    )
+}
+
+/// Tool default models that `rates` has no price for, sorted and deduplicated.
+pub fn unpriced_models(rates: &HashMap<String, ModelRate>) -> Vec<&'static str> {
+    let mut missing: Vec<&'static str> = TOOL_DEFAULT_MODELS
+        .iter()
+        .map(|(_, model)| *model)
+        .filter(|model| !rates.contains_key(*model))
+        .collect();
+    missing.sort_unstable();
+    missing.dedup();
+    missing
+}
+
 pub fn cost_rate(operation: &str) -> (f64, f64) {
     // (per_tweet, per_call)
     match operation {
@@ -12,6 +179,8 @@ pub fn cost_rate(operation: &str) -> (f64, f64) {
         "search_archive" => (0.01, 0.0),
         "bookmarks" => (0.005, 0.0),
         "likes" => (0.005, 0.0),
+        "retweeted_by" => (0.005, 0.0),
+        "list_tweets" => (0.005, 0.0),
         "like" | "unlike" | "follow" | "unfollow" => (0.0, 0.01),
         "following" => (0.0, 0.005),
         "media_metadata" => (0.005, 0.0),
@@ -21,6 +190,7 @@ pub fn cost_rate(operation: &str) -> (f64, f64) {
         "profile" => (0.005, 0.0),
         "tweet" => (0.005, 0.0),
         "trends" => (0.0, 0.10),
+        "spaces" => (0.0, 0.01),
         "thread" => (0.005, 0.0),
         "followers" | "following_list" => (0.0, 0.01),
         "lists_list" | "lists_create" | "lists_update" | "lists_delete" => (0.0, 0.01),
@@ -31,6 +201,264 @@ pub fn cost_rate(operation: &str) -> (f64, f64) {
     }
 }
 
+/// Flat allowance for the Grok completion(s) an AI tool makes; the real
+/// charge depends on token usage and is only known after the call.
+const GROK_CALL_ESTIMATE_USD: f64 = 0.002;
+/// Bounds for one Grok completion: a short prompt on a mini model, and a
+/// long one on a full-size model.
+const GROK_CALL_LOW_USD: f64 = 0.0005;
+const GROK_CALL_HIGH_USD: f64 = 0.01;
+
+/// What one MCP tool call bills: the X API operation, how many items its
+/// arguments ask for, and how many Grok completions it makes. `None` for
+/// tools that make no paid calls.
+fn billing_plan(tool: &str, args: &serde_json::Value) -> Option<(&'static str, u64, u64)> {
+    let arg = |key: &str, default: u64| args.get(key).and_then(|v| v.as_u64()).unwrap_or(default);
+    Some(match tool {
+        "xint_search" => ("search", arg("limit", 15), 0),
+        "xint_hashtag" | "xint_mentions" | "xint_replies" | "xint_quotes" | "xint_media"
+        | "xint_watch" => ("search", arg("limit", 20), 0),
+        "xint_list" => ("list_tweets", arg("limit", 20), 0),
+        "xint_similar" => ("search", 200, 0),
+        "xint_thread" => ("thread", arg("pages", 1) * 100, 0),
+        "xint_profile" => ("profile", arg("count", 20) + 1, 0),
+        "xint_top" => ("profile", (arg("count", 10) * 5).clamp(50, 200) + 1, 0),
+        "xint_engagement_rate" => ("profile", arg("count", 50) + 1, 0),
+        "xint_compare" => ("profile", 2 * (arg("count", 20) + 1), 0),
+        "xint_user_brief" => ("profile", arg("count", 20) + 1, 1),
+        "xint_user_info" | "xint_verify" => ("profile", 1, 0),
+        "xint_pinned" => ("profile", 2, 0),
+        "xint_tweet" | "xint_poll" => ("tweet", 1, 0),
+        // Redirect hops go to third-party hosts, which X does not bill.
+        "xint_resolve_url" => ("", 0, 0),
+        "xint_url_expand" => ("tweet", u64::from(args.get("tweet_id").is_some()), 0),
+        "xint_translate" => ("tweet", u64::from(args.get("tweet_id").is_some()), 1),
+        "xint_retweeters" => ("retweeted_by", arg("limit", 100), 0),
+        "xint_bookmarks" => ("bookmarks", arg("limit", 20), 0),
+        "xint_following" => ("following", arg("limit", 100), 0),
+        "xint_followers" => ("followers", arg("limit", 100), 0),
+        "xint_diff" => ("followers", arg("pages", 5) * 1000, 0),
+        "xint_mutuals" => ("following", 2 * arg("pages", 2) * 1000, 0),
+        "xint_trends" => ("trends", 0, 0),
+        "xint_trends_for" => ("trends", 0, 1),
+        "xint_spaces" => ("spaces", 0, 0),
+        "xint_report" => {
+            let sentiment = args.get("sentiment").and_then(|v| v.as_bool()) == Some(true);
+            ("search", arg("pages", 2) * 100, 1 + u64::from(sentiment))
+        }
+        "xint_analyze" | "xint_xsearch" | "xint_sentiment" => ("", 0, 1),
+        "xint_article" => ("", 0, u64::from(args.get("ai_prompt").is_some())),
+        _ => return None,
+    })
+}
+
+/// USD for `plan` when `billed_units` of its items are billed and each
+/// Grok completion costs `grok_usd`.
+fn plan_cost(plan: (&str, u64, u64), billed_units: u64, grok_usd: f64) -> f64 {
+    let (operation, _, grok_calls) = plan;
+    let (per_tweet, per_call) = if operation.is_empty() {
+        (0.0, 0.0)
+    } else {
+        cost_rate(operation)
+    };
+    let usd = per_call + per_tweet * billed_units as f64 + grok_usd * grok_calls as f64;
+    (usd * 1e6).round() / 1e6
+}
+
+/// Projected USD cost of one MCP tool call, assuming every requested item
+/// is returned. Tools that make no paid calls estimate to zero.
+pub fn estimate(tool: &str, args: &serde_json::Value) -> f64 {
+    billing_plan(tool, args)
+        .map(|plan| plan_cost(plan, plan.1, GROK_CALL_ESTIMATE_USD))
+        .unwrap_or(0.0)
+}
+
+/// Lowest and highest plausible cost of one tool call: nothing matched and
+/// cheap completions, versus every requested item billed and expensive
+/// completions. Pure arithmetic; nothing is fetched or recorded.
+pub fn estimate_range(tool: &str, args: &serde_json::Value) -> (f64, f64) {
+    billing_plan(tool, args)
+        .map(|plan| {
+            (
+                plan_cost(plan, 0, GROK_CALL_LOW_USD),
+                plan_cost(plan, plan.1, GROK_CALL_HIGH_USD),
+            )
+        })
+        .unwrap_or((0.0, 0.0))
+}
+
+/// Price one call and append it to the ledger under `costs_path` so spend
+/// can later be broken down by tool. Grok completions in `usage` are priced
+/// from their token counts at the configured model rates; `fetch_usd` is
+/// what the call's X API requests were charged at the per-operation rates.
+/// A call with no completions costs exactly its fetch charges. Completions
+/// on a model with no rate add nothing and are listed as unpriced instead.
+/// `model` is reported when `usage` names none. Returns the call's cost
+/// next to where it leaves `budget`.
+pub fn record(
+    costs_path: &Path,
+    tool: &str,
+    model: Option<&str>,
+    estimated_usd: f64,
+    fetch_usd: f64,
+    usage: &[TokenUsage],
+    budget: &BudgetStatus,
+) -> CallCost {
+    let round = |usd: f64| (usd * 1e6).round() / 1e6;
+    let mut token_usd = 0.0;
+    let mut unpriced_models: Vec<String> = Vec::new();
+    for u in usage {
+        match token_cost_usd(&u.model, u.input_tokens, u.output_tokens) {
+            Some(usd) => token_usd += usd,
+            None if !unpriced_models.contains(&u.model) => unpriced_models.push(u.model.clone()),
+            None => {}
+        }
+    }
+    let actual_usd = round(fetch_usd.max(0.0) + token_usd);
+    let model = usage
+        .first()
+        .map(|u| u.model.as_str())
+        .or(model)
+        .map(ToString::to_string);
+    let (input_tokens, output_tokens) = if usage.is_empty() {
+        (None, None)
+    } else {
+        (
+            Some(usage.iter().map(|u| u.input_tokens).sum()),
+            Some(usage.iter().map(|u| u.output_tokens).sum()),
+        )
+    };
+    {
+        let _lock = write_lock();
+        let mut data = load_data(costs_path);
+        data.calls.push(CallRecord {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            tool: tool.to_string(),
+            model: model.clone(),
+            input_tokens,
+            output_tokens,
+            usd: actual_usd,
+            unpriced_models: unpriced_models.clone(),
+        });
+        prune_entries(&mut data);
+        save_data(costs_path, &data);
+    }
+    CallCost {
+        tool: tool.to_string(),
+        rate: model.as_deref().and_then(model_rate),
+        model,
+        input_tokens,
+        output_tokens,
+        estimated_usd: round(estimated_usd),
+        actual_usd,
+        unpriced_models,
+        daily_spent_usd: budget.spent,
+        daily_limit_usd: budget.limit,
+        daily_remaining_usd: budget.remaining,
+    }
+}
+
+/// Serializes read-modify-write cycles on the costs file; concurrent MCP
+/// tool calls would otherwise overwrite each other's entries.
+static WRITE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+fn write_lock() -> std::sync::MutexGuard<'static, ()> {
+    WRITE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// USD reserved by in-flight calls, per costs file. Reservations are only
+/// read and changed while holding the write lock, so a reservation check
+/// and the ledger it reads cannot interleave with another call's charge.
+static RESERVATIONS: std::sync::Mutex<Vec<(PathBuf, f64)>> = std::sync::Mutex::new(Vec::new());
+
+fn reservations() -> std::sync::MutexGuard<'static, Vec<(PathBuf, f64)>> {
+    RESERVATIONS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Estimated cost held against the daily budget while a call runs.
+/// Dropping it (or calling [`Reservation::settle`]) releases the hold; by
+/// then the call's actual charges are in the ledger.
+#[derive(Debug)]
+pub struct Reservation {
+    path: PathBuf,
+    usd: f64,
+}
+
+impl Reservation {
+    /// Release the hold once the call's actual cost has been tracked.
+    pub fn settle(self) {}
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        let _lock = write_lock();
+        let mut held = reservations();
+        if let Some(pos) = held
+            .iter()
+            .position(|(path, usd)| *path == self.path && *usd == self.usd)
+        {
+            held.swap_remove(pos);
+        }
+    }
+}
+
+/// Why a reservation was refused: today's recorded spend and what in-flight
+/// calls already hold left nothing of the limit for this call's estimate.
+#[derive(Debug, Clone, Copy)]
+pub struct ReservationDenied {
+    pub spent: f64,
+    pub reserved: f64,
+    pub estimated: f64,
+    pub limit: f64,
+}
+
+/// Atomically reserve `estimated_usd` against the daily limit in `budget`.
+/// Succeeds while recorded spend plus outstanding reservations is below the
+/// limit. Estimates assume every requested item is billed, so the hold is
+/// capped at what remains: a large request is not refused on a budget it
+/// would most likely fit. This overshoot is intended: one admitted call may
+/// take spend past the limit by up to its estimate minus the remainder, and
+/// every later call is refused until the day rolls over. `None` when the
+/// budget is disabled.
+pub fn reserve(
+    costs_path: &Path,
+    estimated_usd: f64,
+    budget: &BudgetStatus,
+) -> Result<Option<Reservation>, ReservationDenied> {
+    if !budget.enabled {
+        return Ok(None);
+    }
+    let daily_limit_usd = budget.limit;
+    let _lock = write_lock();
+    let spent = check_budget(costs_path).spent;
+    let mut held = reservations();
+    let reserved: f64 = held
+        .iter()
+        .filter(|(path, _)| path == costs_path)
+        .map(|(_, usd)| usd)
+        .sum();
+    let estimated = estimated_usd.max(0.0);
+    let committed = spent + reserved;
+    if committed >= daily_limit_usd - 1e-9 {
+        return Err(ReservationDenied {
+            spent,
+            reserved: (reserved * 1e6).round() / 1e6,
+            estimated,
+            limit: daily_limit_usd,
+        });
+    }
+    let usd = estimated.min(daily_limit_usd - committed);
+    held.push((costs_path.to_path_buf(), usd));
+    Ok(Some(Reservation {
+        path: costs_path.to_path_buf(),
+        usd,
+    }))
+}
+
 fn load_data(path: &Path) -> CostData {
     if !path.exists() {
         return CostData::default();
@@ -63,6 +491,8 @@ fn prune_entries(data: &mut CostData) {
 
     data.entries
         .retain(|e| e.timestamp.as_str() >= cutoff.as_str());
+    data.calls
+        .retain(|c| c.timestamp.as_str() >= cutoff.as_str());
     data.daily.retain(|d| d.date.as_str() >= cutoff_day);
 }
 
@@ -75,6 +505,40 @@ pub fn track_cost(
 ) -> CostEntry {
     let (per_tweet, per_call) = cost_rate(operation);
     let cost_usd = per_call + per_tweet * tweets_read as f64;
+    let entry = record_entry(costs_path, operation, endpoint, tweets_read, cost_usd);
+    charge_current_call(entry.cost_usd);
+    entry
+}
+
+/// Track a Grok completion, priced from its token usage at the configured
+/// rate for its model. A model with no rate is tracked at no cost; the call
+/// record lists it as unpriced.
+pub fn track_token_cost(costs_path: &Path, operation: &str, usage: &TokenUsage) -> CostEntry {
+    track_usd_cost(
+        costs_path,
+        operation,
+        "/v1/chat/completions",
+        token_cost_usd(&usage.model, usage.input_tokens, usage.output_tokens).unwrap_or(0.0),
+    )
+}
+
+/// Track a call whose cost is already known in USD.
+pub fn track_usd_cost(
+    costs_path: &Path,
+    operation: &str,
+    endpoint: &str,
+    cost_usd: f64,
+) -> CostEntry {
+    record_entry(costs_path, operation, endpoint, 0, cost_usd)
+}
+
+fn record_entry(
+    costs_path: &Path,
+    operation: &str,
+    endpoint: &str,
+    tweets_read: u64,
+    cost_usd: f64,
+) -> CostEntry {
     let cost_usd = (cost_usd * 1e6).round() / 1e6;
 
     let entry = CostEntry {
@@ -85,6 +549,7 @@ pub fn track_cost(
         cost_usd,
     };
 
+    let _lock = write_lock();
     let mut data = load_data(costs_path);
     data.entries.push(entry.clone());
     data.total_lifetime_usd = ((data.total_lifetime_usd + cost_usd) * 1e6).round() / 1e6;
@@ -133,40 +598,137 @@ pub fn track_cost(
     entry
 }
 
+/// Process exit code for a CLI command stopped by the daily budget.
+pub const EXIT_BUDGET_EXCEEDED: i32 = 3;
+
+/// Error for a CLI command stopped by the daily budget; `main` maps it to
+/// [`EXIT_BUDGET_EXCEEDED`].
+#[derive(Debug, Clone, Copy)]
+pub struct BudgetExceeded {
+    pub spent: f64,
+    pub limit: f64,
+}
+
+impl std::fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Daily budget exceeded (${:.2}/${:.2})",
+            self.spent, self.limit
+        )
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+/// `Err(BudgetExceeded)` once today's spend has reached the daily limit.
+pub fn ensure_within_budget(costs_path: &Path) -> std::result::Result<(), BudgetExceeded> {
+    let budget = check_budget(costs_path);
+    if budget.allowed {
+        return Ok(());
+    }
+    Err(BudgetExceeded {
+        spent: budget.spent,
+        limit: budget.limit,
+    })
+}
+
 /// Check if today's spend is within budget.
 pub fn check_budget(costs_path: &Path) -> BudgetStatus {
     let data = load_data(costs_path);
     let today = today_str();
     let today_agg = data.daily.iter().find(|d| d.date == today);
     let spent = today_agg.map(|a| a.total_cost).unwrap_or(0.0);
-    let limit = data.budget.daily_limit_usd;
+    budget_status(
+        data.budget.enabled,
+        spent,
+        data.budget.daily_limit_usd,
+        data.budget.warn_threshold,
+    )
+}
+
+/// Budget status for `spent` against `limit`. Calls are denied only once
+/// spend reaches the limit; `warning` turns on earlier, at `warn_threshold`
+/// (a fraction of the limit).
+pub fn budget_status(enabled: bool, spent: f64, limit: f64, warn_threshold: f64) -> BudgetStatus {
     let remaining = (limit - spent).max(0.0);
-    let warning = data.budget.enabled && spent >= limit * data.budget.warn_threshold;
-    let allowed = !data.budget.enabled || spent < limit;
+    let percent_used = if limit > 0.0 {
+        (spent / limit * 1000.0).round() / 10.0
+    } else if spent > 0.0 {
+        100.0
+    } else {
+        0.0
+    };
 
     BudgetStatus {
-        allowed,
+        enabled,
+        allowed: !enabled || spent < limit,
         spent: (spent * 1e4).round() / 1e4,
         limit,
         remaining: (remaining * 1e4).round() / 1e4,
-        warning,
+        warning: enabled && spent >= limit * warn_threshold,
+        percent_used,
+        warn_threshold,
+    }
+}
+
+/// Weekly and monthly limits stored in the costs file, if any.
+pub fn stored_window_limits(costs_path: &Path) -> (Option<f64>, Option<f64>) {
+    let budget = load_data(costs_path).budget;
+    (budget.weekly_limit_usd, budget.monthly_limit_usd)
+}
+
+/// Spend over a rolling `weekly` (7-day) or `monthly` (30-day) window
+/// against `limit`. Any other window name is treated as today.
+pub fn window_status(costs_path: &Path, window: &str, limit: f64) -> BudgetWindow {
+    let period = match window {
+        "weekly" => "week",
+        "monthly" => "month",
+        _ => "today",
+    };
+    let spent = period_costs(costs_path, period).total_cost;
+    let status = budget_status(true, spent, limit, 1.0);
+    BudgetWindow {
+        window: window.to_string(),
+        spent: status.spent,
+        limit,
+        remaining: status.remaining,
+        percent_used: status.percent_used,
+        allowed: status.allowed,
     }
 }
 
 /// Set the daily budget limit.
 pub fn set_budget(costs_path: &Path, limit_usd: f64) {
+    let _lock = write_lock();
     let mut data = load_data(costs_path);
     data.budget.daily_limit_usd = limit_usd;
     save_data(costs_path, &data);
 }
 
-/// Reset today's cost data.
-pub fn reset_today(costs_path: &Path) {
+/// Clear the ledger for `period` (`today`, `week`, `month` or `all`) and
+/// return the totals that were (or, with `apply` false, would be) removed.
+/// Resetting `all` also zeroes the lifetime total. The file is replaced
+/// atomically.
+pub fn reset_period(costs_path: &Path, period: &str, apply: bool) -> PeriodCosts {
+    let _lock = write_lock();
+    let cleared = period_costs(costs_path, period);
+    if !apply {
+        return cleared;
+    }
+
     let mut data = load_data(costs_path);
-    let today = today_str();
-    data.entries.retain(|e| e.timestamp[..10] != today);
-    data.daily.retain(|d| d.date != today);
+    let start_date = cleared.start_date.as_str();
+    data.entries
+        .retain(|e| e.timestamp.get(..10).is_some_and(|day| day < start_date));
+    data.calls
+        .retain(|c| c.timestamp.get(..10).is_some_and(|day| day < start_date));
+    data.daily.retain(|d| d.date.as_str() < start_date);
+    if period == "all" {
+        data.total_lifetime_usd = 0.0;
+    }
     save_data(costs_path, &data);
+    cleared
 }
 
 /// Return today's aggregate costs.
@@ -216,20 +778,7 @@ pub fn get_cost_summary(costs_path: &Path, period: &str) -> String {
             today, agg.total_cost, data.budget.daily_limit_usd, agg.calls, agg.tweets_read
         );
 
-        if !agg.by_operation.is_empty() {
-            out.push_str("\n  By operation:\n");
-            let mut ops: Vec<_> = agg.by_operation.iter().collect();
-            ops.sort_by(|a, b| b.1.cost.total_cmp(&a.1.cost));
-            for (op, stats) in ops {
-                out.push_str(&format!(
-                    "    {:<16} {:>3} calls, {:>5} tweets, ${:>6.2}\n",
-                    format!("{}:", op),
-                    stats.calls,
-                    stats.tweets,
-                    stats.cost
-                ));
-            }
-        }
+        push_by_operation(&mut out, &agg.by_operation);
 
         out.push_str(&format!(
             "\n  Budget: {}% used ({}% remaining)",
@@ -239,24 +788,9 @@ pub fn get_cost_summary(costs_path: &Path, period: &str) -> String {
         return out;
     }
 
-    let (start_date, label) = match period {
-        "week" => {
-            let d = chrono::Utc::now() - chrono::Duration::days(6);
-            (d.format("%Y-%m-%d").to_string(), "Last 7 Days")
-        }
-        "month" => {
-            let d = chrono::Utc::now() - chrono::Duration::days(29);
-            (d.format("%Y-%m-%d").to_string(), "Last 30 Days")
-        }
-        _ => (String::new(), "All Time"),
-    };
-
-    let days: Vec<_> = data
-        .daily
-        .iter()
-        .filter(|d| d.date.as_str() >= start_date.as_str())
-        .cloned()
-        .collect();
+    let (start_date, label) = period_start(period);
+    let days = days_since(&data, &start_date);
+    let by_operation = merge_operations(&days);
 
     let total_cost: f64 = days.iter().map(|d| d.total_cost).sum();
     let total_calls: u64 = days.iter().map(|d| d.calls).sum();
@@ -265,6 +799,7 @@ pub fn get_cost_summary(costs_path: &Path, period: &str) -> String {
     let mut out = format!(
         "\u{1f4ca} API Costs \u{2014} {label}\n\n  Total: ${total_cost:.2} | Calls: {total_calls} | Tweets read: {total_tweets}\n"
     );
+    push_by_operation(&mut out, &by_operation);
 
     if period == "all" {
         out.push_str(&format!(
@@ -295,3 +830,203 @@ pub fn get_cost_summary(costs_path: &Path, period: &str) -> String {
 
     out
 }
+
+/// First day included in `period` and its display label. Unknown periods
+/// cover all retained history.
+fn period_start(period: &str) -> (String, &'static str) {
+    let days_back = |days: i64| {
+        (chrono::Utc::now() - chrono::Duration::days(days))
+            .format("%Y-%m-%d")
+            .to_string()
+    };
+    match period {
+        "today" => (today_str(), "Today"),
+        "week" => (days_back(6), "Last 7 Days"),
+        "month" => (days_back(29), "Last 30 Days"),
+        _ => (String::new(), "All Time"),
+    }
+}
+
+fn days_since(data: &CostData, start_date: &str) -> Vec<DailyAggregate> {
+    data.daily
+        .iter()
+        .filter(|d| d.date.as_str() >= start_date)
+        .cloned()
+        .collect()
+}
+
+fn merge_operations(days: &[DailyAggregate]) -> HashMap<String, OperationStats> {
+    let mut merged: HashMap<String, OperationStats> = HashMap::new();
+    for (op, stats) in days.iter().flat_map(|d| d.by_operation.iter()) {
+        let entry = merged.entry(op.clone()).or_insert(OperationStats {
+            calls: 0,
+            cost: 0.0,
+            tweets: 0,
+        });
+        entry.calls += stats.calls;
+        entry.cost += stats.cost;
+        entry.tweets += stats.tweets;
+    }
+    merged
+}
+
+fn push_by_operation(out: &mut String, by_operation: &HashMap<String, OperationStats>) {
+    if by_operation.is_empty() {
+        return;
+    }
+    out.push_str("\n  By operation:\n");
+    let mut ops: Vec<_> = by_operation.iter().collect();
+    ops.sort_by(|a, b| b.1.cost.total_cmp(&a.1.cost));
+    for (op, stats) in ops {
+        out.push_str(&format!(
+            "    {:<16} {:>3} calls, {:>5} tweets, ${:>6.2}\n",
+            format!("{}:", op),
+            stats.calls,
+            stats.tweets,
+            stats.cost
+        ));
+    }
+}
+
+/// Spend for `period` (`today`, `week`, `month` or `all`) with per-operation
+/// subtotals merged across days.
+pub fn period_costs(costs_path: &Path, period: &str) -> PeriodCosts {
+    let data = load_data(costs_path);
+    let (start_date, _) = period_start(period);
+    let days = days_since(&data, &start_date);
+    let round = |usd: f64| (usd * 1e6).round() / 1e6;
+    let mut by_operation = merge_operations(&days);
+    for stats in by_operation.values_mut() {
+        stats.cost = round(stats.cost);
+    }
+
+    PeriodCosts {
+        period: period.to_string(),
+        start_date,
+        total_cost: round(days.iter().map(|d| d.total_cost).sum()),
+        calls: days.iter().map(|d| d.calls).sum(),
+        tweets_read: days.iter().map(|d| d.tweets_read).sum(),
+        by_operation,
+    }
+}
+
+/// MCP spend for `period` (`today`, `week`, `month` or `all`) summed by
+/// tool name, from the per-call ledger.
+pub fn breakdown(costs_path: &Path, period: &str) -> HashMap<String, f64> {
+    let data = load_data(costs_path);
+    let (start_date, _) = period_start(period);
+    let mut by_tool: HashMap<String, f64> = HashMap::new();
+    for call in data.calls.iter().filter(|c| {
+        c.timestamp
+            .get(..10)
+            .is_some_and(|day| day >= start_date.as_str())
+    }) {
+        *by_tool.entry(call.tool.clone()).or_default() += call.usd;
+    }
+    for usd in by_tool.values_mut() {
+        *usd = (*usd * 1e6).round() / 1e6;
+    }
+    by_tool
+}
+
+/// Today's MCP spend on tools in `category`, from the per-call ledger.
+pub fn category_spent(costs_path: &Path, category: &str) -> f64 {
+    breakdown(costs_path, "today")
+        .into_iter()
+        .filter(|(tool, _)| tool_category(tool) == Some(category))
+        .map(|(_, usd)| usd)
+        .sum()
+}
+
+/// Write the per-call ledger for `period` to `out` as CSV with columns
+/// `timestamp, tool, model, tokens, usd`, returning the number of rows.
+pub fn export_csv(costs_path: &Path, period: &str, out: &Path) -> Result<usize> {
+    let data = load_data(costs_path);
+    let (start_date, _) = period_start(period);
+    let calls: Vec<&CallRecord> = data
+        .calls
+        .iter()
+        .filter(|c| {
+            c.timestamp
+                .get(..10)
+                .is_some_and(|day| day >= start_date.as_str())
+        })
+        .collect();
+
+    if let Some(parent) = out.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut writer = csv::Writer::from_path(out)
+        .with_context(|| format!("Failed to create {}", out.display()))?;
+    writer.write_record(["timestamp", "tool", "model", "tokens", "usd", "unpriced"])?;
+    for call in &calls {
+        writer.write_record([
+            call.timestamp.clone(),
+            call.tool.clone(),
+            call.model.clone().unwrap_or_default(),
+            call.input_tokens
+                .zip(call.output_tokens)
+                .map(|(input, output)| (input + output).to_string())
+                .unwrap_or_default(),
+            call.usd.to_string(),
+            call.unpriced_models.join(";"),
+        ])?;
+    }
+    writer
+        .flush()
+        .with_context(|| format!("Failed to write {}", out.display()))?;
+    Ok(calls.len())
+}
+
+/// Trailing window the burn rate is measured over.
+const FORECAST_WINDOW_HOURS: i64 = 24;
+
+/// Project when `budget`'s daily limit will be reached if spending continues
+/// at the rate of the trailing 24 hours. The rate is measured from the first
+/// charge in the window (but over at least an hour), so a burst right after
+/// start-up is not diluted across a full day.
+pub fn forecast(costs_path: &Path, budget: &BudgetStatus) -> BudgetForecast {
+    let data = load_data(costs_path);
+    let now = chrono::Utc::now();
+    let cutoff = now - chrono::Duration::hours(FORECAST_WINDOW_HOURS);
+    let charges: Vec<(chrono::DateTime<chrono::Utc>, f64)> = data
+        .entries
+        .iter()
+        .filter(|e| e.cost_usd > 0.0)
+        .filter_map(|e| {
+            let at = chrono::DateTime::parse_from_rfc3339(&e.timestamp).ok()?;
+            Some((at.with_timezone(&chrono::Utc), e.cost_usd))
+        })
+        .filter(|(at, _)| *at >= cutoff)
+        .collect();
+
+    let window_spent: f64 = charges.iter().map(|(_, usd)| usd).sum();
+    let window_hours = charges
+        .iter()
+        .map(|(at, _)| *at)
+        .min()
+        .map(|first| ((now - first).num_seconds() as f64 / 3600.0).max(1.0))
+        .unwrap_or(FORECAST_WINDOW_HOURS as f64);
+    let usd_per_hour = window_spent / window_hours;
+
+    let tomorrow = (now + chrono::Duration::days(1))
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .map(|midnight| midnight.and_utc())
+        .unwrap_or(now);
+    let hours_until_reset = (tomorrow - now).num_seconds() as f64 / 3600.0;
+    let hours_until_limit = (usd_per_hour > 0.0).then(|| budget.remaining / usd_per_hour);
+
+    let round2 = |v: f64| (v * 100.0).round() / 100.0;
+    BudgetForecast {
+        window_hours: round2(window_hours),
+        window_spent_usd: (window_spent * 1e6).round() / 1e6,
+        usd_per_hour: (usd_per_hour * 1e6).round() / 1e6,
+        remaining_usd: budget.remaining,
+        hours_until_limit: hours_until_limit.map(round2),
+        hours_until_reset: round2(hours_until_reset),
+        limit_before_reset: budget.enabled
+            && hours_until_limit.is_some_and(|hours| hours < hours_until_reset),
+    }
+}
@@ -181,6 +181,58 @@ pub fn format_profile_terminal(user: &serde_json::Value, tweets: &[Tweet]) -> St
     out
 }
 
+// ---------------------------------------------------------------------------
+// Thread outline
+// ---------------------------------------------------------------------------
+
+/// Render a conversation as an indented reply tree: each reply sits one
+/// level below the tweet it answers, siblings in posting order. Replies whose
+/// parent was not fetched hang off the conversation root.
+pub fn format_thread_outline(tweets: &[Tweet]) -> String {
+    let Some(root) = tweets
+        .iter()
+        .find(|t| t.id == t.conversation_id)
+        .or(tweets.first())
+    else {
+        return String::new();
+    };
+    let ids: std::collections::HashSet<&str> = tweets.iter().map(|t| t.id.as_str()).collect();
+
+    let mut children: std::collections::HashMap<&str, Vec<&Tweet>> =
+        std::collections::HashMap::new();
+    for tweet in tweets.iter().filter(|t| t.id != root.id) {
+        let parent = tweet
+            .in_reply_to_id
+            .as_deref()
+            .filter(|id| ids.contains(id) && *id != tweet.id)
+            .unwrap_or(root.id.as_str());
+        children.entry(parent).or_default().push(tweet);
+    }
+    for replies in children.values_mut() {
+        replies.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    }
+
+    let mut out = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    let mut stack = vec![(root, 0usize)];
+    while let Some((tweet, depth)) = stack.pop() {
+        if !seen.insert(tweet.id.as_str()) {
+            continue;
+        }
+        let text = clean_tco(&tweet.text).replace('\n', " ");
+        out.push(format!(
+            "{}- @{}: {}",
+            "  ".repeat(depth),
+            tweet.username,
+            text
+        ));
+        if let Some(replies) = children.get(tweet.id.as_str()) {
+            stack.extend(replies.iter().rev().map(|reply| (*reply, depth + 1)));
+        }
+    }
+    out.join("\n")
+}
+
 // ---------------------------------------------------------------------------
 // Markdown
 // ---------------------------------------------------------------------------
@@ -288,3 +340,58 @@ pub fn format_csv(tweets: &[Tweet]) -> String {
         .collect::<Vec<_>>()
         .join("\n")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::format_thread_outline;
+    use crate::models::{Tweet, TweetMetrics};
+
+    fn reply(id: &str, parent: Option<&str>, username: &str, minute: u32) -> Tweet {
+        Tweet {
+            id: id.to_string(),
+            text: format!("tweet {id}"),
+            author_id: username.to_string(),
+            username: username.to_string(),
+            name: username.to_string(),
+            created_at: format!("2026-02-19T00:{minute:02}:00Z"),
+            conversation_id: "1".to_string(),
+            in_reply_to_id: parent.map(str::to_string),
+            metrics: TweetMetrics {
+                likes: 0,
+                retweets: 0,
+                replies: 0,
+                quotes: 0,
+                impressions: 0,
+                bookmarks: 0,
+            },
+            urls: vec![],
+            mentions: vec![],
+            hashtags: vec![],
+            tweet_url: format!("https://x.com/{username}/status/{id}"),
+        }
+    }
+
+    #[test]
+    fn thread_outline_nests_replies_under_their_parent() {
+        // Newest first, as recency search returns them.
+        let tweets = vec![
+            reply("5", Some("404"), "dave", 5),
+            reply("4", Some("2"), "alice", 4),
+            reply("3", Some("1"), "carol", 3),
+            reply("2", Some("1"), "bob", 2),
+            reply("1", None, "alice", 0),
+        ];
+        assert_eq!(
+            format_thread_outline(&tweets),
+            [
+                "- @alice: tweet 1",
+                "  - @bob: tweet 2",
+                "    - @alice: tweet 4",
+                "  - @carol: tweet 3",
+                "  - @dave: tweet 5",
+            ]
+            .join("\n")
+        );
+        assert_eq!(format_thread_outline(&[]), "");
+    }
+}
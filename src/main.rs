@@ -1,6 +1,7 @@
 mod action_result;
 mod api;
 mod auth;
+mod authenticity;
 mod cache;
 mod cli;
 mod client;
@@ -13,9 +14,11 @@ mod mcp_dispatcher;
 mod models;
 mod output_meta;
 mod policy;
+mod rate_limit;
 mod reliability;
 mod sentiment;
 mod webhook;
+mod write_guard;
 
 use anyhow::Result;
 use clap::Parser;
@@ -28,6 +31,10 @@ use config::Config;
 async fn main() -> Result<()> {
     let cli = Cli::parse();
     let config = Config::load()?;
+    costs::install_model_rates(config.model_rates.clone());
+    for warning in config.model_rate_warnings() {
+        eprintln!("[xint] warning: {warning}");
+    }
     let client = XClient::new()?;
 
     if let Some(ref cmd) = cli.command {
@@ -123,7 +130,13 @@ async fn main() -> Result<()> {
         );
     }
 
-    result?;
+    if let Err(err) = result {
+        if err.downcast_ref::<costs::BudgetExceeded>().is_some() {
+            eprintln!("Error: {err:#}");
+            std::process::exit(costs::EXIT_BUDGET_EXCEEDED);
+        }
+        return Err(err);
+    }
 
     Ok(())
 }
@@ -5,20 +5,24 @@
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
 
-use crate::api::{grok, twitter, xai};
+use crate::api::{availability, grok, redirect, twitter, xai};
 use crate::auth::oauth;
+use crate::authenticity;
 use crate::cache;
 use crate::cli::{McpArgs, PolicyMode};
 use crate::client::XClient;
-use crate::config::Config;
+use crate::commands::{article, diff, media};
+use crate::config::{Config, McpConfig};
 use crate::costs;
 use crate::mcp_dispatcher::{resolve_tool_route, McpToolRoute};
-use crate::models::Tweet;
+use crate::models::{OAuthTokens, ThreadPage, Tweet};
 use crate::policy;
+use crate::rate_limit::ToolRateLimiter;
 use crate::reliability;
 use crate::sentiment;
+use crate::write_guard;
 
 // ============================================================================
 // Tool Definitions
@@ -66,16 +70,761 @@ pub struct MCPContent {
     pub text: String,
 }
 
+/// A message that failed without producing its own JSON-RPC response. Keeps
+/// the id the request carried (`null` when it could not be parsed) so the
+/// transport can still answer it in a way clients can correlate.
+#[derive(Debug)]
+pub struct RequestError {
+    pub id: serde_json::Value,
+    pub code: i32,
+    pub message: String,
+}
+
+impl RequestError {
+    pub fn to_response(&self) -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": self.id,
+            "error": { "code": self.code, "message": self.message }
+        })
+        .to_string()
+    }
+}
+
 // ============================================================================
 // MCP Server Implementation
 // ============================================================================
 
+const DEFAULT_PACKAGE_API_MAX_CONCURRENCY: usize = 4;
+pub const DEFAULT_MAX_CONCURRENCY: usize = 4;
+const DEFAULT_PACKAGE_MAX_WINDOW_DAYS: i64 = 90;
+const DEFAULT_ARTIFACT_THRESHOLD_BYTES: usize = 64 * 1024;
+const ARTIFACT_URI_PREFIX: &str = "xint://artifact/";
+const MAX_CACHED_RESULTS: usize = 50;
+pub const DEFAULT_MAX_TWEETS: u64 = 200;
+const MAX_PAGES: u64 = 10;
+const DEFAULT_MAX_LINE_BYTES: usize = 10 * 1024 * 1024;
+const TOOLS_PAGE_SIZE: usize = 10;
+/// MCP protocol revisions this server speaks, newest first.
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-03-26", "2024-11-05"];
+
 pub struct MCPServer {
-    initialized: bool,
+    initialized: std::sync::atomic::AtomicBool,
     policy_mode: PolicyMode,
     enforce_budget: bool,
     costs_path: PathBuf,
     reliability_path: PathBuf,
+    /// JSONL log of every policy decision; `None` disables auditing.
+    policy_audit_path: Option<PathBuf>,
+    /// Set after the first failed audit write so the warning is not repeated
+    /// for every call.
+    policy_audit_warned: std::sync::atomic::AtomicBool,
+    rate_limiter: std::sync::Mutex<ToolRateLimiter>,
+    settings: McpConfig,
+    allowed_write_dirs: Vec<PathBuf>,
+    /// Non-localhost browser origins the SSE transport accepts.
+    allowed_origins: Vec<String>,
+    package_api_permits: tokio::sync::Semaphore,
+    /// Caps how many tool calls execute at once; acquired after policy,
+    /// budget and rate checks pass.
+    tool_permits: tokio::sync::Semaphore,
+    artifacts: std::sync::Mutex<std::collections::BTreeMap<String, PathBuf>>,
+    max_tweets: u64,
+    /// Daily limit raised for this process only; never written to disk.
+    budget_override_usd: Option<f64>,
+    session_spend: std::sync::Mutex<std::collections::HashMap<String, f64>>,
+    initialized_sessions: std::sync::Mutex<std::collections::HashSet<String>>,
+    /// Where out-of-band notifications go on stdio. SSE requests use their
+    /// session stream (`SESSION_STREAM`) instead. Responses share the channel
+    /// so ordering holds.
+    notifier: std::sync::Mutex<Option<tokio::sync::mpsc::UnboundedSender<String>>>,
+    /// In-flight tools/call requests by JSON-RPC id, for
+    /// `notifications/cancelled`. Shared with transport readers so a cancel
+    /// can land while the call is still running.
+    cancellations: CancellationMap,
+    /// Recent search/article results, readable as `xint://search/<hash>` and
+    /// `xint://article/<hash>` resources without another tool call.
+    cached_results: std::sync::Mutex<std::collections::VecDeque<CachedResult>>,
+    /// Structured `data` of each session's latest data-returning tool call,
+    /// for `xint_export`. Stdio clients share the empty session id.
+    last_results: std::sync::Mutex<std::collections::HashMap<String, LastResult>>,
+}
+
+tokio::task_local! {
+    /// `_meta.progressToken` of the tools/call running on this task, if any.
+    static PROGRESS_TOKEN: Option<serde_json::Value>;
+    /// Session of the tools/call running on this task, if any.
+    static CURRENT_SESSION: Option<String>;
+    /// SSE stream of the session whose request is running on this task.
+    static SESSION_STREAM: tokio::sync::mpsc::UnboundedSender<String>;
+    /// Grok completions made by the tools/call running on this task.
+    static CALL_USAGE: std::sync::Arc<std::sync::Mutex<Vec<crate::models::TokenUsage>>>;
+}
+
+/// Lock server state shared across concurrent tool calls. A panicked call
+/// must not wedge the whole server, so poisoning is ignored.
+fn lock_state<T>(mutex: &std::sync::Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+struct CachedResult {
+    uri: String,
+    name: String,
+    text: String,
+}
+
+struct LastResult {
+    tool: String,
+    data: serde_json::Value,
+}
+
+/// The rows of a tool result: `data` itself when it is an array, otherwise
+/// its main list (`tweets`, `results`, ...), or the whole object as one row.
+fn export_records(data: &serde_json::Value) -> Vec<serde_json::Value> {
+    const LIST_FIELDS: [&str; 7] = [
+        "tweets",
+        "results",
+        "matches",
+        "collections",
+        "users",
+        "entries",
+        "items",
+    ];
+    if let Some(rows) = data.as_array() {
+        return rows.clone();
+    }
+    LIST_FIELDS
+        .iter()
+        .find_map(|field| data.get(*field).and_then(|v| v.as_array()))
+        .cloned()
+        .unwrap_or_else(|| vec![data.clone()])
+}
+
+/// Render export rows as CSV: one column per top-level key, in the order
+/// keys first appear, with nested values kept as JSON text.
+fn export_csv(records: &[serde_json::Value]) -> Result<String, String> {
+    let mut columns: Vec<String> = Vec::new();
+    for key in records
+        .iter()
+        .filter_map(|r| r.as_object())
+        .flat_map(|r| r.keys())
+    {
+        if !columns.contains(key) {
+            columns.push(key.clone());
+        }
+    }
+    if columns.is_empty() {
+        columns.push("value".to_string());
+    }
+    let cell = |value: Option<&serde_json::Value>| match value {
+        None | Some(serde_json::Value::Null) => String::new(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    };
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(&columns)
+        .map_err(|e| format!("Failed to write CSV header: {e}"))?;
+    for record in records {
+        let row: Vec<String> = match record.as_object() {
+            Some(fields) => columns.iter().map(|c| cell(fields.get(c))).collect(),
+            None => vec![cell(Some(record))],
+        };
+        writer
+            .write_record(&row)
+            .map_err(|e| format!("Failed to write CSV row: {e}"))?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| format!("Failed to finish CSV: {e}"))?;
+    String::from_utf8(bytes).map_err(|e| format!("CSV is not UTF-8: {e}"))
+}
+
+/// Last tweet `xint_watch` returned for one query, stored per query under
+/// the watch state dir.
+#[derive(Debug, Serialize, Deserialize)]
+struct WatchCursor {
+    query: String,
+    last_id: String,
+    updated_at: String,
+}
+
+/// State file for a watch query. Queries differing only in case or spacing
+/// share a cursor; different queries never do.
+fn watch_cursor_path(dir: &std::path::Path, query: &str) -> PathBuf {
+    let normalized = query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    dir.join(format!("{}.json", cache::cache_key(&normalized, "watch")))
+}
+
+fn load_watch_cursor(path: &std::path::Path) -> Option<WatchCursor> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Write the cursor through a temporary file so a crash mid-write leaves
+/// the previous cursor intact.
+fn save_watch_cursor(path: &std::path::Path, cursor: &WatchCursor) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create watch state dir: {e}"))?;
+    }
+    let json = serde_json::to_string_pretty(cursor)
+        .map_err(|e| format!("Failed to encode watch cursor: {e}"))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)
+        .and_then(|_| std::fs::rename(&tmp, path))
+        .map_err(|e| format!("Failed to save watch cursor: {e}"))
+}
+
+/// Tweet ids are snowflakes: a longer id is newer, equal lengths compare
+/// lexically.
+fn tweet_id_newer(id: &str, than: &str) -> bool {
+    (id.len(), id) > (than.len(), than)
+}
+
+/// Retweeters `xint_retweeters` asks for: 100 by default, at least 1 and
+/// at most the 1,000 that 10 pages of `retweeted_by` can return.
+fn retweeters_limit(args: &serde_json::Value) -> usize {
+    args.get("limit")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(100)
+        .clamp(1, 1000) as usize
+}
+
+/// Pages of 1,000 accounts `xint_following`/`xint_followers` fetch to list
+/// `limit` of them.
+fn relationship_pages(limit: usize) -> u32 {
+    limit.div_ceil(1000).max(1) as u32
+}
+
+/// Recent tweets `xint_top` ranks to return `count`: five times as many,
+/// between 50 and 200, so the top posts are not just the most recent ones.
+fn top_window(count: usize) -> u32 {
+    (count * 5).clamp(50, 200) as u32
+}
+
+/// The `count` highest-ranked of `tweets` by `metric`, best first.
+fn top_by_metric(mut tweets: Vec<Tweet>, metric: &str, count: usize) -> Vec<Tweet> {
+    twitter::sort_by(&mut tweets, metric);
+    tweets.truncate(count.max(1));
+    tweets
+}
+
+/// Posts mentioning `username` by anyone else, deduplicated and capped at
+/// `limit`. The search already excludes `from:` the account, but handles
+/// match case-insensitively, so self-mentions are dropped here too.
+fn mentions_of(tweets: Vec<Tweet>, username: &str, limit: usize) -> Vec<Tweet> {
+    let mut mentions: Vec<_> = twitter::dedupe(tweets)
+        .into_iter()
+        .filter(|t| !t.username.eq_ignore_ascii_case(username))
+        .collect();
+    mentions.truncate(limit.max(1));
+    mentions
+}
+
+fn bool_arg(args: &serde_json::Value, camel: &str, snake: &str) -> bool {
+    args.get(camel)
+        .and_then(|v| v.as_bool())
+        .or_else(|| args.get(snake).and_then(|v| v.as_bool()))
+        .unwrap_or(false)
+}
+
+/// The X query and page count `xint_search` sends for `limit` results. The
+/// reply/retweet filters are the same query modifiers as `xint search
+/// --no-replies/--no-retweets`, so filtered tweets are never fetched (or
+/// billed). Pages hold 20 tweets, up to 5.
+fn search_request(query: &str, limit: usize, args: &serde_json::Value) -> (String, u32) {
+    let mut search_query = query.to_string();
+    if bool_arg(args, "noReplies", "no_replies") {
+        search_query.push_str(" -is:reply");
+    }
+    if bool_arg(args, "noRetweets", "no_retweets") {
+        search_query.push_str(" -is:retweet");
+    }
+    let pages = ((limit.max(1) as u32).saturating_add(19) / 20).min(5);
+    (search_query, pages)
+}
+
+/// A List id from a bare id or an `x.com/i/lists/<id>` URL.
+fn extract_list_id(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    let candidate = match trimmed.find("/lists/") {
+        Some(idx) => &trimmed[idx + "/lists/".len()..],
+        None => trimmed,
+    };
+    let id: String = candidate
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    let rest = &candidate[id.len()..];
+    let complete = rest.is_empty() || rest.starts_with(['/', '?', '#']);
+    (!id.is_empty() && complete).then_some(id)
+}
+
+/// Tweets newer than the `previous` cursor, oldest first and capped at
+/// `limit`, plus how many newer ones were held back. Oldest first means a
+/// capped poll resumes where it stopped instead of skipping the overflow.
+fn watch_batch(tweets: Vec<Tweet>, previous: Option<&str>, limit: usize) -> (Vec<Tweet>, usize) {
+    let mut fresh: Vec<_> = twitter::dedupe(tweets)
+        .into_iter()
+        .filter(|t| previous.is_none_or(|last| tweet_id_newer(&t.id, last)))
+        .collect();
+    fresh.sort_by(|a, b| (a.id.len(), &a.id).cmp(&(b.id.len(), &b.id)));
+    let pending = fresh.len().saturating_sub(limit.max(1));
+    fresh.truncate(limit.max(1));
+    (fresh, pending)
+}
+
+/// Accept `xint_sentiment` input as plain texts or tweet objects. Objects
+/// that are not full tweets only need a `text`; missing ids are numbered by
+/// position.
+fn sentiment_input_tweets(values: &[serde_json::Value]) -> Result<Vec<Tweet>, String> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            if let Ok(tweet) = serde_json::from_value::<Tweet>(value.clone()) {
+                return Ok(tweet);
+            }
+            let text = value
+                .as_str()
+                .or_else(|| value.get("text").and_then(|v| v.as_str()))
+                .ok_or_else(|| format!("tweets[{index}] has no text to analyze"))?;
+            let id = match value.get("id") {
+                Some(serde_json::Value::String(id)) => id.clone(),
+                Some(serde_json::Value::Number(id)) => id.to_string(),
+                _ => (index + 1).to_string(),
+            };
+            Ok(Tweet {
+                id,
+                text: text.to_string(),
+                username: value
+                    .get("username")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Authors with the most engagement across `tweets`, for the report's
+/// notable accounts section.
+fn notable_accounts(tweets: &[Tweet], limit: usize) -> Vec<serde_json::Value> {
+    let mut by_author: Vec<(&str, usize, u64, &Tweet)> = Vec::new();
+    for tweet in tweets {
+        let engagement = tweet.metrics.likes + tweet.metrics.retweets + tweet.metrics.quotes;
+        match by_author.iter_mut().find(|a| a.0 == tweet.username) {
+            Some(author) => {
+                author.1 += 1;
+                author.2 += engagement;
+                let top = author.3;
+                if engagement > top.metrics.likes + top.metrics.retweets + top.metrics.quotes {
+                    author.3 = tweet;
+                }
+            }
+            None => by_author.push((&tweet.username, 1, engagement, tweet)),
+        }
+    }
+    by_author.sort_by(|a, b| b.2.cmp(&a.2).then(b.1.cmp(&a.1)));
+    by_author
+        .into_iter()
+        .take(limit)
+        .map(|(username, tweets, engagement, top)| {
+            serde_json::json!({
+                "username": username,
+                "tweets": tweets,
+                "engagement": engagement,
+                "top_tweet_url": top.tweet_url
+            })
+        })
+        .collect()
+}
+
+/// What a quote tweet adds: its text without the trailing t.co link X
+/// appends to point at the quoted post.
+fn quote_commentary(text: &str) -> String {
+    let mut commentary = text.trim_end();
+    while let Some((rest, last)) = commentary.rsplit_once(char::is_whitespace) {
+        if !last.starts_with("https://t.co/") {
+            break;
+        }
+        commentary = rest.trim_end();
+    }
+    if commentary.starts_with("https://t.co/") && !commentary.contains(char::is_whitespace) {
+        return String::new();
+    }
+    commentary.to_string()
+}
+
+/// Accounts in `first` whose `id` also appears in `second`, in `first`'s
+/// order. Entries without an id never match.
+fn common_accounts(
+    first: &[serde_json::Value],
+    second: &[serde_json::Value],
+) -> Vec<serde_json::Value> {
+    let second_ids: std::collections::HashSet<&str> = second
+        .iter()
+        .filter_map(|u| u.get("id").and_then(|v| v.as_str()))
+        .collect();
+    first
+        .iter()
+        .filter(|u| {
+            u.get("id")
+                .and_then(|v| v.as_str())
+                .is_some_and(|id| second_ids.contains(id))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Result message for `xint_poll`: open with votes so far, closed with the
+/// final count, or no poll at all.
+fn poll_message(poll: Option<&crate::models::Poll>) -> String {
+    match poll {
+        Some(p) if p.open => format!("Poll is open with {} votes so far.", p.total_votes),
+        Some(p) => format!("Poll closed with {} votes.", p.total_votes),
+        None => "No poll on this tweet.".to_string(),
+    }
+}
+
+/// Result message for `xint_pinned`, which says so when the account has no
+/// pinned tweet rather than returning an empty result.
+fn pinned_message(username: &str, pinned: Option<&Tweet>) -> String {
+    match pinned {
+        Some(_) => "Pinned tweet lookup completed.".to_string(),
+        None => format!("@{username} has no pinned tweet."),
+    }
+}
+
+/// Posting cadence and average engagement over a batch of one account's
+/// tweets, for `xint_compare`.
+fn activity_summary(tweets: &[Tweet]) -> serde_json::Value {
+    let times: Vec<_> = tweets
+        .iter()
+        .filter_map(|t| chrono::DateTime::parse_from_rfc3339(&t.created_at).ok())
+        .collect();
+    let span_days = match (times.iter().min(), times.iter().max()) {
+        (Some(first), Some(last)) => (*last - *first).num_seconds() as f64 / 86_400.0,
+        _ => 0.0,
+    };
+    // A burst within one day still counts as a day of activity.
+    let posts_per_day = if tweets.is_empty() {
+        0.0
+    } else {
+        tweets.len() as f64 / span_days.max(1.0)
+    };
+    let n = tweets.len().max(1) as f64;
+    let avg = |metric: fn(&Tweet) -> u64| {
+        (tweets.iter().map(metric).sum::<u64>() as f64 / n * 10.0).round() / 10.0
+    };
+
+    let mut hashtags = std::collections::HashMap::<String, u64>::new();
+    for tag in tweets.iter().flat_map(|t| &t.hashtags) {
+        *hashtags.entry(tag.to_lowercase()).or_default() += 1;
+    }
+    let mut top_hashtags: Vec<_> = hashtags.into_iter().collect();
+    top_hashtags.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    top_hashtags.truncate(5);
+
+    serde_json::json!({
+        "tweets_analyzed": tweets.len(),
+        "span_days": (span_days * 10.0).round() / 10.0,
+        "posts_per_day": (posts_per_day * 10.0).round() / 10.0,
+        "avg_likes": avg(|t| t.metrics.likes),
+        "avg_retweets": avg(|t| t.metrics.retweets),
+        "avg_replies": avg(|t| t.metrics.replies),
+        "avg_impressions": avg(|t| t.metrics.impressions),
+        "top_hashtags": top_hashtags
+            .into_iter()
+            .map(|(tag, count)| serde_json::json!({ "tag": tag, "count": count }))
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Average likes + retweets + replies per post as a percentage of
+/// `followers`, plus a trend comparing the newer half of the window with the
+/// older half (`rising`/`falling` beyond a 10% change, otherwise `flat`).
+fn engagement_rate(followers: u64, tweets: &[Tweet]) -> serde_json::Value {
+    let engagement = |t: &Tweet| (t.metrics.likes + t.metrics.retweets + t.metrics.replies) as f64;
+    let average = |slice: &[&Tweet]| {
+        if slice.is_empty() {
+            0.0
+        } else {
+            slice.iter().map(|t| engagement(t)).sum::<f64>() / slice.len() as f64
+        }
+    };
+    let rate = |avg: f64| {
+        if followers == 0 {
+            0.0
+        } else {
+            (avg / followers as f64 * 100.0 * 1000.0).round() / 1000.0
+        }
+    };
+
+    let mut ordered: Vec<&Tweet> = tweets.iter().collect();
+    ordered.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    let (older, newer) = ordered.split_at(ordered.len() / 2);
+    let (older_avg, newer_avg) = (average(older), average(newer));
+    let trend = if older.is_empty() {
+        "flat"
+    } else if newer_avg > older_avg * 1.1 {
+        "rising"
+    } else if newer_avg < older_avg * 0.9 {
+        "falling"
+    } else {
+        "flat"
+    };
+    let avg = average(&ordered);
+
+    serde_json::json!({
+        "posts": tweets.len(),
+        "followers": followers,
+        "avg_engagement_per_post": (avg * 10.0).round() / 10.0,
+        "engagement_rate_pct": rate(avg),
+        "trend": trend,
+        "older_half_rate_pct": rate(older_avg),
+        "newer_half_rate_pct": rate(newer_avg)
+    })
+}
+
+/// Accounts that appear across `seed`'s tweets, via retweets (`RT @user:`)
+/// or @-mentions. `score` is the share of scanned tweets each one appears
+/// in; the seed itself is skipped.
+fn co_occurring_accounts(seed: &str, tweets: &[Tweet], limit: usize) -> Vec<serde_json::Value> {
+    let mut counts: Vec<(String, u64, u64)> = Vec::new();
+    for tweet in tweets {
+        let retweeted = tweet
+            .text
+            .strip_prefix("RT @")
+            .and_then(|rest| rest.split_once(':'))
+            .map(|(user, _)| user.to_lowercase());
+        let mut seen: Vec<String> = Vec::new();
+        for mention in &tweet.mentions {
+            let user = mention.trim_start_matches('@').to_lowercase();
+            if user.is_empty() || user == seed.to_lowercase() || seen.contains(&user) {
+                continue;
+            }
+            let is_retweet = retweeted.as_deref() == Some(user.as_str());
+            match counts.iter_mut().find(|c| c.0 == user) {
+                Some(entry) if is_retweet => entry.2 += 1,
+                Some(entry) => entry.1 += 1,
+                None if is_retweet => counts.push((user.clone(), 0, 1)),
+                None => counts.push((user.clone(), 1, 0)),
+            }
+            seen.push(user);
+        }
+    }
+    counts.sort_by(|a, b| (b.1 + b.2).cmp(&(a.1 + a.2)).then(a.0.cmp(&b.0)));
+
+    let scanned = tweets.len().max(1) as f64;
+    counts
+        .into_iter()
+        .take(limit)
+        .map(|(username, mentions, retweets)| {
+            serde_json::json!({
+                "username": username,
+                "score": ((mentions + retweets) as f64 / scanned * 100.0).round() / 100.0,
+                "mentions": mentions,
+                "retweets": retweets,
+                "profile_url": format!("https://x.com/{username}")
+            })
+        })
+        .collect()
+}
+
+/// In-flight calls keyed by (session id, JSON-RPC request id). Request ids
+/// are only unique within a session; stdio uses the empty session id.
+type CancellationMap = std::sync::Arc<
+    std::sync::Mutex<std::collections::HashMap<(String, String), tokio::sync::watch::Sender<bool>>>,
+>;
+
+fn cancellation_key(session_id: Option<&str>, request_id: &serde_json::Value) -> (String, String) {
+    (
+        session_id.unwrap_or_default().to_string(),
+        request_id.to_string(),
+    )
+}
+
+/// A tools/call registered for cancellation. Dropping it unregisters the
+/// call, so a request abandoned mid-await never leaves a stale entry.
+struct InFlightCall {
+    cancellations: CancellationMap,
+    key: (String, String),
+}
+
+impl InFlightCall {
+    /// `None` when the session already has a call in flight with this id.
+    fn register(
+        cancellations: &CancellationMap,
+        key: (String, String),
+        cancel: tokio::sync::watch::Sender<bool>,
+    ) -> Option<Self> {
+        match lock_state(cancellations).entry(key.clone()) {
+            std::collections::hash_map::Entry::Occupied(_) => return None,
+            std::collections::hash_map::Entry::Vacant(slot) => {
+                slot.insert(cancel);
+            }
+        }
+        Some(Self {
+            cancellations: cancellations.clone(),
+            key,
+        })
+    }
+}
+
+impl Drop for InFlightCall {
+    fn drop(&mut self) {
+        lock_state(&self.cancellations).remove(&self.key);
+    }
+}
+
+/// If `msg` is a `notifications/cancelled`, signal the matching in-flight
+/// request of `session_id` and return true.
+fn apply_cancellation(
+    cancellations: &CancellationMap,
+    session_id: Option<&str>,
+    msg: &str,
+) -> bool {
+    let Ok(parsed) = serde_json::from_str::<serde_json::Value>(msg) else {
+        return false;
+    };
+    if parsed.get("method").and_then(|v| v.as_str()) != Some("notifications/cancelled") {
+        return false;
+    }
+    let Some(request_id) = parsed.get("params").and_then(|p| p.get("requestId")) else {
+        return false;
+    };
+    if let Ok(map) = cancellations.lock() {
+        if let Some(cancel) = map.get(&cancellation_key(session_id, request_id)) {
+            let _ = cancel.send(true);
+        }
+    }
+    true
+}
+
+/// `XINT_MCP_MAX_LINE`: largest stdin message in bytes (default 10 MB).
+fn max_line_bytes() -> usize {
+    std::env::var("XINT_MCP_MAX_LINE")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_MAX_LINE_BYTES)
+}
+
+enum StdinLine {
+    Line(String),
+    /// The line exceeded the limit; its bytes were discarded up to and
+    /// including the next newline.
+    TooLong,
+}
+
+/// Read one newline-terminated message without ever buffering more than
+/// `max_bytes` of it. Returns `None` at end of input.
+async fn read_bounded_line<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    max_bytes: usize,
+) -> std::io::Result<Option<StdinLine>> {
+    let mut line = Vec::new();
+    let mut too_long = false;
+    loop {
+        let chunk = reader.fill_buf().await?;
+        if chunk.is_empty() {
+            return Ok(match (too_long, line.is_empty()) {
+                (true, _) => Some(StdinLine::TooLong),
+                (false, true) => None,
+                (false, false) => {
+                    Some(StdinLine::Line(String::from_utf8_lossy(&line).into_owned()))
+                }
+            });
+        }
+        let (taken, done) = match chunk.iter().position(|b| *b == b'\n') {
+            Some(newline) => (newline + 1, true),
+            None => (chunk.len(), false),
+        };
+        if !too_long {
+            let content = &chunk[..if done { taken - 1 } else { taken }];
+            if line.len() + content.len() > max_bytes {
+                too_long = true;
+                line = Vec::new();
+            } else {
+                line.extend_from_slice(content);
+            }
+        }
+        reader.consume(taken);
+        if done {
+            if too_long {
+                return Ok(Some(StdinLine::TooLong));
+            }
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            return Ok(Some(StdinLine::Line(
+                String::from_utf8_lossy(&line).into_owned(),
+            )));
+        }
+    }
+}
+
+/// Forward SIGTERM/SIGINT to a channel so the stdio loop can stop between
+/// messages instead of dying mid-line. Each signal is delivered separately.
+fn spawn_shutdown_listener() -> tokio::sync::mpsc::UnboundedReceiver<&'static str> {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let (Ok(mut term), Ok(mut interrupt)) = (
+            signal(SignalKind::terminate()),
+            signal(SignalKind::interrupt()),
+        ) else {
+            return rx;
+        };
+        tokio::spawn(async move {
+            loop {
+                let name = tokio::select! {
+                    _ = term.recv() => "SIGTERM",
+                    _ = interrupt.recv() => "SIGINT",
+                };
+                if tx.send(name).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    #[cfg(not(unix))]
+    tokio::spawn(async move {
+        while tokio::signal::ctrl_c().await.is_ok() {
+            if tx.send("ctrl-c").is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// Work handed from SSE connection tasks to the single task that owns the
+/// server, so requests are processed in order exactly like stdio.
+enum SseEvent {
+    Open {
+        stream: tokio::sync::mpsc::UnboundedSender<String>,
+        reply: tokio::sync::oneshot::Sender<String>,
+    },
+    Message {
+        session_id: String,
+        body: String,
+        reply: tokio::sync::oneshot::Sender<u16>,
+    },
+    Closed {
+        session_id: String,
+    },
 }
 
 impl MCPServer {
@@ -86,12 +835,210 @@ impl MCPServer {
         reliability_path: PathBuf,
     ) -> Self {
         Self {
-            initialized: false,
+            initialized: std::sync::atomic::AtomicBool::new(false),
             policy_mode,
             enforce_budget,
             costs_path,
             reliability_path,
+            policy_audit_path: None,
+            policy_audit_warned: std::sync::atomic::AtomicBool::new(false),
+            rate_limiter: std::sync::Mutex::new(ToolRateLimiter::from_env()),
+            settings: McpConfig::default(),
+            allowed_write_dirs: Vec::new(),
+            allowed_origins: Vec::new(),
+            package_api_permits: tokio::sync::Semaphore::new(Self::package_api_max_concurrency(
+                None,
+            )),
+            tool_permits: tokio::sync::Semaphore::new(DEFAULT_MAX_CONCURRENCY),
+            artifacts: Default::default(),
+            max_tweets: DEFAULT_MAX_TWEETS,
+            budget_override_usd: None,
+            session_spend: Default::default(),
+            initialized_sessions: Default::default(),
+            notifier: Default::default(),
+            cancellations: CancellationMap::default(),
+            cached_results: Default::default(),
+            last_results: Default::default(),
+        }
+    }
+
+    /// Start a client session for HTTP/SSE transports. The returned id is
+    /// sent back as `Mcp-Session-Id` and passed to
+    /// [`Self::handle_session_message`] on every later request.
+    pub fn open_session(&self) -> String {
+        let session_id = format!("{:032x}", rand::random::<u128>());
+        lock_state(&self.session_spend).insert(session_id.clone(), 0.0);
+        session_id
+    }
+
+    pub fn close_session(&self, session_id: &str) {
+        lock_state(&self.session_spend).remove(session_id);
+        lock_state(&self.initialized_sessions).remove(session_id);
+    }
+
+    /// Echo the client's protocol version when we support it; otherwise
+    /// offer our latest and let the client decide whether to continue.
+    fn negotiate_protocol_version(requested: Option<&str>) -> &'static str {
+        requested
+            .and_then(|version| {
+                SUPPORTED_PROTOCOL_VERSIONS
+                    .iter()
+                    .find(|supported| **supported == version)
+            })
+            .copied()
+            .unwrap_or(SUPPORTED_PROTOCOL_VERSIONS[0])
+    }
+
+    /// `tools/list` cursors are opaque to clients; internally they are the
+    /// offset into the catalog, which keeps `get_tools()` declaration order.
+    /// An empty cursor asks for the first page.
+    fn encode_tools_cursor(offset: usize) -> String {
+        use base64::Engine;
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(format!("tools:{offset}"))
+    }
+
+    fn decode_tools_cursor(cursor: &str) -> Option<usize> {
+        use base64::Engine;
+        if cursor.is_empty() {
+            return Some(0);
+        }
+        let decoded = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()?;
+        String::from_utf8(decoded)
+            .ok()?
+            .strip_prefix("tools:")?
+            .parse()
+            .ok()
+    }
+
+    fn is_initialized(&self, session_id: Option<&str>) -> bool {
+        match session_id {
+            Some(session) => lock_state(&self.initialized_sessions).contains(session),
+            None => self.initialized.load(std::sync::atomic::Ordering::SeqCst),
+        }
+    }
+
+    /// `XINT_MCP_SESSION_BUDGET_USD`, then `budget.session_limit_usd` from the
+    /// config file. Unset means sessions only share the global cap.
+    fn session_budget_limit_usd(&self) -> Option<f64> {
+        std::env::var("XINT_MCP_SESSION_BUDGET_USD")
+            .ok()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .or(self.settings.budget.session_limit_usd)
+    }
+
+    /// Charge a Grok completion at its model's configured rate and note its
+    /// token usage against the running tools/call.
+    fn track_grok_usage(
+        &self,
+        operation: &str,
+        usage: crate::models::TokenUsage,
+    ) -> crate::models::CostEntry {
+        let entry = costs::track_token_cost(&self.costs_path, operation, &usage);
+        let _ = CALL_USAGE.try_with(|call| lock_state(call).push(usage));
+        entry
+    }
+
+    fn record_session_spend(&self, session_id: &str, cost_usd: f64) {
+        *lock_state(&self.session_spend)
+            .entry(session_id.to_string())
+            .or_insert(0.0) += cost_usd.max(0.0);
+    }
+
+    /// How many tool calls may execute concurrently (at least one).
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.tool_permits = tokio::sync::Semaphore::new(max_concurrency.max(1));
+        self
+    }
+
+    /// Upper bound for `limit`/`count` tool arguments.
+    pub fn with_max_tweets(mut self, max_tweets: u64) -> Self {
+        self.max_tweets = max_tweets.max(1);
+        self
+    }
+
+    /// Raise the effective daily limit to `override_usd` for the lifetime of
+    /// this server. A value below the configured limit has no effect.
+    pub fn with_budget_override(mut self, override_usd: Option<f64>) -> Self {
+        self.budget_override_usd = override_usd.filter(|usd| usd.is_finite() && *usd > 0.0);
+        self
+    }
+
+    /// `XINT_PACKAGE_API_MAX_CONCURRENCY`, then the config file, then 4.
+    fn package_api_max_concurrency(configured: Option<usize>) -> usize {
+        std::env::var("XINT_PACKAGE_API_MAX_CONCURRENCY")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .or(configured)
+            .unwrap_or(DEFAULT_PACKAGE_API_MAX_CONCURRENCY)
+            .max(1)
+    }
+
+    /// Record every policy decision as a JSON line in `path`.
+    pub fn with_policy_audit_log(mut self, path: PathBuf) -> Self {
+        self.policy_audit_path = Some(path);
+        self
+    }
+
+    /// Append a policy decision to the audit log. Failures never block the
+    /// call; the first one is reported on stderr.
+    fn audit_policy_decision(&self, tool: &str, required: PolicyMode, decision: &'static str) {
+        let Some(path) = &self.policy_audit_path else {
+            return;
+        };
+        let entry = policy::AuditEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            tool,
+            policy_mode: policy::as_str(self.policy_mode),
+            required_mode: policy::as_str(required),
+            decision,
+        };
+        if let Err(err) = policy::append_audit(path, &entry) {
+            if !self
+                .policy_audit_warned
+                .swap(true, std::sync::atomic::Ordering::Relaxed)
+            {
+                eprintln!(
+                    "[xint] warning: cannot write policy audit log {}: {err}",
+                    path.display()
+                );
+            }
+        }
+    }
+
+    /// Directories file-writing tools may target. Empty disables file writes.
+    pub fn with_allowed_write_dirs(mut self, dirs: Vec<PathBuf>) -> Self {
+        self.allowed_write_dirs = dirs;
+        self
+    }
+
+    /// Browser origins, beyond localhost, allowed to use the SSE transport.
+    pub fn with_allowed_origins(mut self, origins: Vec<String>) -> Self {
+        self.allowed_origins = origins;
+        self
+    }
+
+    /// Apply settings from an MCP config file. Env vars still win over
+    /// file values wherever both exist.
+    pub fn with_settings(mut self, settings: McpConfig) -> Self {
+        self.package_api_permits = tokio::sync::Semaphore::new(Self::package_api_max_concurrency(
+            settings.package_api.max_concurrency,
+        ));
+        if let Some(calls_per_minute) = settings.rate_limits.calls_per_minute {
+            self.rate_limiter =
+                std::sync::Mutex::new(ToolRateLimiter::from_env_or(calls_per_minute));
+        }
+        for tool in settings.policy.tools.keys() {
+            if resolve_tool_route(tool).data.is_none() {
+                tracing::warn!(
+                    tool = tool.as_str(),
+                    "[policy.tools] names an unknown MCP tool; the override has no effect"
+                );
+            }
         }
+        self.settings = settings;
+        self
     }
 
     fn get_tools() -> Vec<MCPTool> {
@@ -110,6 +1057,20 @@ impl MCPServer {
                     "required": ["query"]
                 }),
             },
+            MCPTool {
+                name: "xint_hashtag".to_string(),
+                description: "Get recent original posts carrying a hashtag, newest or most engaged first".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tag": { "type": "string", "description": "Hashtag, with or without the leading #" },
+                        "limit": { "type": "number", "description": "Max results (default: 20)" },
+                        "since": { "type": "string", "description": "Time filter: 1h, 1d, 7d" },
+                        "sort": { "type": "string", "enum": ["recent", "engagement"], "description": "Sort order (default: recent)" },
+                    },
+                    "required": ["tag"]
+                }),
+            },
             MCPTool {
                 name: "xint_profile".to_string(),
                 description: "Get recent tweets from a specific X/Twitter user".to_string(),
@@ -123,74 +1084,289 @@ impl MCPServer {
                 }),
             },
             MCPTool {
-                name: "xint_thread".to_string(),
-                description: "Get full conversation thread from a tweet".to_string(),
+                name: "xint_top".to_string(),
+                description: "Get a user's highest-engagement recent tweets, ranked by likes, retweets or replies".to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
-                        "tweet_id": { "type": "string", "description": "Tweet ID or URL" },
-                        "pages": { "type": "number", "description": "Pages to fetch (default: 2)" },
+                        "username": { "type": "string", "description": "Twitter username (without @)" },
+                        "count": { "type": "number", "description": "Number of top tweets (default: 10)" },
+                        "metric": { "type": "string", "enum": ["likes", "retweets", "replies"], "description": "Ranking metric (default: likes)" },
                     },
-                    "required": ["tweet_id"]
+                    "required": ["username"]
                 }),
             },
             MCPTool {
-                name: "xint_tweet".to_string(),
-                description: "Get a single tweet by ID".to_string(),
+                name: "xint_mentions".to_string(),
+                description: "Get recent posts that @-mention a user, excluding the user's own posts".to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
-                        "tweet_id": { "type": "string", "description": "Tweet ID or URL" },
+                        "username": { "type": "string", "description": "Twitter username (without @)" },
+                        "limit": { "type": "number", "description": "Max results (default: 20)" },
+                        "since": { "type": "string", "description": "Time filter: 1h, 1d, 7d" },
                     },
-                    "required": ["tweet_id"]
+                    "required": ["username"]
                 }),
             },
             MCPTool {
-                name: "xint_trends".to_string(),
-                description: "Get trending topics on X".to_string(),
+                name: "xint_similar".to_string(),
+                description: "Find candidate similar accounts: the ones a user most often retweets or mentions, with a co-occurrence score".to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
-                        "location": { "type": "string", "description": "Location or WOEID (default: worldwide)" },
-                        "limit": { "type": "number", "description": "Number of trends (default: 20)" },
+                        "username": { "type": "string", "description": "Seed username (without @)" },
+                        "limit": { "type": "number", "description": "Max accounts (default: 10)" },
                     },
+                    "required": ["username"]
                 }),
             },
             MCPTool {
-                name: "xint_xsearch".to_string(),
-                description: "Search X using xAI's Grok x-search for AI-powered results".to_string(),
+                name: "xint_engagement_rate".to_string(),
+                description: "Compute a user's engagement rate (likes + retweets + replies per post relative to followers) and its trend over recent posts".to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
-                        "query": { "type": "string", "description": "Search query" },
-                        "limit": { "type": "number", "description": "Max results (default: 10)" },
+                        "username": { "type": "string", "description": "Twitter username (without @)" },
+                        "count": { "type": "number", "description": "Number of recent posts to measure (default: 50)" },
                     },
-                    "required": ["query"]
+                    "required": ["username"]
                 }),
             },
             MCPTool {
-                name: "xint_collections_list".to_string(),
-                description: "List all xAI Collections knowledge base collections".to_string(),
+                name: "xint_pinned".to_string(),
+                description: "Get the pinned tweet of a specific X/Twitter user".to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
-                    "properties": {},
+                    "properties": {
+                        "username": { "type": "string", "description": "Twitter username (without @)" },
+                    },
+                    "required": ["username"]
                 }),
             },
             MCPTool {
-                name: "xint_analyze".to_string(),
-                description: "Analyze tweets or answer questions using Grok AI".to_string(),
+                name: "xint_user_info".to_string(),
+                description: "Get profile metadata for an X/Twitter user: bio, follower/following counts, join date, verification and location".to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
-                        "query": { "type": "string", "description": "Question or analysis request" },
-                        "model": { "type": "string", "description": "Grok model (grok-3-mini, grok-3)" },
+                        "username": { "type": "string", "description": "Twitter username (without @)" },
                     },
-                    "required": ["query"]
+                    "required": ["username"]
                 }),
             },
             MCPTool {
-                name: "xint_article".to_string(),
-                description: "Fetch and extract content from a URL article. Also supports X tweet URLs - extracts linked article automatically. Use ai_prompt to analyze with Grok.".to_string(),
+                name: "xint_verify".to_string(),
+                description: "Check an account's authenticity signals (age, verification, follower ratio, default avatar, posting cadence) and a heuristic bot score".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "username": { "type": "string", "description": "Twitter username (without @)" },
+                    },
+                    "required": ["username"]
+                }),
+            },
+            MCPTool {
+                name: "xint_user_brief".to_string(),
+                description: "Summarize a user's recent posts into a one-paragraph brief with citations (requires XAI_API_KEY)".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "username": { "type": "string", "description": "Twitter username (without @)" },
+                        "count": { "type": "number", "description": "Recent tweets to summarize (default: 20, max: 100)" },
+                        "model": { "type": "string", "description": "Grok model (default: grok-3-mini)" },
+                    },
+                    "required": ["username"]
+                }),
+            },
+            MCPTool {
+                name: "xint_thread".to_string(),
+                description: "Get full conversation thread from a tweet".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tweet_id": { "type": "string", "description": "Tweet ID or URL" },
+                        "pages": { "type": "number", "description": "Pages to fetch (default: 2)" },
+                        "cursor": { "type": "string", "description": "Resume from next_cursor of a previous partial fetch" },
+                    },
+                    "required": ["tweet_id"]
+                }),
+            },
+            MCPTool {
+                name: "xint_replies".to_string(),
+                description: "Get the direct replies to a tweet (not the wider thread), most engaged first".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tweet_id": { "type": "string", "description": "Tweet ID or URL" },
+                        "limit": { "type": "number", "description": "Max replies (default: 20)" },
+                    },
+                    "required": ["tweet_id"]
+                }),
+            },
+            MCPTool {
+                name: "xint_retweeters".to_string(),
+                description: "List the accounts that retweeted a tweet, with their follower counts".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tweet_id": { "type": "string", "description": "Tweet ID or URL" },
+                        "limit": { "type": "number", "description": "Max accounts (default: 100)" },
+                    },
+                    "required": ["tweet_id"]
+                }),
+            },
+            MCPTool {
+                name: "xint_poll".to_string(),
+                description: "Read the poll on a tweet: each option's votes and percentage, and whether voting is still open".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tweet_id": { "type": "string", "description": "Tweet ID or URL" },
+                    },
+                    "required": ["tweet_id"]
+                }),
+            },
+            MCPTool {
+                name: "xint_quotes".to_string(),
+                description: "Get quote-tweets that reference a tweet, with the commentary each one adds".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tweet_id": { "type": "string", "description": "Tweet ID or URL" },
+                        "limit": { "type": "number", "description": "Max results (default: 20, max: 100)" },
+                        "cursor": { "type": "string", "description": "Pagination cursor from a previous call" },
+                    },
+                    "required": ["tweet_id"]
+                }),
+            },
+            MCPTool {
+                name: "xint_media".to_string(),
+                description: "List image and video URLs (with alt text) from a user's recent tweets or a search, grouped by tweet".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "username": { "type": "string", "description": "Twitter username (without @); use instead of query" },
+                        "query": { "type": "string", "description": "Search query; use instead of username" },
+                        "limit": { "type": "number", "description": "Max tweets with media (default: 20)" },
+                    },
+                    "oneOf": [
+                        { "required": ["username"] },
+                        { "required": ["query"] }
+                    ]
+                }),
+            },
+            MCPTool {
+                name: "xint_tweet".to_string(),
+                description: "Get a single tweet by ID".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tweet_id": { "type": "string", "description": "Tweet ID or URL" },
+                    },
+                    "required": ["tweet_id"]
+                }),
+            },
+            MCPTool {
+                name: "xint_spaces".to_string(),
+                description: "Find live and scheduled Twitter Spaces about a topic, with host, participant count and state".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "query": { "type": "string", "description": "Topic to match against Space titles" },
+                        "limit": { "type": "number", "description": "Max Spaces (default: 10)" },
+                    },
+                    "required": ["query"]
+                }),
+            },
+            MCPTool {
+                name: "xint_list".to_string(),
+                description: "Get the newest tweets on a Twitter List's timeline".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "list_id": { "type": "string", "description": "List ID or URL (x.com/i/lists/<id>)" },
+                        "limit": { "type": "number", "description": "Max tweets (default: 20)" },
+                    },
+                    "required": ["list_id"]
+                }),
+            },
+            MCPTool {
+                name: "xint_trends".to_string(),
+                description: "Get trending topics on X".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "location": { "type": "string", "description": "Location or WOEID (default: worldwide)" },
+                        "limit": { "type": "number", "description": "Number of trends (default: 20)" },
+                    },
+                }),
+            },
+            MCPTool {
+                name: "xint_trends_for".to_string(),
+                description: "Get trending topics on X ranked by relevance to a topic (Grok-ranked when XAI_API_KEY is set, keyword overlap otherwise)".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "topic": { "type": "string", "description": "Subject to filter trends by" },
+                        "location": { "type": "string", "description": "Location or WOEID (default: worldwide)" },
+                        "limit": { "type": "number", "description": "Number of trends (default: 10)" },
+                    },
+                    "required": ["topic"]
+                }),
+            },
+            MCPTool {
+                name: "xint_xsearch".to_string(),
+                description: "Search X using xAI's Grok x-search for AI-powered results".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "query": { "type": "string", "description": "Search query" },
+                        "limit": { "type": "number", "description": "Max results (default: 10)" },
+                    },
+                    "required": ["query"]
+                }),
+            },
+            MCPTool {
+                name: "xint_collections_list".to_string(),
+                description: "List all xAI Collections knowledge base collections".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {},
+                }),
+            },
+            MCPTool {
+                name: "xint_analyze".to_string(),
+                description: "Analyze tweets or answer questions using Grok AI".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "query": { "type": "string", "description": "Question or analysis request" },
+                        "model": { "type": "string", "description": "Grok model (grok-3-mini, grok-3)" },
+                    },
+                    "required": ["query"]
+                }),
+            },
+            MCPTool {
+                name: "xint_translate".to_string(),
+                description: "Translate a tweet or raw text with Grok, returning the detected source language and the translation".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tweet_id": { "type": "string", "description": "Tweet ID or URL to translate; use instead of text" },
+                        "text": { "type": "string", "description": "Text to translate; use instead of tweet_id" },
+                        "target_lang": { "type": "string", "description": "Target language code (default: en)" },
+                        "model": { "type": "string", "description": "Grok model (default: grok-3-mini)" },
+                    },
+                    "oneOf": [
+                        { "required": ["tweet_id"] },
+                        { "required": ["text"] }
+                    ]
+                }),
+            },
+            MCPTool {
+                name: "xint_article".to_string(),
+                description: "Fetch and extract content from a URL article. Also supports X tweet URLs - extracts linked article automatically. Use ai_prompt to analyze with Grok.".to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -201,6 +1377,34 @@ impl MCPServer {
                     "required": ["url"]
                 }),
             },
+            MCPTool {
+                name: "xint_resolve_url".to_string(),
+                description: "Follow redirects (t.co and other shorteners) and return the final destination URL".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "url": { "type": "string", "description": "URL to resolve" },
+                        "max_hops": { "type": "number", "description": "Maximum redirects to follow (default: 10, max: 10)" },
+                    },
+                    "required": ["url"]
+                }),
+            },
+            MCPTool {
+                name: "xint_url_expand".to_string(),
+                description: "Extract every link from a tweet or raw text and follow shorteners (t.co, bit.ly, ...) to their final destinations".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "tweet_id": { "type": "string", "description": "Tweet ID or URL whose links to expand; use instead of text" },
+                        "text": { "type": "string", "description": "Text whose links to expand; use instead of tweet_id" },
+                        "max_hops": { "type": "number", "description": "Maximum redirects per link (default: 10, max: 10)" },
+                    },
+                    "oneOf": [
+                        { "required": ["tweet_id"] },
+                        { "required": ["text"] }
+                    ]
+                }),
+            },
             MCPTool {
                 name: "xint_collections_search".to_string(),
                 description: "Search within an xAI Collections knowledge base".to_string(),
@@ -253,6 +1457,22 @@ impl MCPServer {
                     "required": ["name", "topic_query", "sources", "time_window", "policy", "analysis_profile"]
                 }),
             },
+            MCPTool {
+                name: "xint_package_validate".to_string(),
+                description: "Validate xint_package_create arguments without creating a package"
+                    .to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string", "description": "Human-readable package name" },
+                        "topic_query": { "type": "string", "description": "Topic query used for ingest and refresh" },
+                        "sources": { "type": "array", "items": { "type": "string" }, "description": "Data sources to ingest" },
+                        "time_window": { "type": "object", "description": "{ from, to } RFC3339 timestamps (default: last 24h)" },
+                        "policy": { "type": "string", "description": "private or shared_candidate" },
+                        "analysis_profile": { "type": "string", "description": "summary, analyst, or forensic" }
+                    }
+                }),
+            },
             MCPTool {
                 name: "xint_package_status".to_string(),
                 description: "Get package metadata and freshness (v1 draft contract)".to_string(),
@@ -332,6 +1552,18 @@ impl MCPServer {
                     "properties": {},
                 }),
             },
+            MCPTool {
+                name: "xint_export".to_string(),
+                description: "Write the data of this session's most recent tool result to a file inside an --allow-write-dir directory".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "format": { "type": "string", "enum": ["json", "csv", "ndjson"], "description": "Output format" },
+                        "path": { "type": "string", "description": "Target file; relative paths resolve inside the first allowed write dir" },
+                    },
+                    "required": ["format", "path"]
+                }),
+            },
             MCPTool {
                 name: "xint_watch".to_string(),
                 description: "Monitor X in real-time with polling. Returns new tweets since last check.".to_string(),
@@ -345,6 +1577,30 @@ impl MCPServer {
                     "required": ["query"]
                 }),
             },
+            MCPTool {
+                name: "xint_following".to_string(),
+                description: "List the accounts a user follows (requires OAuth)".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "username": { "type": "string", "description": "Twitter username (without @)" },
+                        "limit": { "type": "number", "description": "Max accounts (default: 100)" },
+                    },
+                    "required": ["username"]
+                }),
+            },
+            MCPTool {
+                name: "xint_followers".to_string(),
+                description: "List the accounts that follow a user (requires OAuth)".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "username": { "type": "string", "description": "Twitter username (without @)" },
+                        "limit": { "type": "number", "description": "Max accounts (default: 100)" },
+                    },
+                    "required": ["username"]
+                }),
+            },
             MCPTool {
                 name: "xint_diff".to_string(),
                 description: "Track follower/following changes for a user".to_string(),
@@ -357,9 +1613,35 @@ impl MCPServer {
                     "required": ["username"]
                 }),
             },
+            MCPTool {
+                name: "xint_mutuals".to_string(),
+                description: "Find accounts that two X/Twitter users both follow (requires OAuth)".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "username_a": { "type": "string", "description": "First username (without @)" },
+                        "username_b": { "type": "string", "description": "Second username (without @)" },
+                        "pages": { "type": "number", "description": "Following pages to fetch per user (default: 2, max: 10)" },
+                    },
+                    "required": ["username_a", "username_b"]
+                }),
+            },
+            MCPTool {
+                name: "xint_compare".to_string(),
+                description: "Compare two X/Twitter users' recent activity side by side: post frequency, average engagement, shared hashtags and mentioned accounts".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "username_a": { "type": "string", "description": "First username (without @)" },
+                        "username_b": { "type": "string", "description": "Second username (without @)" },
+                        "count": { "type": "number", "description": "Recent tweets to fetch per user (default: 20)" },
+                    },
+                    "required": ["username_a", "username_b"]
+                }),
+            },
             MCPTool {
                 name: "xint_report".to_string(),
-                description: "Generate an AI-powered intelligence report on a topic".to_string(),
+                description: "Generate an AI-powered intelligence report on a topic: headline, key claims, notable accounts and optional sentiment (requires XAI_API_KEY)".to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -373,11 +1655,17 @@ impl MCPServer {
             },
             MCPTool {
                 name: "xint_sentiment".to_string(),
-                description: "Analyze sentiment of tweets".to_string(),
+                description: "Label tweets positive/neutral/negative with a confidence, plus the overall distribution. Uses Grok when XAI_API_KEY is set, a local word list otherwise.".to_string(),
                 input_schema: serde_json::json!({
                     "type": "object",
                     "properties": {
-                        "tweets": { "type": "array", "description": "Array of tweets to analyze" },
+                        "tweets": {
+                            "type": "array",
+                            "minItems": 1,
+                            "items": { "type": ["string", "object"] },
+                            "description": "Tweet texts, or tweet objects with at least a text field"
+                        },
+                        "model": { "type": "string", "description": "Grok model (default: grok-3-mini)" },
                     },
                     "required": ["tweets"]
                 }),
@@ -389,15 +1677,62 @@ impl MCPServer {
                     "type": "object",
                     "properties": {
                         "period": { "type": "string", "enum": ["today", "week", "month", "all"], "description": "Time period (default: today)" },
+                        "export": { "type": "string", "enum": ["csv"], "description": "Also write the per-call ledger for the period to a file (needs --allow-write-dir)" },
+                        "path": { "type": "string", "description": "Export file, relative to the first allowed write dir (default: costs-<period>-<date>.csv)" },
+                    },
+                }),
+            },
+            MCPTool {
+                name: "xint_estimate".to_string(),
+                description: "Dry-run cost estimate for a tool call: projected USD range for the given tool and arguments, without calling any API or touching the ledger".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string", "description": "Tool to estimate, e.g. xint_report" },
+                        "arguments": { "type": "object", "description": "Arguments the tool would be called with (default: {})" },
+                    },
+                    "required": ["name"]
+                }),
+            },
+            MCPTool {
+                name: "xint_budget_status".to_string(),
+                description: "Pre-flight budget check: daily spend, limit, remaining and warning state plus weekly/monthly windows and category sub-limits, without running a paid tool".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {},
+                }),
+            },
+            MCPTool {
+                name: "xint_budget_reset".to_string(),
+                description: "Clear recorded API spend for a period and return the totals removed. Without confirm: true, only previews what would be cleared.".to_string(),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "period": { "type": "string", "enum": ["today", "week", "month", "all"], "description": "Spend to clear (default: today)" },
+                        "confirm": { "type": "boolean", "description": "Must be true to actually clear the ledger (default: false)" },
                     },
                 }),
             },
         ]
     }
 
+    /// Policy mode `name` requires: the `[policy.tools]` override from the
+    /// config file, else the built-in default.
+    fn required_policy(&self, name: &str) -> PolicyMode {
+        self.settings
+            .policy
+            .tools
+            .get(name)
+            .copied()
+            .unwrap_or_else(|| Self::tool_required_policy(name))
+    }
+
     fn tool_required_policy(name: &str) -> PolicyMode {
         match name {
-            "xint_bookmarks" | "xint_diff" | "xint_package_publish" => PolicyMode::Engagement,
+            "xint_package_publish" => PolicyMode::Full,
+            "xint_bookmarks" | "xint_diff" | "xint_mutuals" | "xint_budget_reset" => {
+                PolicyMode::Engagement
+            }
             _ => PolicyMode::ReadOnly,
         }
     }
@@ -406,18 +1741,42 @@ impl MCPServer {
         matches!(
             name,
             "xint_search"
+                | "xint_hashtag"
                 | "xint_profile"
+                | "xint_top"
+                | "xint_mentions"
+                | "xint_similar"
+                | "xint_engagement_rate"
+                | "xint_pinned"
+                | "xint_user_info"
+                | "xint_verify"
+                | "xint_user_brief"
                 | "xint_thread"
+                | "xint_replies"
+                | "xint_retweeters"
+                | "xint_quotes"
+                | "xint_poll"
+                | "xint_media"
                 | "xint_tweet"
+                | "xint_spaces"
+                | "xint_list"
                 | "xint_trends"
+                | "xint_trends_for"
                 | "xint_xsearch"
                 | "xint_collections_list"
                 | "xint_collections_search"
                 | "xint_analyze"
+                | "xint_translate"
                 | "xint_article"
+                | "xint_resolve_url"
+                | "xint_url_expand"
                 | "xint_bookmarks"
                 | "xint_watch"
+                | "xint_following"
+                | "xint_followers"
                 | "xint_diff"
+                | "xint_mutuals"
+                | "xint_compare"
                 | "xint_report"
                 | "xint_sentiment"
                 | "xint_package_create"
@@ -429,10 +1788,21 @@ impl MCPServer {
     }
 
     fn ensure_tool_allowed(&self, name: &str) -> Result<(), String> {
-        let required = Self::tool_required_policy(name);
+        let required = self.required_policy(name);
+        if !self.settings.tool_enabled(name) {
+            self.audit_policy_decision(name, required, "disabled");
+            return Err(serde_json::json!({
+                "code": "TOOL_DISABLED",
+                "message": format!("MCP tool '{}' is disabled by server config", name),
+                "tool": name,
+            })
+            .to_string());
+        }
         if policy::is_allowed(self.policy_mode, required) {
+            self.audit_policy_decision(name, required, "allowed");
             return Ok(());
         }
+        self.audit_policy_decision(name, required, "denied");
         Err(serde_json::json!({
             "code": "POLICY_DENIED",
             "message": format!("MCP tool '{}' requires '{}' policy mode", name, policy::as_str(required)),
@@ -443,55 +1813,794 @@ impl MCPServer {
         .to_string())
     }
 
-    fn ensure_budget_allowed(&self, name: &str) -> Result<(), String> {
-        if !self.enforce_budget || !Self::tool_budget_guarded(name) {
-            return Ok(());
+    /// `XINT_MCP_BUDGET_WARN_THRESHOLD`, then `budget.warn_threshold` from the
+    /// config file, as a fraction of the daily limit. Unset keeps the costs
+    /// file's threshold (80% by default).
+    fn budget_warn_threshold(&self) -> Option<f64> {
+        std::env::var("XINT_MCP_BUDGET_WARN_THRESHOLD")
+            .ok()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .or(self.settings.budget.warn_threshold)
+            .map(|t| t.clamp(0.0, 1.0))
+    }
+
+    /// Today's budget status, with the MCP config's daily limit and warning
+    /// threshold (when set) taking precedence over the costs file's.
+    fn daily_budget(&self) -> crate::models::BudgetStatus {
+        let budget = self.configured_daily_budget();
+        match self.budget_override_usd {
+            Some(override_usd) if override_usd > budget.limit => costs::budget_status(
+                budget.enabled,
+                budget.spent,
+                override_usd,
+                budget.warn_threshold,
+            ),
+            _ => budget,
         }
+    }
+
+    /// Daily budget from the costs file and config, ignoring
+    /// `--budget-override-usd`.
+    fn configured_daily_budget(&self) -> crate::models::BudgetStatus {
         let budget = costs::check_budget(&self.costs_path);
-        if budget.allowed {
+        let limit = self.settings.budget.daily_limit_usd;
+        let threshold = self.budget_warn_threshold();
+        if limit.is_none() && threshold.is_none() {
+            return budget;
+        }
+        costs::budget_status(
+            budget.enabled || limit.is_some(),
+            budget.spent,
+            limit.unwrap_or(budget.limit),
+            threshold.unwrap_or(budget.warn_threshold),
+        )
+    }
+
+    /// Weekly or monthly limit: `XINT_MCP_WEEKLY_BUDGET_USD` /
+    /// `XINT_MCP_MONTHLY_BUDGET_USD`, then the config file, then the costs
+    /// file. Unset means the window is not enforced.
+    fn window_limit_usd(&self, window: &str) -> Option<f64> {
+        let (env_key, configured) = match window {
+            "weekly" => (
+                "XINT_MCP_WEEKLY_BUDGET_USD",
+                self.settings.budget.weekly_limit_usd,
+            ),
+            "monthly" => (
+                "XINT_MCP_MONTHLY_BUDGET_USD",
+                self.settings.budget.monthly_limit_usd,
+            ),
+            _ => return None,
+        };
+        let (stored_weekly, stored_monthly) = costs::stored_window_limits(&self.costs_path);
+        std::env::var(env_key)
+            .ok()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .or(configured)
+            .or(if window == "weekly" {
+                stored_weekly
+            } else {
+                stored_monthly
+            })
+    }
+
+    /// The daily window plus every weekly/monthly window that has a limit.
+    fn budget_windows(&self) -> Vec<crate::models::BudgetWindow> {
+        let daily = self.daily_budget();
+        let mut windows = vec![crate::models::BudgetWindow {
+            window: "daily".to_string(),
+            spent: daily.spent,
+            limit: daily.limit,
+            remaining: daily.remaining,
+            percent_used: daily.percent_used,
+            allowed: daily.allowed,
+        }];
+        for window in ["weekly", "monthly"] {
+            if let Some(limit) = self.window_limit_usd(window) {
+                windows.push(costs::window_status(&self.costs_path, window, limit));
+            }
+        }
+        windows
+    }
+
+    /// Check the global daily, weekly and monthly budgets, then the daily
+    /// sub-limit for the tool's category, then the caller's
+    /// session sub-budget.
+    fn ensure_budget_allowed(&self, name: &str, session_id: Option<&str>) -> Result<(), String> {
+        if !self.enforce_budget || !Self::tool_budget_guarded(name) {
+            return Ok(());
+        }
+        if let Some(window) = self.budget_windows().into_iter().find(|w| !w.allowed) {
+            let label = match window.window.as_str() {
+                "weekly" => "Weekly",
+                "monthly" => "Monthly",
+                _ => "Daily",
+            };
+            return Err(serde_json::json!({
+                "code": "BUDGET_DENIED",
+                "message": format!(
+                    "{label} budget exceeded (${:.2} / ${:.2})",
+                    window.spent, window.limit
+                ),
+                "tool": name,
+                "scope": "global",
+                "window": window.window,
+                "spent_usd": window.spent,
+                "limit_usd": window.limit,
+                "remaining_usd": window.remaining,
+            })
+            .to_string());
+        }
+        if let Some(override_usd) = self.budget_override_usd {
+            let configured = self.configured_daily_budget();
+            if configured.enabled && !configured.allowed {
+                tracing::warn!(
+                    tool = name,
+                    spent_usd = configured.spent,
+                    configured_limit_usd = configured.limit,
+                    override_limit_usd = override_usd,
+                    "daily budget override in effect"
+                );
+            }
+        }
+
+        if let Some((category, limit)) = costs::tool_category(name).and_then(|category| {
+            self.settings
+                .budget
+                .categories
+                .limit_usd(category)
+                .map(|limit| (category, limit))
+        }) {
+            let spent = costs::category_spent(&self.costs_path, category);
+            if spent >= limit {
+                return Err(serde_json::json!({
+                    "code": "BUDGET_DENIED",
+                    "message": format!(
+                        "Daily '{category}' category budget exceeded (${:.2} / ${:.2})",
+                        spent, limit
+                    ),
+                    "tool": name,
+                    "scope": "category",
+                    "category": category,
+                    "spent_usd": spent,
+                    "limit_usd": limit,
+                    "remaining_usd": 0.0,
+                })
+                .to_string());
+            }
+        }
+
+        let (Some(session_id), Some(limit)) = (session_id, self.session_budget_limit_usd()) else {
+            return Ok(());
+        };
+        let spent = lock_state(&self.session_spend)
+            .get(session_id)
+            .copied()
+            .unwrap_or(0.0);
+        if spent < limit {
             return Ok(());
         }
         Err(serde_json::json!({
             "code": "BUDGET_DENIED",
             "message": format!(
-                "Daily budget exceeded (${:.2} / ${:.2})",
-                budget.spent, budget.limit
+                "Session budget exceeded (${:.2} / ${:.2})",
+                spent, limit
+            ),
+            "tool": name,
+            "scope": "session",
+            "session_id": session_id,
+            "spent_usd": spent,
+            "limit_usd": limit,
+            "remaining_usd": 0.0,
+        })
+        .to_string())
+    }
+
+    /// Hold a guarded call's estimated cost against the daily limit while it
+    /// runs, so concurrent calls that each fit on their own cannot together
+    /// overshoot it. `None` when the budget is not enforced for this call.
+    fn reserve_budget(
+        &self,
+        name: &str,
+        estimated_usd: f64,
+    ) -> Result<Option<costs::Reservation>, String> {
+        if !self.enforce_budget || !Self::tool_budget_guarded(name) {
+            return Ok(None);
+        }
+        let budget = self.daily_budget();
+        costs::reserve(&self.costs_path, estimated_usd, &budget).map_err(|denied| {
+            let message = format!(
+                "Daily budget is held by spend and in-flight calls \
+                 (${:.2} spent + ${:.2} reserved of ${:.2})",
+                denied.spent, denied.reserved, denied.limit
+            );
+            serde_json::json!({
+                "code": "BUDGET_DENIED",
+                "message": message,
+                "tool": name,
+                "scope": "global",
+                "window": "daily",
+                "spent_usd": denied.spent,
+                "reserved_usd": denied.reserved,
+                "estimated_usd": denied.estimated,
+                "limit_usd": denied.limit,
+                "remaining_usd": (denied.limit - denied.spent - denied.reserved).max(0.0),
+            })
+            .to_string()
+        })
+    }
+
+    /// Policy, budget and rate checks for one tools/call, in that order,
+    /// logging which one decided the outcome. An allowed guarded call gets
+    /// a budget reservation to hold until its cost is recorded.
+    fn ensure_tool_call_allowed(
+        &self,
+        name: &str,
+        session_id: Option<&str>,
+        estimated_usd: f64,
+    ) -> Result<Option<costs::Reservation>, String> {
+        let reject = |decision: &str, err: String| {
+            tracing::warn!(tool = name, decision, error = %err, "tool call rejected");
+            err
+        };
+        self.ensure_tool_allowed(name)
+            .map_err(|err| reject("policy_denied", err))?;
+        self.ensure_budget_allowed(name, session_id)
+            .map_err(|err| reject("budget_denied", err))?;
+        let reservation = self
+            .reserve_budget(name, estimated_usd)
+            .map_err(|err| reject("budget_denied", err))?;
+        // Rate last: a denied call must not consume a rate-limit slot.
+        self.ensure_rate_allowed(name)
+            .map_err(|err| reject("rate_limited", err))?;
+        tracing::debug!(tool = name, decision = "allowed", "tool call allowed");
+        Ok(reservation)
+    }
+
+    fn ensure_rate_allowed(&self, name: &str) -> Result<(), String> {
+        let Err(state) = lock_state(&self.rate_limiter).try_acquire(name) else {
+            return Ok(());
+        };
+        Err(serde_json::json!({
+            "code": "RATE_LIMITED",
+            "message": format!(
+                "MCP tool '{}' exceeded {} calls per {}s; retry in {}ms",
+                name, state.limit, state.window_seconds, state.retry_after_ms
             ),
             "tool": name,
-            "spent_usd": budget.spent,
-            "limit_usd": budget.limit,
-            "remaining_usd": budget.remaining,
+            "limit": state.limit,
+            "remaining": state.remaining,
+            "retry_after_ms": state.retry_after_ms,
+            "reset_at": state.reset_at,
         })
         .to_string())
     }
 
-    fn package_api_base_url() -> Option<String> {
-        std::env::var("XINT_PACKAGE_API_BASE_URL")
+    /// Send `notifications/progress` for the tools/call running on this task
+    /// when the client asked for progress. `progress` is a percentage (0-100).
+    fn report_progress(&self, progress: u32, message: &str) {
+        let token = PROGRESS_TOKEN
+            .try_with(|token| token.clone())
             .ok()
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
+            .flatten();
+        self.send_progress(token.as_ref(), progress, message);
     }
 
-    fn package_api_key() -> Option<String> {
-        std::env::var("XINT_PACKAGE_API_KEY")
+    fn send_progress(&self, token: Option<&serde_json::Value>, progress: u32, message: &str) {
+        let Some(token) = token else {
+            return;
+        };
+        let notifier = SESSION_STREAM
+            .try_with(|stream| stream.clone())
             .ok()
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
+            .or_else(|| lock_state(&self.notifier).clone());
+        let Some(notifier) = notifier else {
+            return;
+        };
+        let _ = notifier.send(
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/progress",
+                "params": {
+                    "progressToken": token,
+                    "progress": progress.min(100),
+                    "total": 100,
+                    "message": message,
+                }
+            })
+            .to_string(),
+        );
+    }
+
+    /// Check `tools/call` arguments against the tool's declared
+    /// `inputSchema`: required properties, `oneOf` alternatives, JSON types
+    /// and enum membership.
+    /// Returns the offending field and a message; unknown tools pass through
+    /// so `execute_tool` can report them.
+    fn validate_tool_arguments(
+        name: &str,
+        args: &serde_json::Value,
+    ) -> Result<(), (String, String)> {
+        let Some(tool) = Self::get_tools().into_iter().find(|tool| tool.name == name) else {
+            return Ok(());
+        };
+        let Some(args) = args.as_object() else {
+            return Err((
+                "arguments".to_string(),
+                "arguments must be an object".to_string(),
+            ));
+        };
+
+        let schema = &tool.input_schema;
+        for field in schema
+            .get("required")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str())
+        {
+            if args.get(field).is_none_or(|v| v.is_null()) {
+                return Err((
+                    field.to_string(),
+                    format!("Missing required field '{field}'"),
+                ));
+            }
+        }
+
+        if let Some(alternatives) = schema.get("oneOf").and_then(|v| v.as_array()) {
+            let fields: Vec<&str> = alternatives
+                .iter()
+                .filter_map(|alt| alt.get("required").and_then(|v| v.as_array()))
+                .flatten()
+                .filter_map(|v| v.as_str())
+                .collect();
+            let present: Vec<&str> = fields
+                .iter()
+                .copied()
+                .filter(|field| args.get(*field).is_some_and(|v| !v.is_null()))
+                .collect();
+            let matched = alternatives
+                .iter()
+                .filter(|alt| {
+                    alt.get("required")
+                        .and_then(|v| v.as_array())
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|v| v.as_str())
+                        .all(|field| present.contains(&field))
+                })
+                .count();
+            if matched != 1 {
+                let field = present
+                    .get(1)
+                    .or(fields.first())
+                    .copied()
+                    .unwrap_or("arguments");
+                let choices = fields
+                    .iter()
+                    .map(|f| format!("'{f}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err((
+                    field.to_string(),
+                    format!("Provide exactly one of {choices}"),
+                ));
+            }
+        }
+
+        let Some(properties) = schema.get("properties").and_then(|v| v.as_object()) else {
+            return Ok(());
+        };
+        for (field, value) in args {
+            if value.is_null() {
+                continue;
+            }
+            if let Some(property) = properties.get(field) {
+                Self::validate_schema_value(field, property, value)?;
+            }
+        }
+
+        // A model nobody priced would make the budget guard blind to the
+        // call's real cost, so refuse it up front.
+        if let Some(model) = args.get("model").and_then(|v| v.as_str()) {
+            let uses_grok = costs::tool_model(name, &serde_json::Value::Null).is_some();
+            if uses_grok && costs::model_rate(model).is_none() {
+                return Err((
+                    "model".to_string(),
+                    format!(
+                        "No token rate configured for model '{model}'; add it to the model \
+                         rates file or XINT_MODEL_RATES"
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_schema_value(
+        field: &str,
+        schema: &serde_json::Value,
+        value: &serde_json::Value,
+    ) -> Result<(), (String, String)> {
+        let expected: Vec<&str> = match schema.get("type") {
+            Some(serde_json::Value::String(t)) => vec![t.as_str()],
+            Some(serde_json::Value::Array(types)) => {
+                types.iter().filter_map(|t| t.as_str()).collect()
+            }
+            _ => Vec::new(),
+        };
+        if !expected.is_empty() {
+            let matches = expected.iter().any(|expected| match *expected {
+                "string" => value.is_string(),
+                "number" => value.is_number(),
+                "integer" => {
+                    value.is_i64()
+                        || value.is_u64()
+                        || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+                }
+                "boolean" => value.is_boolean(),
+                "array" => value.is_array(),
+                "object" => value.is_object(),
+                _ => true,
+            });
+            if !matches {
+                return Err((
+                    field.to_string(),
+                    format!("Field '{field}' must be a {}", expected.join(" or ")),
+                ));
+            }
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(|v| v.as_array()) {
+            if !allowed.contains(value) {
+                let choices = allowed
+                    .iter()
+                    .map(|v| {
+                        v.as_str()
+                            .map(str::to_string)
+                            .unwrap_or_else(|| v.to_string())
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err((
+                    field.to_string(),
+                    format!("Field '{field}' must be one of: {choices}"),
+                ));
+            }
+        }
+
+        if let (Some(min), Some(values)) = (
+            schema.get("minItems").and_then(|v| v.as_u64()),
+            value.as_array(),
+        ) {
+            if (values.len() as u64) < min {
+                return Err((
+                    field.to_string(),
+                    format!("Field '{field}' must have at least {min} item(s)"),
+                ));
+            }
+        }
+
+        if let (Some(items), Some(values)) = (schema.get("items"), value.as_array()) {
+            for (index, item) in values.iter().enumerate() {
+                Self::validate_schema_value(&format!("{field}[{index}]"), items, item)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject `limit`/`count`/`pages` values that are not whole numbers in
+    /// range before they reach a downstream API. The schema types them as
+    /// `number`, so integral floats such as `15.0` pass and are rewritten as
+    /// the integers the handlers read.
+    fn ensure_numeric_args_valid(
+        &self,
+        name: &str,
+        args: &mut serde_json::Value,
+    ) -> Result<(), String> {
+        let bounds = [
+            ("limit", self.max_tweets),
+            ("count", self.max_tweets),
+            ("pages", MAX_PAGES),
+        ];
+        for (field, max) in bounds {
+            let Some(value) = args.get_mut(field) else {
+                continue;
+            };
+            let whole = value.as_u64().or_else(|| {
+                value
+                    .as_f64()
+                    .filter(|v| v.fract() == 0.0 && *v >= 0.0 && *v <= u64::MAX as f64)
+                    .map(|v| v as u64)
+            });
+            if let Some(whole) = whole.filter(|v| (1..=max).contains(v)) {
+                *value = serde_json::json!(whole);
+                continue;
+            }
+            return Err(serde_json::json!({
+                "code": "INVALID_ARGUMENT",
+                "message": format!("{field} must be between 1 and {max}"),
+                "tool": name,
+                "field": field,
+                "value": value,
+                "min": 1,
+                "max": max,
+            })
+            .to_string());
+        }
+        Ok(())
+    }
+
+    /// Trim whitespace and a leading `@`, then check the result is a possible
+    /// X handle (1-15 letters, digits or underscores) so malformed input gets
+    /// a clear error instead of an opaque API failure.
+    fn normalize_username(tool: &str, raw: &str) -> Result<String, String> {
+        let username = raw.trim().trim_start_matches('@');
+        let valid = (1..=15).contains(&username.len())
+            && username
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if valid {
+            return Ok(username.to_string());
+        }
+        Err(serde_json::json!({
+            "code": "INVALID_ARGUMENT",
+            "message": format!("'{raw}' is not a valid X username"),
+            "tool": tool,
+            "field": "username",
+            "value": raw,
+        })
+        .to_string())
+    }
+
+    /// Trim whitespace and a leading `#` from a hashtag and check what is left
+    /// is a single word of letters, digits or underscores.
+    fn normalize_hashtag(tool: &str, raw: &str) -> Result<String, String> {
+        let tag = raw.trim().trim_start_matches('#');
+        if !tag.is_empty() && tag.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Ok(tag.to_string());
+        }
+        Err(serde_json::json!({
+            "code": "INVALID_ARGUMENT",
+            "message": format!("'{raw}' is not a valid hashtag"),
+            "tool": tool,
+            "field": "tag",
+            "value": raw,
+        })
+        .to_string())
+    }
+
+    /// Single gate for every tool that writes to disk. Returns the resolved
+    /// path when it sits inside an `--allow-write-dir` directory.
+    fn ensure_write_path_allowed(&self, tool: &str, path: &str) -> Result<PathBuf, String> {
+        write_guard::resolve_write_path(std::path::Path::new(path), &self.allowed_write_dirs)
+            .ok_or_else(|| {
+                serde_json::json!({
+                    "code": "PATH_DENIED",
+                    "message": if self.allowed_write_dirs.is_empty() {
+                        format!("MCP tool '{tool}' cannot write files; start the server with --allow-write-dir")
+                    } else {
+                        format!("MCP tool '{tool}' cannot write outside the allowed directories")
+                    },
+                    "tool": tool,
+                    "path": path,
+                    "allowed_dirs": self.allowed_write_dirs,
+                })
+                .to_string()
+            })
     }
 
-    fn package_api_workspace_id() -> Option<String> {
-        std::env::var("XINT_WORKSPACE_ID")
+    /// `XINT_MCP_ARTIFACT_THRESHOLD_BYTES` (default 64 KiB, 0 disables).
+    fn artifact_threshold_bytes() -> usize {
+        std::env::var("XINT_MCP_ARTIFACT_THRESHOLD_BYTES")
             .ok()
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .unwrap_or(DEFAULT_ARTIFACT_THRESHOLD_BYTES)
+    }
+
+    /// Replace a tool result with an `xint://artifact/<id>` reference when the
+    /// caller asked for one (`as_artifact`) or the payload exceeds the size
+    /// threshold. Oversized results stay inline when no write dir is allowed.
+    fn store_result_artifact(
+        &self,
+        tool: &str,
+        result: Vec<MCPContent>,
+        requested: bool,
+    ) -> Result<Vec<MCPContent>, String> {
+        let bytes: usize = result.iter().map(|c| c.text.len()).sum();
+        let threshold = Self::artifact_threshold_bytes();
+        let oversized = threshold > 0 && bytes > threshold;
+        if !requested && (!oversized || self.allowed_write_dirs.is_empty()) {
+            return Ok(result);
+        }
+
+        let id = format!(
+            "{tool}-{}-{}",
+            chrono::Utc::now().timestamp_millis(),
+            lock_state(&self.artifacts).len() + 1
+        );
+        let base = self.allowed_write_dirs.first().cloned().unwrap_or_default();
+        let target = base.join("artifacts").join(format!("{id}.json"));
+        let path = self.ensure_write_path_allowed(tool, &target.to_string_lossy())?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create artifact dir: {e}"))?;
+        }
+        let text = result
+            .iter()
+            .map(|c| c.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(&path, text).map_err(|e| format!("Failed to write artifact: {e}"))?;
+        lock_state(&self.artifacts).insert(id.clone(), path);
+
+        let summary = serde_json::json!({
+            "type": "success",
+            "message": format!("Result stored as artifact ({bytes} bytes). Fetch it with resources/read."),
+            "data": {
+                "uri": format!("{ARTIFACT_URI_PREFIX}{id}"),
+                "mime_type": "application/json",
+                "bytes": bytes,
+            }
+        });
+        Ok(vec![MCPContent {
+            content_type: "text".to_string(),
+            text: serde_json::to_string_pretty(&summary)
+                .map_err(|e| format!("Failed to encode MCP response payload: {e}"))?,
+        }])
+    }
+
+    /// Remember a successful search/article result as a resource, newest
+    /// first. Re-running the same call replaces its entry.
+    fn cache_result(&self, tool: &str, args: &serde_json::Value, result: &[MCPContent]) {
+        let (uri, name) = match tool {
+            "xint_search" => {
+                let query = args.get("query").and_then(|v| v.as_str()).unwrap_or("");
+                (
+                    format!(
+                        "xint://search/{}",
+                        cache::cache_key(query, &args.to_string())
+                    ),
+                    format!("search: {query}"),
+                )
+            }
+            "xint_article" => {
+                let url = args.get("url").and_then(|v| v.as_str()).unwrap_or("");
+                (
+                    format!("xint://article/{}", cache::cache_key(url, "")),
+                    format!("article: {url}"),
+                )
+            }
+            _ => return,
+        };
+        let text = result
+            .iter()
+            .map(|c| c.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut cached_results = lock_state(&self.cached_results);
+        cached_results.retain(|entry| entry.uri != uri);
+        cached_results.push_front(CachedResult { uri, name, text });
+        cached_results.truncate(MAX_CACHED_RESULTS);
+    }
+
+    /// Keep the `data` payload of a successful tool result as the session's
+    /// export candidate. Results without structured data leave the previous
+    /// one in place.
+    fn remember_last_result(&self, session_id: Option<&str>, tool: &str, result: &[MCPContent]) {
+        if tool == "xint_export" {
+            return;
+        }
+        let Some(data) = result.iter().find_map(|c| {
+            serde_json::from_str::<serde_json::Value>(&c.text)
+                .ok()?
+                .get("data")
+                .filter(|d| !d.is_null())
+                .cloned()
+        }) else {
+            return;
+        };
+        lock_state(&self.last_results).insert(
+            session_id.unwrap_or_default().to_string(),
+            LastResult {
+                tool: tool.to_string(),
+                data,
+            },
+        );
+    }
+
+    fn list_resources(&self) -> serde_json::Value {
+        let cached_results = lock_state(&self.cached_results);
+        let artifacts = lock_state(&self.artifacts);
+        let cached = cached_results.iter().map(|entry| {
+            serde_json::json!({
+                "uri": entry.uri,
+                "name": entry.name,
+                "mimeType": "application/json",
+            })
+        });
+        let artifacts = artifacts.keys().map(|id| {
+            serde_json::json!({
+                "uri": format!("{ARTIFACT_URI_PREFIX}{id}"),
+                "name": id,
+                "mimeType": "application/json",
+            })
+        });
+        let resources: Vec<_> = cached.chain(artifacts).collect();
+        serde_json::json!({ "resources": resources })
+    }
+
+    fn read_resource(&self, uri: &str) -> Result<serde_json::Value, String> {
+        if let Some(entry) = lock_state(&self.cached_results)
+            .iter()
+            .find(|entry| entry.uri == uri)
+        {
+            return Ok(serde_json::json!({
+                "contents": [{
+                    "uri": uri,
+                    "mimeType": "application/json",
+                    "text": entry.text,
+                }]
+            }));
+        }
+        let path = uri
+            .strip_prefix(ARTIFACT_URI_PREFIX)
+            .and_then(|id| lock_state(&self.artifacts).get(id).cloned())
+            .ok_or_else(|| format!("Resource not found: {uri}"))?;
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read resource {uri}: {e}"))?;
+        Ok(serde_json::json!({
+            "contents": [{
+                "uri": uri,
+                "mimeType": "application/json",
+                "text": text,
+            }]
+        }))
+    }
+
+    fn rate_limit_status(&self, tool: Option<&str>) -> serde_json::Value {
+        let mut limiter = lock_state(&self.rate_limiter);
+        let names: Vec<String> = match tool {
+            Some(name) => vec![name.to_string()],
+            None => Self::get_tools().into_iter().map(|t| t.name).collect(),
+        };
+        let tools: Vec<_> = names.iter().map(|name| limiter.state(name)).collect();
+        serde_json::json!({
+            "enabled": limiter.enabled(),
+            "limit_per_window": limiter.capacity(),
+            "window_seconds": limiter.window().as_secs(),
+            "tools": tools,
+        })
     }
 
-    fn billing_upgrade_url() -> String {
-        std::env::var("XINT_BILLING_UPGRADE_URL")
+    fn env_or_setting(key: &str, setting: &Option<String>) -> Option<String> {
+        std::env::var(key)
             .ok()
+            .or_else(|| setting.clone())
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty())
-            .unwrap_or_else(|| "https://xint.dev/pricing".to_string())
+    }
+
+    fn package_api_base_url(&self) -> Option<String> {
+        Self::env_or_setting(
+            "XINT_PACKAGE_API_BASE_URL",
+            &self.settings.package_api.base_url,
+        )
+    }
+
+    fn package_api_key(&self) -> Option<String> {
+        Self::env_or_setting("XINT_PACKAGE_API_KEY", &self.settings.package_api.api_key)
+    }
+
+    fn package_api_workspace_id(&self) -> Option<String> {
+        Self::env_or_setting("XINT_WORKSPACE_ID", &self.settings.package_api.workspace_id)
+    }
+
+    fn billing_upgrade_url(&self) -> String {
+        Self::env_or_setting(
+            "XINT_BILLING_UPGRADE_URL",
+            &self.settings.package_api.billing_upgrade_url,
+        )
+        .unwrap_or_else(|| "https://xint.dev/pricing".to_string())
     }
 
     async fn call_package_api(
@@ -500,17 +2609,24 @@ impl MCPServer {
         path: &str,
         body: Option<serde_json::Value>,
     ) -> Result<serde_json::Value, String> {
-        let base = Self::package_api_base_url().ok_or_else(|| {
+        let base = self.package_api_base_url().ok_or_else(|| {
             "XINT_PACKAGE_API_BASE_URL not set. Start xint-cloud service on :8787 and set XINT_PACKAGE_API_BASE_URL=http://localhost:8787/v1".to_string()
         })?;
         let url = format!("{}{}", base.trim_end_matches('/'), path);
+        let _permit = self
+            .package_api_permits
+            .acquire()
+            .await
+            .map_err(|e| format!("Package API limiter closed: {e}"))?;
 
-        let client = reqwest::Client::new();
-        let mut req = client.request(method, &url);
-        if let Some(key) = Self::package_api_key() {
+        let mut req = reqwest::Client::new().request(method, &url);
+        if let Some(seconds) = self.settings.timeouts.package_api_seconds {
+            req = req.timeout(std::time::Duration::from_secs(seconds));
+        }
+        if let Some(key) = self.package_api_key() {
             req = req.header(reqwest::header::AUTHORIZATION, format!("Bearer {key}"));
         }
-        if let Some(workspace_id) = Self::package_api_workspace_id() {
+        if let Some(workspace_id) = self.package_api_workspace_id() {
             req = req.header("x-workspace-id", workspace_id);
         }
         if let Some(ref payload) = body {
@@ -545,7 +2661,7 @@ impl MCPServer {
                     code,
                     "PLAN_REQUIRED" | "QUOTA_EXCEEDED" | "FEATURE_NOT_IN_PLAN"
                 ) {
-                    message = format!("{message}. Upgrade: {}", Self::billing_upgrade_url());
+                    message = format!("{message}. Upgrade: {}", self.billing_upgrade_url());
                 }
                 return Err(message);
             }
@@ -563,54 +2679,427 @@ impl MCPServer {
             .map_err(|e| format!("Package API JSON decode failed: {e}"))
     }
 
-    fn ensure_package_query_citations(
-        &self,
-        result: &serde_json::Value,
-        require_citations: bool,
-    ) -> Result<(), String> {
-        if !require_citations {
-            return Ok(());
+    /// Keyword-overlap relevance of a trend to a topic: the share of topic
+    /// words found in the trend name, ignoring case, `#`/`$` and spacing.
+    fn trend_relevance(topic: &str, trend: &str) -> f64 {
+        let normalized: String = trend
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect();
+        let words: Vec<String> = topic
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.chars().count() >= 2)
+            .map(str::to_lowercase)
+            .collect();
+        if words.is_empty() || normalized.is_empty() {
+            return 0.0;
         }
-        let obj = result
-            .as_object()
-            .ok_or_else(|| "Package API query response must be a JSON object.".to_string())?;
+        let matched = words
+            .iter()
+            .filter(|w| normalized.contains(w.as_str()))
+            .count();
+        matched as f64 / words.len() as f64
+    }
 
-        let claims = obj
-            .get("claims")
-            .and_then(serde_json::Value::as_array)
-            .cloned()
-            .unwrap_or_default();
-        let citations = obj
-            .get("citations")
-            .and_then(serde_json::Value::as_array)
-            .cloned()
-            .unwrap_or_default();
+    /// Error string for a failed X lookup. Protected, suspended, deleted and
+    /// missing resources become a JSON payload with a stable `code` so agents
+    /// can branch on them; anything else stays a plain message.
+    fn tool_error(context: &str, err: anyhow::Error) -> String {
+        let Some(unavailable) = err.downcast_ref::<availability::ResourceUnavailable>() else {
+            return format!("{context}: {err}");
+        };
+        serde_json::json!({
+            "code": unavailable.kind.code(),
+            "message": format!("{context}: {unavailable}"),
+            "resource_type": unavailable.resource_type,
+            "resource_id": unavailable.resource_id,
+        })
+        .to_string()
+    }
 
-        if !claims.is_empty() && citations.is_empty() {
-            return Err(
-                "Package API query response missing citations while require_citations=true."
-                    .to_string(),
-            );
-        }
+    /// Search link for a trend name; `#` and spaces must be escaped or the
+    /// hashtag is lost as a URL fragment.
+    fn trend_search_url(name: &str) -> String {
+        let query: String = url::form_urlencoded::byte_serialize(name.as_bytes()).collect();
+        format!("https://x.com/search?q={query}")
+    }
 
-        let claim_ids: std::collections::HashSet<String> = claims
+    /// Spaces search needs an access level many apps lack, so 401/403/404
+    /// surface as a typed `CAPABILITY_UNAVAILABLE` error rather than a failure.
+    fn spaces_error(tool: &str, err: anyhow::Error) -> String {
+        let unavailable = ["X API 401", "X API 403", "X API 404"]
             .iter()
-            .filter_map(|claim| {
-                claim
-                    .as_object()
-                    .and_then(|item| item.get("claim_id"))
-                    .and_then(serde_json::Value::as_str)
-                    .map(ToOwned::to_owned)
-            })
-            .collect();
-
-        if claim_ids.is_empty() {
-            return Ok(());
+            .any(|status| err.to_string().starts_with(status));
+        if !unavailable {
+            return Self::tool_error("Spaces search failed", err);
         }
+        serde_json::json!({
+            "code": "CAPABILITY_UNAVAILABLE",
+            "message": format!(
+                "Spaces search is not available with the configured X API access: {err}"
+            ),
+            "tool": tool,
+        })
+        .to_string()
+    }
 
-        let cited_claim_ids: std::collections::HashSet<String> = citations
-            .iter()
-            .filter_map(|citation| {
+    /// `xint_following`/`xint_followers` result: each account reduced to the
+    /// same snapshot `xint diff` stores.
+    fn relationships_content(
+        username: &str,
+        snap_type: &str,
+        users: &[serde_json::Value],
+    ) -> Result<Vec<MCPContent>, String> {
+        let users: Vec<_> = users.iter().map(diff::user_snapshot).collect();
+        let message = if snap_type == "following" {
+            format!("@{username} follows {} listed account(s).", users.len())
+        } else {
+            format!("@{username} has {} listed follower(s).", users.len())
+        };
+        Self::retrieval_content(
+            message,
+            users.len(),
+            serde_json::json!({
+                "username": username,
+                "relationship": snap_type,
+                "users": users
+            }),
+        )
+    }
+
+    /// `xint_retweeters` result, with the combined follower count of every
+    /// retweeter as a rough measure of reach.
+    fn retweeters_content(
+        tweet_id: &str,
+        users: &[crate::models::UserInfo],
+    ) -> Result<Vec<MCPContent>, String> {
+        let total_followers: u64 = users.iter().map(|u| u.followers).sum();
+        Self::retrieval_content(
+            format!("Found {} retweeters.", users.len()),
+            users.len(),
+            serde_json::json!({
+                "tweet_id": tweet_id,
+                "total_followers": total_followers,
+                "users": users
+            }),
+        )
+    }
+
+    /// `xint_thread` result: an outline block, then the page in reading
+    /// order (root first, then replies oldest first) and whether more pages
+    /// remain.
+    fn thread_content(mut thread: ThreadPage) -> Result<Vec<MCPContent>, String> {
+        let message = if thread.complete {
+            "Thread lookup completed.".to_string()
+        } else {
+            format!(
+                "Partial thread: fetched {} pages; pass cursor to continue.",
+                thread.fetched_pages
+            )
+        };
+        let outline = crate::format::format_thread_outline(&thread.tweets);
+        thread.tweets.sort_by(|a, b| {
+            let is_reply = |t: &Tweet| t.id != t.conversation_id;
+            is_reply(a)
+                .cmp(&is_reply(b))
+                .then_with(|| a.created_at.cmp(&b.created_at))
+        });
+        let mut content =
+            Self::retrieval_content(message, thread.tweets.len(), serde_json::json!(thread))?;
+        if !outline.is_empty() {
+            content.insert(
+                0,
+                MCPContent {
+                    content_type: "text".to_string(),
+                    text: outline,
+                },
+            );
+        }
+        Ok(content)
+    }
+
+    /// The quote_tweets endpoint is not on every API tier, so a 403 becomes
+    /// an empty, explained result with `quotes_available: false`. `None` for
+    /// any other failure.
+    fn quotes_unavailable_content(
+        tweet_id: &str,
+        err: &anyhow::Error,
+    ) -> Option<Result<Vec<MCPContent>, String>> {
+        if !err.to_string().starts_with("X API 403") {
+            return None;
+        }
+        Some(Self::retrieval_content(
+            "Quote tweets are unavailable for this tweet or API access level.".to_string(),
+            0,
+            serde_json::json!({
+                "tweet_id": tweet_id,
+                "quotes_available": false,
+                "reason": err.to_string(),
+                "tweets": []
+            }),
+        ))
+    }
+
+    /// `xint_quotes` result: the quote tweets plus one citation per quote
+    /// carrying only the commentary it adds.
+    fn quotes_content(
+        tweet_id: &str,
+        tweets: &[Tweet],
+        next_cursor: Option<String>,
+    ) -> Result<Vec<MCPContent>, String> {
+        let citations: Vec<_> = tweets
+            .iter()
+            .map(|t| {
+                serde_json::json!({
+                    "tweet_id": t.id,
+                    "username": t.username,
+                    "url": t.tweet_url,
+                    "commentary": quote_commentary(&t.text),
+                })
+            })
+            .collect();
+        Self::retrieval_content(
+            format!("Found {} quote tweets.", tweets.len()),
+            tweets.len(),
+            serde_json::json!({
+                "tweet_id": tweet_id,
+                "quotes_available": true,
+                "tweets": tweets,
+                "citations": citations,
+                "next_cursor": next_cursor
+            }),
+        )
+    }
+
+    /// Shape a retrieval tool result. Empty results stay successful but carry
+    /// `count: 0`, `no_results: true` and a leading plain-text block so agents
+    /// can tell "nothing matched" apart from a failed call.
+    fn retrieval_content(
+        message: String,
+        count: usize,
+        data: serde_json::Value,
+    ) -> Result<Vec<MCPContent>, String> {
+        let mut payload = serde_json::json!({
+            "type": "success",
+            "message": message,
+            "count": count,
+            "data": data,
+        });
+        let mut content = Vec::new();
+        if count == 0 {
+            payload["message"] = serde_json::json!("No matching tweets found.");
+            payload["no_results"] = serde_json::json!(true);
+            content.push(MCPContent {
+                content_type: "text".to_string(),
+                text: "No matching tweets found.".to_string(),
+            });
+        }
+        let encoded = serde_json::to_string_pretty(&payload)
+            .map_err(|e| format!("Failed to encode MCP response payload: {e}"))?;
+        content.push(MCPContent {
+            content_type: "text".to_string(),
+            text: encoded,
+        });
+        Ok(content)
+    }
+
+    fn package_create_payload(args: &serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "name": args.get("name").and_then(|v| v.as_str()).unwrap_or(""),
+            "topic_query": args
+                .get("topic_query")
+                .and_then(|v| v.as_str())
+                .unwrap_or(""),
+            "sources": args
+                .get("sources")
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default(),
+            "time_window": args.get("time_window").cloned().unwrap_or_else(|| {
+                serde_json::json!({
+                    "from": chrono::Utc::now()
+                        .checked_sub_signed(chrono::Duration::days(1))
+                        .unwrap_or_else(chrono::Utc::now)
+                        .to_rfc3339(),
+                    "to": chrono::Utc::now().to_rfc3339()
+                })
+            }),
+            "policy": args
+                .get("policy")
+                .and_then(|v| v.as_str())
+                .unwrap_or("private"),
+            "analysis_profile": args
+                .get("analysis_profile")
+                .and_then(|v| v.as_str())
+                .unwrap_or("summary")
+        })
+    }
+
+    /// `XINT_PACKAGE_MAX_WINDOW_DAYS`, then the config file, then 90 days.
+    fn package_max_window_days(&self) -> i64 {
+        std::env::var("XINT_PACKAGE_MAX_WINDOW_DAYS")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .or(self.settings.package_api.max_window_days)
+            .unwrap_or(DEFAULT_PACKAGE_MAX_WINDOW_DAYS)
+            .max(1)
+    }
+
+    /// Check a package create payload against the v1 draft contract.
+    /// Returns every problem found as `{ field, message }`; an empty list
+    /// means the spec is valid.
+    fn validate_package_spec(&self, payload: &serde_json::Value) -> Vec<serde_json::Value> {
+        const SOURCES: &[&str] = &["x_api_v2", "xai_search", "web_article"];
+        const POLICIES: &[&str] = &["private", "shared_candidate"];
+        const PROFILES: &[&str] = &["summary", "analyst", "forensic"];
+
+        let mut problems = Vec::new();
+        let mut problem = |field: &str, message: String| {
+            problems.push(serde_json::json!({ "field": field, "message": message }));
+        };
+        let str_field = |key: &str| payload.get(key).and_then(|v| v.as_str()).unwrap_or("");
+
+        for key in ["name", "topic_query"] {
+            if str_field(key).trim().is_empty() {
+                problem(key, format!("{key} is required"));
+            }
+        }
+
+        let sources = payload
+            .get("sources")
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+        if sources.is_empty() {
+            problem(
+                "sources",
+                "sources must list at least one source".to_string(),
+            );
+        }
+        for source in &sources {
+            match source.as_str() {
+                Some(name) if SOURCES.contains(&name) => {}
+                Some(name) => problem(
+                    "sources",
+                    format!(
+                        "unknown source '{name}' (expected one of: {})",
+                        SOURCES.join(", ")
+                    ),
+                ),
+                None => problem("sources", "sources entries must be strings".to_string()),
+            }
+        }
+
+        let window = payload.get("time_window");
+        let mut parse = |key: &str| {
+            let field = format!("time_window.{key}");
+            let raw = window.and_then(|w| w.get(key)).and_then(|v| v.as_str());
+            let Some(raw) = raw else {
+                problem(&field, format!("{field} is required"));
+                return None;
+            };
+            let parsed = chrono::DateTime::parse_from_rfc3339(raw).ok();
+            if parsed.is_none() {
+                problem(
+                    &field,
+                    format!("{field} '{raw}' is not a valid RFC3339 timestamp"),
+                );
+            }
+            parsed
+        };
+        let from = parse("from");
+        let to = parse("to");
+        if let (Some(from), Some(to)) = (from, to) {
+            let max_days = self.package_max_window_days();
+            if from >= to {
+                problem(
+                    "time_window",
+                    "time_window.from must be before time_window.to".to_string(),
+                );
+            } else if to - from > chrono::Duration::days(max_days) {
+                problem(
+                    "time_window",
+                    format!(
+                        "time_window spans {} days; the maximum is {max_days} (XINT_PACKAGE_MAX_WINDOW_DAYS)",
+                        (to - from).num_days()
+                    ),
+                );
+            }
+        }
+
+        let policy = str_field("policy");
+        if !POLICIES.contains(&policy) {
+            problem(
+                "policy",
+                format!(
+                    "unknown policy '{policy}' (expected one of: {})",
+                    POLICIES.join(", ")
+                ),
+            );
+        }
+        let profile = str_field("analysis_profile");
+        if !PROFILES.contains(&profile) {
+            problem(
+                "analysis_profile",
+                format!(
+                    "unknown analysis_profile '{profile}' (expected one of: {})",
+                    PROFILES.join(", ")
+                ),
+            );
+        }
+
+        problems
+    }
+
+    fn ensure_package_query_citations(
+        &self,
+        result: &serde_json::Value,
+        require_citations: bool,
+    ) -> Result<(), String> {
+        if !require_citations {
+            return Ok(());
+        }
+        let obj = result
+            .as_object()
+            .ok_or_else(|| "Package API query response must be a JSON object.".to_string())?;
+
+        let claims = obj
+            .get("claims")
+            .and_then(serde_json::Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        let citations = obj
+            .get("citations")
+            .and_then(serde_json::Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        if !claims.is_empty() && citations.is_empty() {
+            return Err(
+                "Package API query response missing citations while require_citations=true."
+                    .to_string(),
+            );
+        }
+
+        let claim_ids: std::collections::HashSet<String> = claims
+            .iter()
+            .filter_map(|claim| {
+                claim
+                    .as_object()
+                    .and_then(|item| item.get("claim_id"))
+                    .and_then(serde_json::Value::as_str)
+                    .map(ToOwned::to_owned)
+            })
+            .collect();
+
+        if claim_ids.is_empty() {
+            return Ok(());
+        }
+
+        let cited_claim_ids: std::collections::HashSet<String> = citations
+            .iter()
+            .filter_map(|citation| {
                 let obj = citation.as_object()?;
                 let claim_id = obj.get("claim_id")?.as_str()?;
                 let url = obj.get("url")?.as_str()?;
@@ -631,27 +3120,129 @@ impl MCPServer {
         Ok(())
     }
 
-    pub async fn handle_message(&mut self, msg: &str) -> Result<Option<String>, String> {
-        let parsed: serde_json::Value =
-            serde_json::from_str(msg).map_err(|e| format!("Failed to parse JSON: {e}"))?;
+    pub async fn handle_message(&self, msg: &str) -> Result<Option<String>, RequestError> {
+        self.handle_session_message(None, msg).await
+    }
+
+    /// Handle one JSON-RPC message on behalf of `session_id` (the
+    /// `Mcp-Session-Id` of an HTTP/SSE client). Budget-guarded tool spend is
+    /// attributed to the session so its sub-budget can be enforced alongside
+    /// the global cap.
+    pub async fn handle_session_message(
+        &self,
+        session_id: Option<&str>,
+        msg: &str,
+    ) -> Result<Option<String>, RequestError> {
+        let parsed: serde_json::Value = serde_json::from_str(msg).map_err(|e| RequestError {
+            id: serde_json::Value::Null,
+            code: -32700,
+            message: format!("Failed to parse JSON: {e}"),
+        })?;
+
+        let serde_json::Value::Array(batch) = parsed else {
+            let id = parsed.get("id").cloned().unwrap_or_default();
+            return self
+                .handle_request(session_id, parsed)
+                .await
+                .map_err(|message| RequestError {
+                    id,
+                    code: -32603,
+                    message,
+                });
+        };
+        if batch.is_empty() {
+            return Ok(Some(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": { "code": -32600, "message": "Invalid Request: empty batch" }
+                })
+                .to_string(),
+            ));
+        }
+
+        // JSON-RPC batches answer every request that has an id, in order;
+        // a batch of only notifications produces no output at all.
+        let mut responses = Vec::new();
+        for request in batch {
+            let id = request.get("id").cloned();
+            match self.handle_request(session_id, request).await {
+                Ok(Some(response)) => responses.push(response),
+                Ok(None) => {}
+                Err(err) => responses.push(
+                    serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": -32603, "message": err }
+                    })
+                    .to_string(),
+                ),
+            }
+        }
+        if responses.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(format!("[{}]", responses.join(","))))
+    }
 
+    #[tracing::instrument(
+        name = "mcp_request",
+        skip_all,
+        fields(method = tracing::field::Empty, id = tracing::field::Empty, session = session_id)
+    )]
+    async fn handle_request(
+        &self,
+        session_id: Option<&str>,
+        parsed: serde_json::Value,
+    ) -> Result<Option<String>, String> {
         let method = parsed
             .get("method")
             .and_then(|v| v.as_str())
             .ok_or("Missing method field")?;
 
         let id = parsed.get("id");
+        let span = tracing::Span::current();
+        span.record("method", method);
+        if let Some(id) = id {
+            span.record("id", tracing::field::display(id));
+        }
+        tracing::debug!("request received");
+
+        if matches!(method, "tools/list" | "tools/call") && !self.is_initialized(session_id) {
+            return Ok(Some(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32002, "message": "Server not initialized" }
+                })
+                .to_string(),
+            ));
+        }
 
         match method {
             "initialize" => {
-                self.initialized = true;
+                match session_id {
+                    Some(session) => {
+                        lock_state(&self.initialized_sessions).insert(session.to_string());
+                    }
+                    None => self
+                        .initialized
+                        .store(true, std::sync::atomic::Ordering::SeqCst),
+                }
+                let requested = parsed
+                    .get("params")
+                    .and_then(|p| p.get("protocolVersion"))
+                    .and_then(|v| v.as_str());
                 let response = serde_json::json!({
                     "jsonrpc": "2.0",
                     "id": id,
                     "result": {
-                        "protocolVersion": "2024-11-05",
+                        "protocolVersion": Self::negotiate_protocol_version(requested),
                         "capabilities": {
-                            "tools": {}
+                            "tools": {
+                                "pagination": { "pageSize": TOOLS_PAGE_SIZE }
+                            },
+                            "resources": {}
                         },
                         "serverInfo": {
                             "name": "xint",
@@ -665,14 +3256,53 @@ impl MCPServer {
                 // Client confirmed initialization
                 Ok(None)
             }
+            "notifications/cancelled" => {
+                apply_cancellation(&self.cancellations, session_id, &parsed.to_string());
+                Ok(None)
+            }
             "tools/list" => {
-                let tools = Self::get_tools();
+                let tools: Vec<_> = Self::get_tools()
+                    .into_iter()
+                    .filter(|tool| self.settings.tool_enabled(&tool.name))
+                    .collect();
+                let cursor = parsed
+                    .get("params")
+                    .and_then(|p| p.get("cursor"))
+                    .filter(|c| !c.is_null());
+                let Some(cursor) = cursor else {
+                    let response = serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "result": {
+                            "tools": tools
+                        }
+                    });
+                    return Ok(Some(response.to_string()));
+                };
+
+                let Some(offset) = cursor
+                    .as_str()
+                    .and_then(Self::decode_tools_cursor)
+                    .filter(|offset| *offset <= tools.len())
+                else {
+                    return Ok(Some(
+                        serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "error": { "code": -32602, "message": "Invalid params: unknown cursor" }
+                        })
+                        .to_string(),
+                    ));
+                };
+                let end = (offset + TOOLS_PAGE_SIZE).min(tools.len());
+                let mut result = serde_json::json!({ "tools": &tools[offset..end] });
+                if end < tools.len() {
+                    result["nextCursor"] = serde_json::json!(Self::encode_tools_cursor(end));
+                }
                 let response = serde_json::json!({
                     "jsonrpc": "2.0",
                     "id": id,
-                    "result": {
-                        "tools": tools
-                    }
+                    "result": result
                 });
                 Ok(Some(response.to_string()))
             }
@@ -683,22 +3313,181 @@ impl MCPServer {
                     .get("name")
                     .and_then(|v| v.as_str())
                     .ok_or("Missing tool name")?;
-                let arguments = params
+                let mut arguments = params
                     .get("arguments")
                     .cloned()
                     .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
+                let as_artifact = arguments
+                    .get("as_artifact")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+
+                if let Err((field, message)) = Self::validate_tool_arguments(name, &arguments) {
+                    return Ok(Some(
+                        serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "id": id,
+                            "error": {
+                                "code": -32602,
+                                "message": format!("Invalid params: {message}"),
+                                "data": { "field": field }
+                            }
+                        })
+                        .to_string(),
+                    ));
+                }
 
-                let execution: Result<Vec<MCPContent>, String> =
-                    if let Err(err) = self.ensure_tool_allowed(name) {
-                        Err(err)
-                    } else if let Err(err) = self.ensure_budget_allowed(name) {
-                        Err(err)
+                let progress_token = params
+                    .get("_meta")
+                    .and_then(|meta| meta.get("progressToken"))
+                    .cloned();
+                self.send_progress(progress_token.as_ref(), 0, &format!("{name} started"));
+
+                // Checked before the budget and rate limiter so a rejected
+                // call holds neither.
+                let numeric_check = self.ensure_numeric_args_valid(name, &mut arguments);
+                let cache_args = arguments.clone();
+                let guarded = Self::tool_budget_guarded(name);
+                let estimated_usd = costs::estimate(name, &arguments);
+
+                let mut reservation = None;
+                let call_usage = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+                let call_fetch_usd = std::sync::Arc::new(std::sync::Mutex::new(0.0));
+                // Record what the call spent, even when it was cut short.
+                let record_call = || {
+                    let budget = self.daily_budget();
+                    let usage = lock_state(&call_usage).clone();
+                    let cost = costs::record(
+                        &self.costs_path,
+                        name,
+                        costs::tool_model(name, &cache_args).as_deref(),
+                        estimated_usd,
+                        *lock_state(&call_fetch_usd),
+                        &usage,
+                        &budget,
+                    );
+                    if let Some(session) = session_id {
+                        self.record_session_spend(session, cost.actual_usd);
+                    }
+                    (budget, cost)
+                };
+                let execution: Result<Vec<MCPContent>, String> = match numeric_check
+                    .and_then(|()| self.ensure_tool_call_allowed(name, session_id, estimated_usd))
+                {
+                    Err(err) => Err(err),
+                    Ok(held) => {
+                        reservation = held;
+                        let key =
+                            cancellation_key(session_id, id.unwrap_or(&serde_json::Value::Null));
+                        let (cancel_tx, mut cancel_rx) = tokio::sync::watch::channel(false);
+                        let Some(in_flight) =
+                            InFlightCall::register(&self.cancellations, key, cancel_tx)
+                        else {
+                            let message =
+                                "Invalid Request: a call with this id is already in flight";
+                            return Ok(Some(
+                                serde_json::json!({
+                                    "jsonrpc": "2.0",
+                                    "id": id,
+                                    "error": { "code": -32600, "message": message }
+                                })
+                                .to_string(),
+                            ));
+                        };
+                        let _permit = self
+                            .tool_permits
+                            .acquire()
+                            .await
+                            .map_err(|e| format!("Tool concurrency limiter closed: {e}"))?;
+                        let execution = CURRENT_SESSION.scope(
+                            session_id.map(str::to_string),
+                            PROGRESS_TOKEN.scope(
+                                progress_token.clone(),
+                                CALL_USAGE.scope(
+                                    call_usage.clone(),
+                                    costs::CALL_FETCH_USD.scope(
+                                        call_fetch_usd.clone(),
+                                        self.execute_tool_with_timeout(name, arguments),
+                                    ),
+                                ),
+                            ),
+                        );
+                        let outcome = tokio::select! {
+                            result = execution => Some(result),
+                            _ = cancel_rx.wait_for(|cancelled| *cancelled) => None,
+                        };
+                        drop(in_flight);
+                        let Some(result) = outcome else {
+                            if guarded {
+                                record_call();
+                            }
+                            reliability::record_command_result(
+                                &self.reliability_path,
+                                &format!("mcp:{name}"),
+                                false,
+                                started_at.elapsed().as_millis(),
+                                reliability::ReliabilityMode::Mcp,
+                                false,
+                            );
+                            tracing::info!(
+                                tool = name,
+                                elapsed_ms = started_at.elapsed().as_millis() as u64,
+                                "tool call cancelled"
+                            );
+                            return Ok(None);
+                        };
+                        result
+                    }
+                };
+                let (budget_after, call_cost) = guarded.then(record_call).unzip();
+                if let Some(reservation) = reservation {
+                    reservation.settle();
+                }
+                if let Ok(result) = &execution {
+                    self.cache_result(name, &cache_args, result);
+                    self.remember_last_result(session_id, name, result);
+                }
+                let execution = execution
+                    .and_then(|result| self.store_result_artifact(name, result, as_artifact));
+                self.send_progress(
+                    progress_token.as_ref(),
+                    100,
+                    if execution.is_ok() {
+                        "completed"
                     } else {
-                        self.execute_tool(name, arguments).await
-                    };
+                        "failed"
+                    },
+                );
 
                 match execution {
-                    Ok(result) => {
+                    Ok(mut result) => {
+                        if let Some(cost) = &call_cost {
+                            result.push(MCPContent {
+                                content_type: "text".to_string(),
+                                text: serde_json::json!({ "type": "cost", "data": cost })
+                                    .to_string(),
+                            });
+                        }
+                        if let Some(budget) = budget_after.filter(|b| b.warning) {
+                            result.push(MCPContent {
+                                content_type: "text".to_string(),
+                                text: serde_json::json!({
+                                    "type": "budget_warning",
+                                    "message": format!(
+                                        "Daily budget {}% used (${:.2} of ${:.2}); calls are denied at 100%.",
+                                        budget.percent_used, budget.spent, budget.limit
+                                    ),
+                                    "data": {
+                                        "percent_used": budget.percent_used,
+                                        "warn_threshold": budget.warn_threshold,
+                                        "spent_usd": budget.spent,
+                                        "limit_usd": budget.limit,
+                                        "remaining_usd": budget.remaining
+                                    }
+                                })
+                                .to_string(),
+                            });
+                        }
                         let command_name = format!("mcp:{name}");
                         reliability::record_command_result(
                             &self.reliability_path,
@@ -708,6 +3497,11 @@ impl MCPServer {
                             reliability::ReliabilityMode::Mcp,
                             reliability::consume_command_fallback(&command_name),
                         );
+                        tracing::info!(
+                            tool = name,
+                            elapsed_ms = started_at.elapsed().as_millis() as u64,
+                            "tool call succeeded"
+                        );
                         let response = serde_json::json!({
                             "jsonrpc": "2.0",
                             "id": id,
@@ -726,6 +3520,12 @@ impl MCPServer {
                             reliability::ReliabilityMode::Mcp,
                             false,
                         );
+                        tracing::warn!(
+                            tool = name,
+                            elapsed_ms = started_at.elapsed().as_millis() as u64,
+                            error = %err,
+                            "tool call failed"
+                        );
                         let response = serde_json::json!({
                             "jsonrpc": "2.0",
                             "id": id,
@@ -738,6 +3538,46 @@ impl MCPServer {
                     }
                 }
             }
+            "resources/list" => {
+                let response = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": self.list_resources()
+                });
+                Ok(Some(response.to_string()))
+            }
+            "resources/read" => {
+                let uri = parsed
+                    .get("params")
+                    .and_then(|p| p.get("uri"))
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing resource uri")?;
+                let response = match self.read_resource(uri) {
+                    Ok(result) => serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "result": result
+                    }),
+                    Err(message) => serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": -32002, "message": message }
+                    }),
+                };
+                Ok(Some(response.to_string()))
+            }
+            "xint/ratelimit" => {
+                let tool = parsed
+                    .get("params")
+                    .and_then(|p| p.get("tool"))
+                    .and_then(|v| v.as_str());
+                let response = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": self.rate_limit_status(tool)
+                });
+                Ok(Some(response.to_string()))
+            }
             _ => {
                 let response = serde_json::json!({
                     "jsonrpc": "2.0",
@@ -752,6 +3592,32 @@ impl MCPServer {
         }
     }
 
+    async fn execute_tool_with_timeout(
+        &self,
+        name: &str,
+        args: serde_json::Value,
+    ) -> Result<Vec<MCPContent>, String> {
+        let Some(seconds) = self.settings.timeouts.tool_seconds.filter(|s| *s > 0) else {
+            return self.execute_tool(name, args).await;
+        };
+        match tokio::time::timeout(
+            std::time::Duration::from_secs(seconds),
+            self.execute_tool(name, args),
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(_) => Err(serde_json::json!({
+                "code": "TOOL_TIMEOUT",
+                "message": format!("MCP tool '{}' timed out after {}s", name, seconds),
+                "tool": name,
+                "timeout_seconds": seconds,
+            })
+            .to_string()),
+        }
+    }
+
+    #[tracing::instrument(level = "debug", skip(self, args))]
     async fn execute_tool(
         &self,
         name: &str,
@@ -770,13 +3636,6 @@ impl MCPServer {
                 .map_err(|e| format!("Failed to encode MCP response payload: {e}"))
         }
 
-        fn bool_arg(args: &serde_json::Value, camel: &str, snake: &str) -> bool {
-            args.get(camel)
-                .and_then(|v| v.as_bool())
-                .or_else(|| args.get(snake).and_then(|v| v.as_bool()))
-                .unwrap_or(false)
-        }
-
         fn extract_tweet_id(input: &str) -> String {
             if let Some(idx) = input.find("/status/") {
                 let suffix = &input[(idx + "/status/".len())..];
@@ -918,23 +3777,144 @@ impl MCPServer {
         }
 
         async fn oauth_runtime() -> Result<(XClient, String), String> {
+            oauth_user_runtime()
+                .await
+                .map(|(client, token, _)| (client, token))
+        }
+
+        /// OAuth client, access token and the authorized user. A missing
+        /// client id or token store is reported as `OAUTH_REQUIRED`.
+        async fn oauth_user_runtime() -> Result<(XClient, String, OAuthTokens), String> {
+            let oauth_required = |message: String| {
+                serde_json::json!({
+                    "code": "OAUTH_REQUIRED",
+                    "message": message,
+                    "instructions": "Set X_CLIENT_ID, run `xint auth setup` to authorize your X account, then retry.",
+                })
+                .to_string()
+            };
             let runtime_config =
                 Config::load().map_err(|e| format!("Failed to load config: {e}"))?;
-            let client =
-                XClient::new().map_err(|e| format!("Failed to initialize HTTP client: {e}"))?;
             let client_id = runtime_config
                 .require_client_id()
-                .map_err(|e| e.to_string())?
+                .map_err(|e| oauth_required(e.to_string()))?
                 .to_string();
-            let (token, _) =
-                oauth::get_valid_token(&client, &runtime_config.tokens_path(), &client_id)
-                    .await
-                    .map_err(|e| e.to_string())?;
-            Ok((client, token))
+            let tokens_path = runtime_config.tokens_path();
+            if oauth::load_tokens(&tokens_path).is_none() {
+                return Err(oauth_required(format!(
+                    "No OAuth tokens found at {}",
+                    tokens_path.display()
+                )));
+            }
+            let client =
+                XClient::new().map_err(|e| format!("Failed to initialize HTTP client: {e}"))?;
+            let (token, tokens) = oauth::get_valid_token(&client, &tokens_path, &client_id)
+                .await
+                .map_err(|e| format!("OAuth token refresh failed: {e}"))?;
+            Ok((client, token, tokens))
         }
 
-        let route_result = resolve_tool_route(name);
-        let Some(route) = route_result.data else {
+        async fn fetch_user_relationships(
+            client: &XClient,
+            access_token: &str,
+            username: &str,
+            snap_type: &str,
+            pages: u32,
+            limit: usize,
+        ) -> Result<(String, Vec<serde_json::Value>), String> {
+            let lookup_path = format!("users/by/username/{username}?user.fields=public_metrics");
+            let lookup = client
+                .oauth_get(&lookup_path, access_token)
+                .await
+                .map_err(|e| MCPServer::tool_error(&format!("Failed to resolve @{username}"), e))?;
+            let user_id = lookup
+                .data
+                .as_ref()
+                .and_then(|d| d.get("id"))
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| {
+                    MCPServer::tool_error(
+                        "Failed to resolve user",
+                        availability::ResourceUnavailable::not_found(
+                            "user",
+                            username,
+                            format!("User @{username} not found"),
+                        )
+                        .into(),
+                    )
+                })?
+                .to_string();
+
+            let mut users = Vec::<serde_json::Value>::new();
+            let mut next_token: Option<String> = None;
+            for page_idx in 0..pages {
+                let pagination = match &next_token {
+                    Some(t) => format!("&pagination_token={t}"),
+                    None => String::new(),
+                };
+                let max_results = limit.saturating_sub(users.len()).clamp(1, 1000);
+                let path = format!(
+                    "users/{user_id}/{snap_type}?max_results={max_results}&user.fields=public_metrics,username,name{pagination}"
+                );
+                let raw = client
+                    .oauth_get(&path, access_token)
+                    .await
+                    .map_err(|e| format!("Failed to fetch {snap_type}: {e}"))?;
+                if let Some(data) = &raw.data {
+                    if let Some(arr) = data.as_array() {
+                        users.extend(arr.iter().cloned());
+                    }
+                }
+                next_token = raw.meta.and_then(|m| m.next_token);
+                if next_token.is_none() || users.len() >= limit {
+                    break;
+                }
+                if page_idx + 1 < pages {
+                    crate::client::rate_delay().await;
+                }
+            }
+            users.truncate(limit);
+            Ok((user_id, users))
+        }
+
+        async fn fetch_api_trends(
+            client: &XClient,
+            token: &str,
+            woeid: u32,
+            max_trends: usize,
+        ) -> Vec<serde_json::Value> {
+            // The endpoint accepts 1-50 trends per request.
+            let max_trends = max_trends.clamp(1, 50);
+            let Ok(raw) = client
+                .bearer_get(
+                    &format!("trends/by/woeid/{woeid}?max_trends={max_trends}"),
+                    token,
+                )
+                .await
+            else {
+                return Vec::new();
+            };
+            raw.data
+                .as_ref()
+                .and_then(|data| data.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|item| {
+                            let name = item.get("trend_name")?.as_str()?;
+                            Some(serde_json::json!({
+                                "name": name,
+                                "tweet_count": item.get("tweet_count").and_then(|v| v.as_u64()),
+                                "url": MCPServer::trend_search_url(name),
+                                "category": item.get("category").and_then(|v| v.as_str()),
+                            }))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+
+        let route_result = resolve_tool_route(name);
+        let Some(route) = route_result.data else {
             return Err(route_result.message);
         };
         match route {
@@ -945,41 +3925,94 @@ impl MCPServer {
                     .and_then(|v| v.as_str())
                     .ok_or("Missing query")?;
                 let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(15) as usize;
-                let pages = ((limit.max(1) as u32).saturating_add(19) / 20).min(5);
-                let sort_order = match args.get("sort").and_then(|v| v.as_str()).unwrap_or("likes")
-                {
+                let sort = args.get("sort").and_then(|v| v.as_str()).unwrap_or("likes");
+                let sort_order = match sort {
                     "recent" | "recency" => "recency",
                     _ => "relevancy",
                 };
                 let since = args.get("since").and_then(|v| v.as_str());
-                let no_retweets = bool_arg(&args, "noRetweets", "no_retweets");
-                let no_replies = bool_arg(&args, "noReplies", "no_replies");
+                let (search_query, pages) = search_request(query, limit, &args);
 
-                let mut tweets = twitter::search(
-                    &client, &token, query, pages, sort_order, since, None, false,
+                let fetched = twitter::search(
+                    &client,
+                    &token,
+                    &search_query,
+                    pages,
+                    sort_order,
+                    since,
+                    None,
+                    false,
                 )
                 .await
-                .map_err(|e| format!("Search failed: {e}"))?;
+                .map_err(|e| Self::tool_error("Search failed", e))?;
+                // Every tweet read is billed, not just the ones returned.
+                costs::track_cost(
+                    &self.costs_path,
+                    "search",
+                    "/2/tweets/search/recent",
+                    fetched.len() as u64,
+                );
 
-                if no_retweets {
-                    tweets.retain(|t| !t.text.starts_with("RT @"));
-                }
-                if no_replies {
-                    tweets.retain(|t| t.conversation_id == t.id);
+                let mut tweets = twitter::dedupe(fetched);
+                if sort_order != "recency" {
+                    twitter::sort_by(&mut tweets, sort);
                 }
                 let shown: Vec<_> = tweets.into_iter().take(limit.max(1)).collect();
+
+                Self::retrieval_content(
+                    "Search completed.".to_string(),
+                    shown.len(),
+                    serde_json::json!(shown),
+                )
+            }
+            McpToolRoute::Hashtag => {
+                let (client, token) = bearer_runtime()?;
+                let tag = args
+                    .get("tag")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing tag")?;
+                let tag = MCPServer::normalize_hashtag(name, tag)?;
+                let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+                let by_engagement = args.get("sort").and_then(|v| v.as_str()) == Some("engagement");
+                let since = args.get("since").and_then(|v| v.as_str());
+                // Engagement ranking needs a wider pool than it returns.
+                let wanted = if by_engagement { limit * 3 } else { limit };
+                let pages = (wanted.max(1) as u32).div_ceil(100).min(5);
+
+                let fetched = twitter::search(
+                    &client,
+                    &token,
+                    &format!("#{tag} -is:retweet"),
+                    pages,
+                    "recency",
+                    since,
+                    None,
+                    false,
+                )
+                .await
+                .map_err(|e| Self::tool_error("Hashtag search failed", e))?;
                 costs::track_cost(
                     &self.costs_path,
                     "search",
                     "/2/tweets/search/recent",
-                    shown.len() as u64,
+                    fetched.len() as u64,
                 );
 
-                json_content(serde_json::json!({
-                    "type": "success",
-                    "message": "Search completed.",
-                    "data": shown
-                }))
+                let mut tweets = twitter::dedupe(fetched);
+                if by_engagement {
+                    twitter::sort_by(&mut tweets, "engagement");
+                }
+                tweets.truncate(limit.max(1));
+
+                Self::retrieval_content(
+                    format!("Found {} posts tagged #{tag}.", tweets.len()),
+                    tweets.len(),
+                    serde_json::json!({
+                        "tag": tag,
+                        "sort": if by_engagement { "engagement" } else { "recent" },
+                        "tweets": tweets
+                    }),
+                )
             }
             McpToolRoute::Profile => {
                 let (client, token) = bearer_runtime()?;
@@ -989,12 +4022,106 @@ impl MCPServer {
                     .ok_or("Missing username")?;
                 let count = args.get("count").and_then(|v| v.as_u64()).unwrap_or(20) as u32;
                 let include_replies = bool_arg(&args, "includeReplies", "include_replies");
-                let normalized = username.trim_start_matches('@');
+                let normalized = &MCPServer::normalize_username(name, username)?;
 
                 let (user, tweets) =
                     twitter::get_profile(&client, &token, normalized, count, include_replies)
                         .await
-                        .map_err(|e| format!("Profile lookup failed: {e}"))?;
+                        .map_err(|e| Self::tool_error("Profile lookup failed", e))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "profile",
+                    &format!("/2/users/by/username/{normalized}"),
+                    tweets.len() as u64 + 1,
+                );
+
+                Self::retrieval_content(
+                    "Profile lookup completed.".to_string(),
+                    tweets.len(),
+                    serde_json::json!({
+                        "user": user,
+                        "tweets": tweets
+                    }),
+                )
+            }
+            McpToolRoute::UserInfo => {
+                let (client, token) = bearer_runtime()?;
+                let username = args
+                    .get("username")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing username")?;
+                let normalized = &MCPServer::normalize_username(name, username)?;
+
+                let user = twitter::get_user_info(&client, &token, normalized)
+                    .await
+                    .map_err(|e| Self::tool_error("User info lookup failed", e))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "profile",
+                    &format!("/2/users/by/username/{normalized}"),
+                    1,
+                );
+
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": format!("Profile metadata for @{}.", user.username),
+                    "data": user
+                }))
+            }
+            McpToolRoute::Verify => {
+                let (client, token) = bearer_runtime()?;
+                let username = args
+                    .get("username")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing username")?;
+                let normalized = &MCPServer::normalize_username(name, username)?;
+
+                let user = twitter::get_user_info(&client, &token, normalized)
+                    .await
+                    .map_err(|e| Self::tool_error("User info lookup failed", e))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "profile",
+                    &format!("/2/users/by/username/{normalized}"),
+                    1,
+                );
+
+                let signals = authenticity::assess(&user, chrono::Utc::now());
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": format!(
+                        "@{} has a {} likelihood of being automated (score {}).",
+                        user.username, signals.bot_likelihood, signals.bot_score
+                    ),
+                    "data": signals
+                }))
+            }
+            McpToolRoute::UserBrief => {
+                let (client, token) = bearer_runtime()?;
+                let api_key = xai_runtime()?;
+                let username = args
+                    .get("username")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing username")?;
+                let normalized = &MCPServer::normalize_username(name, username)?;
+                let count = args
+                    .get("count")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(20)
+                    .clamp(1, 100) as u32;
+                let opts = crate::models::GrokOpts {
+                    model: args
+                        .get("model")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("grok-3-mini")
+                        .to_string(),
+                    ..Default::default()
+                };
+
+                let (user, tweets) =
+                    twitter::get_profile(&client, &token, normalized, count, false)
+                        .await
+                        .map_err(|e| Self::tool_error("Profile lookup failed", e))?;
                 costs::track_cost(
                     &self.costs_path,
                     "profile",
@@ -1002,140 +4129,573 @@ impl MCPServer {
                     tweets.len() as u64 + 1,
                 );
 
+                let response = grok::summarize_user(
+                    &reqwest::Client::new(),
+                    &api_key,
+                    normalized,
+                    &tweets,
+                    &opts,
+                )
+                .await
+                .map_err(|e| format!("User brief failed: {e}"))?;
+                self.track_grok_usage("grok_user_brief", response.token_usage());
+
+                let citations: Vec<_> = grok::cited_indices(&response.content, tweets.len())
+                    .into_iter()
+                    .map(|n| {
+                        let t = &tweets[n - 1];
+                        serde_json::json!({
+                            "ref": n,
+                            "tweet_id": t.id,
+                            "url": t.tweet_url,
+                        })
+                    })
+                    .collect();
                 json_content(serde_json::json!({
                     "type": "success",
-                    "message": "Profile lookup completed.",
+                    "message": "User brief completed.",
                     "data": {
+                        "username": normalized,
                         "user": user,
-                        "tweets": tweets
+                        "summary": response.content,
+                        "citations": citations,
+                        "source_tweet_ids": tweets.iter().map(|t| t.id.clone()).collect::<Vec<_>>(),
+                        "model": response.model,
+                        "usage": response.usage
                     }
                 }))
             }
-            McpToolRoute::Thread => {
+            McpToolRoute::Replies => {
                 let (client, token) = bearer_runtime()?;
                 let tweet_id = args
                     .get("tweet_id")
                     .or_else(|| args.get("tweetId"))
                     .and_then(|v| v.as_str())
                     .ok_or("Missing tweet_id or tweetId")?;
-                let pages = args.get("pages").and_then(|v| v.as_u64()).unwrap_or(2) as u32;
+                let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
                 let normalized_id = extract_tweet_id(tweet_id);
 
-                let tweets = twitter::get_thread(&client, &token, &normalized_id, pages)
+                let replies = twitter::get_replies(&client, &token, &normalized_id, limit)
                     .await
-                    .map_err(|e| format!("Thread lookup failed: {e}"))?;
+                    .map_err(|e| Self::tool_error("Replies lookup failed", e))?;
                 costs::track_cost(
                     &self.costs_path,
-                    "thread",
+                    "search",
                     "/2/tweets/search/recent",
-                    tweets.len() as u64,
+                    replies.len() as u64,
                 );
 
-                json_content(serde_json::json!({
-                    "type": "success",
-                    "message": "Thread lookup completed.",
-                    "data": {
-                        "tweets": tweets
-                    }
-                }))
+                Self::retrieval_content(
+                    format!("Found {} direct replies.", replies.len()),
+                    replies.len(),
+                    serde_json::json!({
+                        "tweet_id": normalized_id,
+                        "tweets": replies
+                    }),
+                )
             }
-            McpToolRoute::Tweet => {
+            McpToolRoute::Retweeters => {
                 let (client, token) = bearer_runtime()?;
                 let tweet_id = args
                     .get("tweet_id")
                     .or_else(|| args.get("tweetId"))
                     .and_then(|v| v.as_str())
                     .ok_or("Missing tweet_id or tweetId")?;
+                let limit = retweeters_limit(&args);
                 let normalized_id = extract_tweet_id(tweet_id);
-                let tweet = twitter::get_tweet(&client, &token, &normalized_id)
+
+                let users = twitter::get_retweeters(&client, &token, &normalized_id, limit)
+                    .await
+                    .map_err(|e| Self::tool_error("Retweeters lookup failed", e))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "retweeted_by",
+                    &format!("/2/tweets/{normalized_id}/retweeted_by"),
+                    users.len() as u64,
+                );
+
+                Self::retweeters_content(&normalized_id, &users)
+            }
+            McpToolRoute::Poll => {
+                let (client, token) = bearer_runtime()?;
+                let tweet_id = args
+                    .get("tweet_id")
+                    .or_else(|| args.get("tweetId"))
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing tweet_id or tweetId")?;
+                let normalized_id = extract_tweet_id(tweet_id);
+
+                let (tweet, poll) = twitter::get_poll(&client, &token, &normalized_id)
                     .await
-                    .map_err(|e| format!("Tweet lookup failed: {e}"))?;
+                    .map_err(|e| Self::tool_error("Tweet lookup failed", e))?;
                 costs::track_cost(
                     &self.costs_path,
                     "tweet",
                     &format!("/2/tweets/{normalized_id}"),
-                    if tweet.is_some() { 1 } else { 0 },
+                    1,
                 );
 
                 json_content(serde_json::json!({
                     "type": "success",
-                    "message": "Tweet lookup completed.",
-                    "data": tweet
+                    "message": poll_message(poll.as_ref()),
+                    "data": {
+                        "tweet_id": normalized_id,
+                        "tweet_url": tweet.tweet_url,
+                        "has_poll": poll.is_some(),
+                        "poll": poll
+                    }
                 }))
             }
-            McpToolRoute::Trends => {
+            McpToolRoute::Quotes => {
                 let (client, token) = bearer_runtime()?;
-                let location = args
-                    .get("location")
+                let tweet_id = args
+                    .get("tweet_id")
+                    .or_else(|| args.get("tweetId"))
                     .and_then(|v| v.as_str())
-                    .unwrap_or("worldwide");
-                let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
-                let woeid = resolve_woeid(location)?;
-                let trends_path = format!("trends/by/woeid/{woeid}");
-
-                let primary = client.bearer_get(&trends_path, &token).await.ok().and_then(|raw| {
-                    let data = raw.data?;
-                    let arr = data.as_array()?;
-                    let trends: Vec<serde_json::Value> = arr
-                        .iter()
-                        .filter_map(|item| {
-                            let name = item.get("trend_name")?.as_str()?;
-                            Some(serde_json::json!({
-                                "name": name,
-                                "tweet_count": item.get("tweet_count").and_then(|v| v.as_u64()),
-                                "url": format!("https://x.com/search?q={}", name.replace(' ', "%20")),
-                                "category": item.get("category").and_then(|v| v.as_str()),
-                            }))
-                        })
-                        .collect();
+                    .ok_or("Missing tweet_id or tweetId")?;
+                let limit = args
+                    .get("limit")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(20)
+                    .clamp(1, 100) as usize;
+                let cursor = args.get("cursor").and_then(|v| v.as_str());
+                let normalized_id = extract_tweet_id(tweet_id);
 
-                    if trends.is_empty() {
-                        None
-                    } else {
-                        Some(serde_json::json!({
-                            "source": "api",
-                            "location": woeid_name(woeid),
-                            "woeid": woeid,
-                            "trends": trends.into_iter().take(limit.max(1)).collect::<Vec<_>>(),
-                            "fetched_at": chrono::Utc::now().to_rfc3339()
-                        }))
+                let (tweets, next_cursor) =
+                    match twitter::get_quote_tweets(&client, &token, &normalized_id, limit, cursor)
+                        .await
+                    {
+                        Ok(page) => page,
+                        Err(e) => {
+                            return Self::quotes_unavailable_content(&normalized_id, &e)
+                                .unwrap_or_else(|| {
+                                    Err(Self::tool_error("Quote tweet lookup failed", e))
+                                })
+                        }
+                    };
+                costs::track_cost(
+                    &self.costs_path,
+                    "tweet",
+                    &format!("/2/tweets/{normalized_id}/quote_tweets"),
+                    tweets.len() as u64,
+                );
+                Self::quotes_content(&normalized_id, &tweets, next_cursor)
+            }
+            McpToolRoute::Media => {
+                let (client, token) = bearer_runtime()?;
+                let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+                let (source, search_query) = match (
+                    args.get("username").and_then(|v| v.as_str()),
+                    args.get("query").and_then(|v| v.as_str()),
+                ) {
+                    (Some(username), None) => {
+                        let normalized = MCPServer::normalize_username(name, username)?;
+                        (format!("@{normalized}"), format!("from:{normalized}"))
                     }
-                });
-
-                if let Some(result) = primary {
-                    costs::track_cost(&self.costs_path, "trends", "/2/trends/by/woeid", 0);
-                    return json_content(serde_json::json!({
-                        "type": "success",
-                        "message": "Trends fetch completed.",
-                        "data": result
-                    }));
-                }
+                    (None, Some(query)) => (query.to_string(), query.to_string()),
+                    _ => return Err("Provide exactly one of username or query".to_string()),
+                };
 
-                let fallback_query = format!("-is:retweet lang:{}", language_for_woeid(woeid));
-                let fallback_tweets = twitter::search(
-                    &client,
-                    &token,
-                    &fallback_query,
-                    1,
-                    "recency",
-                    None,
-                    None,
-                    false,
-                )
-                .await
-                .map_err(|e| format!("Trends fallback failed: {e}"))?;
+                let found = twitter::search_media(&client, &token, &search_query, limit)
+                    .await
+                    .map_err(|e| Self::tool_error("Media lookup failed", e))?;
                 costs::track_cost(
                     &self.costs_path,
                     "search",
                     "/2/tweets/search/recent",
-                    fallback_tweets.len() as u64,
+                    found.len() as u64,
                 );
 
-                let mut counts = std::collections::HashMap::<String, u64>::new();
-                for tweet in &fallback_tweets {
-                    for hashtag in &tweet.hashtags {
-                        let tag = format!("#{}", hashtag.to_lowercase());
-                        *counts.entry(tag).or_insert(0) += 1;
+                let groups: Vec<_> = found
+                    .iter()
+                    .map(|(tweet, items)| {
+                        serde_json::json!({
+                            "tweet_id": tweet.id,
+                            "username": tweet.username,
+                            "created_at": tweet.created_at,
+                            "tweet_url": tweet.tweet_url,
+                            "text": tweet.text,
+                            "media": items
+                                .iter()
+                                .map(|m| serde_json::json!({
+                                    "media_key": m.get("media_key"),
+                                    "type": m.get("type"),
+                                    "url": media::select_download_url(m),
+                                    "preview_image_url": m.get("preview_image_url"),
+                                    "alt_text": m.get("alt_text")
+                                }))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                let media_count: usize = found.iter().map(|(_, media)| media.len()).sum();
+
+                Self::retrieval_content(
+                    format!(
+                        "Found {media_count} media item(s) across {} tweets from {source}.",
+                        groups.len()
+                    ),
+                    groups.len(),
+                    serde_json::json!({
+                        "source": source,
+                        "media_count": media_count,
+                        "tweets": groups
+                    }),
+                )
+            }
+            McpToolRoute::Top => {
+                let (client, token) = bearer_runtime()?;
+                let username = args
+                    .get("username")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing username")?;
+                let count = args.get("count").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+                let metric = args
+                    .get("metric")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("likes");
+                let normalized = &MCPServer::normalize_username(name, username)?;
+
+                let (user, tweets) =
+                    twitter::get_profile(&client, &token, normalized, top_window(count), false)
+                        .await
+                        .map_err(|e| Self::tool_error("Profile lookup failed", e))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "profile",
+                    &format!("/2/users/by/username/{normalized}"),
+                    tweets.len() as u64 + 1,
+                );
+
+                let scanned = tweets.len();
+                let tweets = top_by_metric(tweets, metric, count);
+
+                Self::retrieval_content(
+                    format!(
+                        "Top {} of {scanned} recent tweets from @{normalized} by {metric}.",
+                        tweets.len()
+                    ),
+                    tweets.len(),
+                    serde_json::json!({
+                        "user": user,
+                        "metric": metric,
+                        "scanned": scanned,
+                        "tweets": tweets
+                    }),
+                )
+            }
+            McpToolRoute::Mentions => {
+                let (client, token) = bearer_runtime()?;
+                let username = args
+                    .get("username")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing username")?;
+                let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+                let since = args.get("since").and_then(|v| v.as_str());
+                let normalized = &MCPServer::normalize_username(name, username)?;
+                let pages = (limit.max(1) as u32).div_ceil(100).min(5);
+
+                let fetched = twitter::search(
+                    &client,
+                    &token,
+                    &format!("@{normalized} -from:{normalized} -is:retweet"),
+                    pages,
+                    "recency",
+                    since,
+                    None,
+                    false,
+                )
+                .await
+                .map_err(|e| Self::tool_error("Mentions search failed", e))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "search",
+                    "/2/tweets/search/recent",
+                    fetched.len() as u64,
+                );
+
+                let tweets = mentions_of(fetched, normalized, limit);
+
+                Self::retrieval_content(
+                    format!("Found {} posts mentioning @{normalized}.", tweets.len()),
+                    tweets.len(),
+                    serde_json::json!({
+                        "username": normalized,
+                        "tweets": tweets
+                    }),
+                )
+            }
+            McpToolRoute::Similar => {
+                let (client, token) = bearer_runtime()?;
+                let username = args
+                    .get("username")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing username")?;
+                let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+                let normalized = &MCPServer::normalize_username(name, username)?;
+
+                // Retweets and replies are kept: they are the network signal.
+                let tweets = twitter::search(
+                    &client,
+                    &token,
+                    &format!("from:{normalized}"),
+                    2,
+                    "recency",
+                    None,
+                    None,
+                    false,
+                )
+                .await
+                .map_err(|e| Self::tool_error("Activity search failed", e))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "search",
+                    "/2/tweets/search/recent",
+                    tweets.len() as u64,
+                );
+
+                let accounts = co_occurring_accounts(normalized, &tweets, limit.max(1));
+                Self::retrieval_content(
+                    format!(
+                        "Found {} accounts in @{normalized}'s recent network.",
+                        accounts.len()
+                    ),
+                    accounts.len(),
+                    serde_json::json!({
+                        "username": normalized,
+                        "tweets_scanned": tweets.len(),
+                        "accounts": accounts
+                    }),
+                )
+            }
+            McpToolRoute::EngagementRate => {
+                let (client, token) = bearer_runtime()?;
+                let username = args
+                    .get("username")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing username")?;
+                let count = args.get("count").and_then(|v| v.as_u64()).unwrap_or(50) as u32;
+                let normalized = &MCPServer::normalize_username(name, username)?;
+
+                let (user, tweets) =
+                    twitter::get_profile(&client, &token, normalized, count, false)
+                        .await
+                        .map_err(|e| Self::tool_error("Profile lookup failed", e))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "profile",
+                    &format!("/2/users/by/username/{normalized}"),
+                    tweets.len() as u64 + 1,
+                );
+
+                let followers = user
+                    .get("public_metrics")
+                    .and_then(|m| m.get("followers_count"))
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0);
+                let mut data = engagement_rate(followers, &tweets);
+                data["username"] = serde_json::json!(normalized);
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": format!(
+                        "@{normalized} engagement rate is {}% over {} posts ({}).",
+                        data["engagement_rate_pct"], tweets.len(), data["trend"].as_str().unwrap_or("flat")
+                    ),
+                    "data": data
+                }))
+            }
+            McpToolRoute::Pinned => {
+                let (client, token) = bearer_runtime()?;
+                let username = args
+                    .get("username")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing username")?;
+                let normalized = &MCPServer::normalize_username(name, username)?;
+
+                let (user, pinned) = twitter::get_pinned_tweet(&client, &token, normalized)
+                    .await
+                    .map_err(|e| Self::tool_error("Pinned tweet lookup failed", e))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "profile",
+                    &format!("/2/users/by/username/{normalized}"),
+                    pinned.is_some() as u64 + 1,
+                );
+
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": pinned_message(normalized, pinned.as_ref()),
+                    "data": {
+                        "user": user,
+                        "pinned_tweet": pinned
+                    }
+                }))
+            }
+            McpToolRoute::Thread => {
+                let (client, token) = bearer_runtime()?;
+                let tweet_id = args
+                    .get("tweet_id")
+                    .or_else(|| args.get("tweetId"))
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing tweet_id or tweetId")?;
+                let pages = args.get("pages").and_then(|v| v.as_u64()).unwrap_or(2) as u32;
+                let normalized_id = extract_tweet_id(tweet_id);
+
+                let cursor = args.get("cursor").and_then(|v| v.as_str());
+
+                let thread =
+                    twitter::get_thread_page(&client, &token, &normalized_id, pages, cursor)
+                        .await
+                        .map_err(|e| Self::tool_error("Thread lookup failed", e))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "thread",
+                    "/2/tweets/search/recent",
+                    thread.tweets.len() as u64,
+                );
+                Self::thread_content(thread)
+            }
+            McpToolRoute::Tweet => {
+                let (client, token) = bearer_runtime()?;
+                let tweet_id = args
+                    .get("tweet_id")
+                    .or_else(|| args.get("tweetId"))
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing tweet_id or tweetId")?;
+                let normalized_id = extract_tweet_id(tweet_id);
+                let tweet = twitter::get_tweet(&client, &token, &normalized_id)
+                    .await
+                    .map_err(|e| Self::tool_error("Tweet lookup failed", e))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "tweet",
+                    &format!("/2/tweets/{normalized_id}"),
+                    if tweet.is_some() { 1 } else { 0 },
+                );
+
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": "Tweet lookup completed.",
+                    "data": tweet
+                }))
+            }
+            McpToolRoute::List => {
+                let (client, token) = bearer_runtime()?;
+                let raw_id = args
+                    .get("list_id")
+                    .or_else(|| args.get("listId"))
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing list_id or listId")?;
+                let list_id = extract_list_id(raw_id).ok_or_else(|| {
+                    serde_json::json!({
+                        "code": "INVALID_ARGUMENT",
+                        "message": format!("'{raw_id}' is not a List id or URL"),
+                        "tool": name,
+                        "field": "list_id",
+                        "value": raw_id,
+                    })
+                    .to_string()
+                })?;
+                let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+
+                let tweets = twitter::get_list_tweets(&client, &token, &list_id, limit.max(1))
+                    .await
+                    .map_err(|e| Self::tool_error("List timeline lookup failed", e))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "list_tweets",
+                    &format!("/2/lists/{list_id}/tweets"),
+                    tweets.len() as u64,
+                );
+
+                Self::retrieval_content(
+                    format!("Found {} tweets on List {list_id}.", tweets.len()),
+                    tweets.len(),
+                    serde_json::json!({
+                        "list_id": list_id,
+                        "tweets": tweets
+                    }),
+                )
+            }
+            McpToolRoute::Spaces => {
+                let (client, token) = bearer_runtime()?;
+                let query = args
+                    .get("query")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing query")?;
+                let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+
+                let spaces = twitter::search_spaces(&client, &token, query, limit)
+                    .await
+                    .map_err(|e| Self::spaces_error(name, e))?;
+                // Spaces search is priced per request; the count only records
+                // how many Spaces were read.
+                costs::track_cost(
+                    &self.costs_path,
+                    "spaces",
+                    "/2/spaces/search",
+                    spaces.len() as u64,
+                );
+
+                Self::retrieval_content(
+                    format!("Found {} Spaces.", spaces.len()),
+                    spaces.len(),
+                    serde_json::json!({
+                        "query": query,
+                        "spaces": spaces
+                    }),
+                )
+            }
+            McpToolRoute::Trends => {
+                let (client, token) = bearer_runtime()?;
+                let location = args
+                    .get("location")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("worldwide");
+                let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+                let woeid = resolve_woeid(location)?;
+
+                let trends = fetch_api_trends(&client, &token, woeid, limit).await;
+                if !trends.is_empty() {
+                    costs::track_cost(&self.costs_path, "trends", "/2/trends/by/woeid", 0);
+                    return json_content(serde_json::json!({
+                        "type": "success",
+                        "message": "Trends fetch completed.",
+                        "data": {
+                            "source": "api",
+                            "location": woeid_name(woeid),
+                            "woeid": woeid,
+                            "trends": trends.into_iter().take(limit.max(1)).collect::<Vec<_>>(),
+                            "fetched_at": chrono::Utc::now().to_rfc3339()
+                        }
+                    }));
+                }
+
+                let fallback_query = format!("-is:retweet lang:{}", language_for_woeid(woeid));
+                let fallback_tweets = twitter::search(
+                    &client,
+                    &token,
+                    &fallback_query,
+                    1,
+                    "recency",
+                    None,
+                    None,
+                    false,
+                )
+                .await
+                .map_err(|e| format!("Trends fallback failed: {e}"))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "search",
+                    "/2/tweets/search/recent",
+                    fallback_tweets.len() as u64,
+                );
+
+                let mut counts = std::collections::HashMap::<String, u64>::new();
+                for tweet in &fallback_tweets {
+                    for hashtag in &tweet.hashtags {
+                        let tag = format!("#{}", hashtag.to_lowercase());
+                        *counts.entry(tag).or_insert(0) += 1;
                     }
                     for word in tweet.text.split_whitespace() {
                         if word.starts_with('#') && word.len() > 1 {
@@ -1156,7 +4716,7 @@ impl MCPServer {
                     .into_iter()
                     .filter(|(_, count)| *count >= 2)
                     .collect();
-                sorted.sort_by(|a, b| b.1.cmp(&a.1));
+                sorted.sort_by_key(|b| std::cmp::Reverse(b.1));
 
                 let trends: Vec<serde_json::Value> = sorted
                     .into_iter()
@@ -1165,7 +4725,7 @@ impl MCPServer {
                         serde_json::json!({
                             "name": name,
                             "tweet_count": count,
-                            "url": format!("https://x.com/search?q={}", name.replace(' ', "%20"))
+                            "url": Self::trend_search_url(&name)
                         })
                     })
                     .collect();
@@ -1183,12 +4743,142 @@ impl MCPServer {
                     }
                 }))
             }
-            McpToolRoute::XSearch => {
-                let api_key = xai_runtime()?;
-                let query = args
-                    .get("query")
-                    .and_then(|v| v.as_str())
-                    .ok_or("Missing query")?;
+            McpToolRoute::TrendsFor => {
+                let (client, token) = bearer_runtime()?;
+                let topic = args
+                    .get("topic")
+                    .and_then(|v| v.as_str())
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .ok_or("Missing topic")?;
+                let location = args
+                    .get("location")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("worldwide");
+                let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+                let woeid = resolve_woeid(location)?;
+
+                // Rank from the widest pool available, then cut to `limit`.
+                let mut trends = fetch_api_trends(&client, &token, woeid, 50).await;
+                let source = if trends.is_empty() {
+                    // No trends endpoint access: hashtags from a topic search
+                    // are relevant by construction.
+                    let tweets = twitter::search(
+                        &client,
+                        &token,
+                        &format!("{topic} -is:retweet"),
+                        1,
+                        "recency",
+                        None,
+                        None,
+                        false,
+                    )
+                    .await
+                    .map_err(|e| format!("Trends fallback failed: {e}"))?;
+                    costs::track_cost(
+                        &self.costs_path,
+                        "search",
+                        "/2/tweets/search/recent",
+                        tweets.len() as u64,
+                    );
+                    let mut counts = std::collections::HashMap::<String, u64>::new();
+                    for tweet in &tweets {
+                        for hashtag in &tweet.hashtags {
+                            *counts
+                                .entry(format!("#{}", hashtag.to_lowercase()))
+                                .or_insert(0) += 1;
+                        }
+                    }
+                    let mut sorted: Vec<(String, u64)> = counts.into_iter().collect();
+                    sorted.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                    trends = sorted
+                        .into_iter()
+                        .map(|(name, count)| {
+                            serde_json::json!({
+                                "name": name,
+                                "tweet_count": count,
+                                "url": Self::trend_search_url(&name),
+                                "relevance": 1.0,
+                            })
+                        })
+                        .collect();
+                    reliability::mark_command_fallback("mcp:xint_trends_for");
+                    "search_fallback"
+                } else {
+                    costs::track_cost(&self.costs_path, "trends", "/2/trends/by/woeid", 0);
+                    "api"
+                };
+
+                let names: Vec<String> = trends
+                    .iter()
+                    .filter_map(|t| t.get("name").and_then(|v| v.as_str()).map(str::to_string))
+                    .collect();
+                for trend in trends.iter_mut() {
+                    if trend.get("relevance").is_none() {
+                        let name = trend.get("name").and_then(|v| v.as_str()).unwrap_or("");
+                        trend["relevance"] = serde_json::json!(Self::trend_relevance(topic, name));
+                    }
+                }
+
+                let mut method = "keyword";
+                let mut ranked: Vec<serde_json::Value> = Vec::new();
+                if source == "api" {
+                    if let Ok(api_key) = xai_runtime() {
+                        let http = reqwest::Client::new();
+                        if let Ok(response) = grok::classify_trends(
+                            &http,
+                            &api_key,
+                            topic,
+                            &names,
+                            &crate::models::GrokOpts::default(),
+                        )
+                        .await
+                        {
+                            self.track_grok_usage("grok_trends_for", response.token_usage());
+                            method = "grok";
+                            ranked = grok::cited_indices(&response.content, trends.len())
+                                .into_iter()
+                                .map(|n| trends[n - 1].clone())
+                                .collect();
+                        }
+                    }
+                }
+                if method == "keyword" {
+                    ranked = trends
+                        .into_iter()
+                        .filter(|t| t["relevance"].as_f64().unwrap_or(0.0) > 0.0)
+                        .collect();
+                    ranked.sort_by(|a, b| {
+                        let score = |t: &serde_json::Value| t["relevance"].as_f64().unwrap_or(0.0);
+                        let volume = |t: &serde_json::Value| t["tweet_count"].as_u64().unwrap_or(0);
+                        score(b)
+                            .total_cmp(&score(a))
+                            .then_with(|| volume(b).cmp(&volume(a)))
+                    });
+                }
+                ranked.truncate(limit.max(1));
+
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": format!("Found {} trend(s) relevant to \"{}\".", ranked.len(), topic),
+                    "data": {
+                        "topic": topic,
+                        "source": source,
+                        "method": method,
+                        "location": woeid_name(woeid),
+                        "woeid": woeid,
+                        "considered": names.len(),
+                        "trends": ranked,
+                        "fetched_at": chrono::Utc::now().to_rfc3339()
+                    }
+                }))
+            }
+            McpToolRoute::XSearch => {
+                let api_key = xai_runtime()?;
+                let query = args
+                    .get("query")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing query")?;
                 let max_results = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as u32;
                 let model = args
                     .get("model")
@@ -1212,23 +4902,25 @@ impl MCPServer {
                     }
                 }))
             }
-            McpToolRoute::CollectionsList => Ok(vec![MCPContent {
-                content_type: "text".to_string(),
-                text: serde_json::to_string_pretty(&{
-                    let result =
-                        xai::collections_list(&reqwest::Client::new(), &xai_management_runtime()?)
-                            .await
-                            .map_err(|e| format!("collections list failed: {e}"))?;
-                    serde_json::json!({
-                        "type": "success",
-                        "message": "Collections list fetched.",
-                        "data": result
-                    })
-                })
-                .map_err(|e| format!("Failed to encode collections list payload: {e}"))?,
-            }]),
+            McpToolRoute::CollectionsList => {
+                let body =
+                    xai::collections_list(&reqwest::Client::new(), &xai_management_runtime()?)
+                        .await
+                        .map_err(|e| format!("Collections list failed: {e}"))?;
+                let collections = xai::parse_collections(&body);
+
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": format!("{} collection(s) found.", collections.len()),
+                    "data": {
+                        "count": collections.len(),
+                        "collections": collections
+                    }
+                }))
+            }
             McpToolRoute::Analyze => {
-                let api_key = xai_runtime()?;
+                let api_key =
+                    xai_runtime().map_err(|e| format!("xint_analyze needs Grok access: {e}"))?;
                 let query = args
                     .get("query")
                     .and_then(|v| v.as_str())
@@ -1255,6 +4947,7 @@ impl MCPServer {
                         .await
                         .map_err(|e| format!("Analyze query failed: {e}"))?
                 };
+                let cost = self.track_grok_usage("grok_analyze", response.token_usage());
 
                 json_content(serde_json::json!({
                     "type": "success",
@@ -1262,7 +4955,77 @@ impl MCPServer {
                     "data": {
                         "model": response.model,
                         "content": response.content,
-                        "usage": response.usage
+                        "usage": response.usage,
+                        "cost_usd": cost.cost_usd
+                    }
+                }))
+            }
+            McpToolRoute::Translate => {
+                let api_key =
+                    xai_runtime().map_err(|e| format!("xint_translate needs Grok access: {e}"))?;
+                let target_lang = args
+                    .get("target_lang")
+                    .and_then(|v| v.as_str())
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .unwrap_or("en");
+                let (tweet, text) = match (
+                    args.get("tweet_id").and_then(|v| v.as_str()),
+                    args.get("text").and_then(|v| v.as_str()),
+                ) {
+                    (Some(tweet_id), None) => {
+                        let (client, token) = bearer_runtime()?;
+                        let normalized_id = extract_tweet_id(tweet_id);
+                        let tweet = twitter::get_tweet(&client, &token, &normalized_id)
+                            .await
+                            .map_err(|e| Self::tool_error("Tweet lookup failed", e))?
+                            .ok_or_else(|| format!("Tweet {normalized_id} not found"))?;
+                        costs::track_cost(
+                            &self.costs_path,
+                            "tweet",
+                            &format!("/2/tweets/{normalized_id}"),
+                            1,
+                        );
+                        let text = tweet.text.clone();
+                        (Some(tweet), text)
+                    }
+                    (None, Some(text)) => (None, text.to_string()),
+                    _ => return Err("Provide exactly one of tweet_id or text".to_string()),
+                };
+                if text.trim().is_empty() {
+                    return Err("Nothing to translate: text is empty".to_string());
+                }
+
+                let opts = crate::models::GrokOpts {
+                    model: args
+                        .get("model")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("grok-3-mini")
+                        .to_string(),
+                    ..Default::default()
+                };
+                let http = reqwest::Client::new();
+                let response = grok::translate(&http, &api_key, &text, target_lang, &opts)
+                    .await
+                    .map_err(|e| format!("Translation failed: {e}"))?;
+                let cost = self.track_grok_usage("grok_translate", response.token_usage());
+                let (source_lang, translation) = grok::parse_translation(&response.content);
+
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": format!(
+                        "Translated from {} to {target_lang}.",
+                        source_lang.as_deref().unwrap_or("unknown")
+                    ),
+                    "data": {
+                        "tweet_id": tweet.as_ref().map(|t| t.id.clone()),
+                        "tweet_url": tweet.as_ref().map(|t| t.tweet_url.clone()),
+                        "source_lang": source_lang,
+                        "target_lang": target_lang,
+                        "original": text,
+                        "translation": translation,
+                        "model": response.model,
+                        "cost_usd": cost.cost_usd
                     }
                 }))
             }
@@ -1271,15 +5034,210 @@ impl MCPServer {
                     .get("url")
                     .and_then(|v| v.as_str())
                     .ok_or("Missing url")?;
+                let full = args.get("full").and_then(|v| v.as_bool()).unwrap_or(false);
+                let ai_prompt = args
+                    .get("ai_prompt")
+                    .and_then(|v| v.as_str())
+                    .map(str::trim)
+                    .filter(|prompt| !prompt.is_empty());
+                let api_key =
+                    xai_runtime().map_err(|e| format!("xint_article needs Grok access: {e}"))?;
+
+                let mut article_url = url.to_string();
+                let mut source_tweet = None;
+                if article::is_x_tweet_like_url(url) {
+                    let (client, token) = bearer_runtime()?;
+                    let (tweet, linked) = article::fetch_tweet_for_article(&client, &token, url)
+                        .await
+                        .map_err(|e| Self::tool_error("Tweet lookup failed", e))?;
+                    costs::track_cost(
+                        &self.costs_path,
+                        "tweet",
+                        &format!("/2/tweets/{}", tweet.id),
+                        1,
+                    );
+                    article_url = linked.ok_or_else(|| {
+                        format!("No linked article found in tweet {}", tweet.tweet_url)
+                    })?;
+                    source_tweet = Some(tweet.tweet_url);
+                }
+
+                let resolved = redirect::resolve_url(&article_url, redirect::DEFAULT_MAX_HOPS)
+                    .await
+                    .map_err(|e| format!("Article URL is unreachable: {article_url} ({e})"))?;
+                match resolved.status_code {
+                    Some(status @ (401 | 402)) => {
+                        return Err(format!(
+                            "Article at {} requires a login or subscription (HTTP {status}); paywalled content cannot be extracted",
+                            resolved.final_url
+                        ));
+                    }
+                    Some(status @ (404 | 410)) => {
+                        return Err(format!(
+                            "Article not found at {} (HTTP {status})",
+                            resolved.final_url
+                        ));
+                    }
+                    _ => {}
+                }
+
+                let http = reqwest::Client::new();
+                let extracted = article::extract_article(
+                    &http,
+                    &api_key,
+                    &resolved.final_url,
+                    article::DEFAULT_MODEL,
+                    full,
+                )
+                .await
+                .map_err(|e| format!("Article extraction failed: {e}"))?;
+                if extracted.content.trim().is_empty() {
+                    return Err(format!(
+                        "No readable content extracted from {}; the page may be paywalled or require a login",
+                        resolved.final_url
+                    ));
+                }
+
+                let analysis = match ai_prompt {
+                    Some(prompt) => {
+                        let opts = crate::models::GrokOpts::default();
+                        let response = grok::analyze_query(
+                            &http,
+                            &api_key,
+                            prompt,
+                            Some(&extracted.content),
+                            &opts,
+                        )
+                        .await
+                        .map_err(|e| format!("Article analysis failed: {e}"))?;
+                        self.track_grok_usage("grok_analyze", response.token_usage());
+                        Some(serde_json::json!({
+                            "prompt": prompt,
+                            "model": response.model,
+                            "content": response.content,
+                            "usage": response.usage
+                        }))
+                    }
+                    None => None,
+                };
+
                 json_content(serde_json::json!({
                     "type": "success",
-                    "message": "Article route acknowledged.",
+                    "message": if analysis.is_some() {
+                        "Article extracted and analyzed."
+                    } else {
+                        "Article extracted."
+                    },
                     "data": {
                         "url": url,
-                        "note": "Use CLI article command for full web/article extraction output."
+                        "resolved_url": resolved.final_url,
+                        "source_tweet": source_tweet,
+                        "article": extracted,
+                        "analysis": analysis
                     }
                 }))
             }
+            McpToolRoute::ResolveUrl => {
+                let url = args
+                    .get("url")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing url")?;
+                let max_hops = args
+                    .get("max_hops")
+                    .and_then(|v| v.as_u64())
+                    .map(|v| (v as usize).min(redirect::DEFAULT_MAX_HOPS))
+                    .unwrap_or(redirect::DEFAULT_MAX_HOPS);
+                let resolved = redirect::resolve_url(url, max_hops)
+                    .await
+                    .map_err(|e| format!("URL resolution failed: {e}"))?;
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": format!("Resolved after {} redirect(s).", resolved.hops),
+                    "data": resolved
+                }))
+            }
+            McpToolRoute::UrlExpand => {
+                const MAX_URLS: usize = 20;
+                const PER_URL_TIMEOUT_SECS: u64 = 15;
+                let max_hops = args
+                    .get("max_hops")
+                    .and_then(|v| v.as_u64())
+                    .map(|v| (v as usize).min(redirect::DEFAULT_MAX_HOPS))
+                    .unwrap_or(redirect::DEFAULT_MAX_HOPS);
+                let (tweet_id, mut urls) = match (
+                    args.get("tweet_id").and_then(|v| v.as_str()),
+                    args.get("text").and_then(|v| v.as_str()),
+                ) {
+                    (Some(tweet_id), None) => {
+                        let (client, token) = bearer_runtime()?;
+                        let normalized_id = extract_tweet_id(tweet_id);
+                        let tweet = twitter::get_tweet(&client, &token, &normalized_id)
+                            .await
+                            .map_err(|e| Self::tool_error("Tweet lookup failed", e))?
+                            .ok_or_else(|| format!("Tweet {normalized_id} not found"))?;
+                        costs::track_cost(
+                            &self.costs_path,
+                            "tweet",
+                            &format!("/2/tweets/{normalized_id}"),
+                            1,
+                        );
+                        (Some(normalized_id), redirect::extract_urls(&tweet.text))
+                    }
+                    (None, Some(text)) => (None, redirect::extract_urls(text)),
+                    _ => return Err("Provide exactly one of tweet_id or text".to_string()),
+                };
+                let skipped = urls.len().saturating_sub(MAX_URLS);
+                urls.truncate(MAX_URLS);
+
+                let mut lookups = tokio::task::JoinSet::new();
+                for (index, url) in urls.iter().cloned().enumerate() {
+                    lookups.spawn(async move {
+                        let result = tokio::time::timeout(
+                            std::time::Duration::from_secs(PER_URL_TIMEOUT_SECS),
+                            redirect::resolve_url(&url, max_hops),
+                        )
+                        .await;
+                        (index, url, result)
+                    });
+                }
+                let mut expanded = Vec::with_capacity(urls.len());
+                while let Some(joined) = lookups.join_next().await {
+                    let Ok((index, url, result)) = joined else {
+                        continue;
+                    };
+                    let entry = match result {
+                        Ok(Ok(resolved)) => serde_json::json!({
+                            "short_url": url,
+                            "final_url": resolved.final_url,
+                            "final_domain": url::Url::parse(&resolved.final_url)
+                                .ok()
+                                .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_string())),
+                            "hops": resolved.hops,
+                            "status_code": resolved.status_code
+                        }),
+                        Ok(Err(e)) => {
+                            serde_json::json!({ "short_url": url, "error": e.to_string() })
+                        }
+                        Err(_) => serde_json::json!({
+                            "short_url": url,
+                            "error": format!("Timed out after {PER_URL_TIMEOUT_SECS}s")
+                        }),
+                    };
+                    expanded.push((index, entry));
+                }
+                expanded.sort_by_key(|(index, _)| *index);
+                let expanded: Vec<_> = expanded.into_iter().map(|(_, entry)| entry).collect();
+
+                Self::retrieval_content(
+                    format!("Expanded {} link(s).", expanded.len()),
+                    expanded.len(),
+                    serde_json::json!({
+                        "tweet_id": tweet_id,
+                        "urls": expanded,
+                        "skipped": skipped
+                    }),
+                )
+            }
             McpToolRoute::CollectionsSearch => {
                 let api_key = xai_runtime()?;
                 let collection_id = args
@@ -1291,61 +5249,114 @@ impl MCPServer {
                     .get("query")
                     .and_then(|v| v.as_str())
                     .ok_or("Missing query")?;
-                let top_k = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(8) as u32;
+                let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(5) as u32;
                 let http = reqwest::Client::new();
-                let result = xai::documents_search(
+                let body = xai::documents_search(
                     &http,
                     &api_key,
                     &[collection_id.to_string()],
                     query,
-                    top_k,
+                    limit,
                 )
                 .await
                 .map_err(|e| format!("Collections search failed: {e}"))?;
+                let matches = xai::parse_document_matches(&body, collection_id, limit as usize);
+
+                Self::retrieval_content(
+                    format!("{} matching chunk(s) in {collection_id}.", matches.len()),
+                    matches.len(),
+                    serde_json::json!({
+                        "collection_id": collection_id,
+                        "query": query,
+                        "matches": matches
+                    }),
+                )
+            }
+            McpToolRoute::Bookmarks => {
+                let (client, access_token, tokens) = oauth_user_runtime().await?;
+                let limit = args
+                    .get("limit")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(20)
+                    .clamp(1, 800) as usize;
+                let since = args
+                    .get("since")
+                    .and_then(|v| v.as_str())
+                    .map(str::trim)
+                    .filter(|since| !since.is_empty());
+                // Over-fetch when filtering by recency so enough survive.
+                let fetch_count = if since.is_some() {
+                    (limit * 3).clamp(100, 800)
+                } else {
+                    limit
+                };
+
+                let fetched =
+                    twitter::get_bookmarks(&client, &access_token, &tokens.user_id, fetch_count)
+                        .await
+                        .map_err(|e| Self::tool_error("Bookmarks fetch failed", e))?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "bookmarks",
+                    "/2/users/bookmarks",
+                    fetched.len() as u64,
+                );
 
+                let mut tweets = twitter::dedupe(fetched);
+                if let Some(since) = since {
+                    tweets = twitter::filter_since(tweets, since);
+                }
+                tweets.truncate(limit);
+
+                Self::retrieval_content(
+                    format!(
+                        "Fetched {} bookmarks for @{}.",
+                        tweets.len(),
+                        tokens.username
+                    ),
+                    tweets.len(),
+                    serde_json::json!({
+                        "username": tokens.username,
+                        "since": since,
+                        "tweets": tweets
+                    }),
+                )
+            }
+            McpToolRoute::PackageValidate => {
+                let payload = Self::package_create_payload(&args);
+                let problems = self.validate_package_spec(&payload);
                 json_content(serde_json::json!({
                     "type": "success",
-                    "message": "Collections search completed.",
-                    "data": result
+                    "message": if problems.is_empty() {
+                        "Package spec is valid.".to_string()
+                    } else {
+                        format!("Package spec has {} problem(s).", problems.len())
+                    },
+                    "data": {
+                        "valid": problems.is_empty(),
+                        "problems": problems,
+                        "payload": payload
+                    }
                 }))
             }
-            McpToolRoute::Bookmarks => json_content(serde_json::json!({
-                "type": "info",
-                "message": "Bookmarks requires OAuth user context.",
-                "data": {
-                    "note": "Use xint bookmarks command for full OAuth flow and caching behavior."
-                }
-            })),
             McpToolRoute::PackageCreate => {
-                let payload = serde_json::json!({
-                    "name": args.get("name").and_then(|v| v.as_str()).unwrap_or(""),
-                    "topic_query": args
-                        .get("topic_query")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or(""),
-                    "sources": args
-                        .get("sources")
-                        .and_then(|v| v.as_array())
-                        .cloned()
-                        .unwrap_or_default(),
-                    "time_window": args.get("time_window").cloned().unwrap_or_else(|| {
-                        serde_json::json!({
-                            "from": chrono::Utc::now()
-                                .checked_sub_signed(chrono::Duration::days(1))
-                                .unwrap_or_else(chrono::Utc::now)
-                                .to_rfc3339(),
-                            "to": chrono::Utc::now().to_rfc3339()
-                        })
-                    }),
-                    "policy": args
-                        .get("policy")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("private"),
-                    "analysis_profile": args
-                        .get("analysis_profile")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("summary")
-                });
+                let payload = Self::package_create_payload(&args);
+                let problems = self.validate_package_spec(&payload);
+                if !problems.is_empty() {
+                    return Err(serde_json::json!({
+                        "code": "VALIDATION_FAILED",
+                        "message": format!(
+                            "Invalid package spec: {}",
+                            problems
+                                .iter()
+                                .filter_map(|p| p.get("message").and_then(|m| m.as_str()))
+                                .collect::<Vec<_>>()
+                                .join("; ")
+                        ),
+                        "problems": problems,
+                    })
+                    .to_string());
+                }
                 let result = self
                     .call_package_api(reqwest::Method::POST, "/packages", Some(payload))
                     .await?;
@@ -1467,6 +5478,62 @@ impl MCPServer {
                     "data": result
                 }))
             }
+            McpToolRoute::Export => {
+                let format = args
+                    .get("format")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing format")?;
+                let path = args
+                    .get("path")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing path")?;
+                let session = CURRENT_SESSION
+                    .try_with(|session| session.clone())
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default();
+                let (tool, data) = lock_state(&self.last_results)
+                    .get(&session)
+                    .map(|last| (last.tool.clone(), last.data.clone()))
+                    .ok_or(
+                        "No tool result to export yet; run a data tool such as xint_search first",
+                    )?;
+
+                let target = match self.allowed_write_dirs.first() {
+                    Some(base) if std::path::Path::new(path).is_relative() => {
+                        base.join(path).to_string_lossy().to_string()
+                    }
+                    _ => path.to_string(),
+                };
+                let resolved = self.ensure_write_path_allowed(name, &target)?;
+
+                let records = export_records(&data);
+                let body = match format {
+                    "json" => serde_json::to_string_pretty(&data)
+                        .map_err(|e| format!("Failed to encode export: {e}"))?,
+                    "ndjson" => records.iter().map(|r| format!("{r}\n")).collect::<String>(),
+                    "csv" => export_csv(&records)?,
+                    other => return Err(format!("Unsupported export format: {other}")),
+                };
+                if let Some(parent) = resolved.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| format!("Failed to create export dir: {e}"))?;
+                }
+                std::fs::write(&resolved, &body)
+                    .map_err(|e| format!("Failed to write export: {e}"))?;
+
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": format!("Exported {tool} result to {}.", resolved.display()),
+                    "data": {
+                        "path": resolved,
+                        "format": format,
+                        "bytes": body.len(),
+                        "records": records.len(),
+                        "source_tool": tool
+                    }
+                }))
+            }
             McpToolRoute::CacheClear => {
                 let runtime_config =
                     Config::load().map_err(|e| format!("Failed to load config: {e}"))?;
@@ -1507,99 +5574,291 @@ impl MCPServer {
                 )
                 .await
                 .map_err(|e| format!("Watch probe failed: {e}"))?;
-                let shown: Vec<_> = tweets.into_iter().take(limit.max(1)).collect();
                 costs::track_cost(
                     &self.costs_path,
                     "search",
                     "/2/tweets/search/recent",
-                    shown.len() as u64,
+                    tweets.len() as u64,
+                );
+
+                let state_dir = Config::load()
+                    .map_err(|e| format!("Failed to load config: {e}"))?
+                    .watch_state_dir();
+                let cursor_path = watch_cursor_path(&state_dir, &search_query);
+                let previous = load_watch_cursor(&cursor_path).map(|cursor| cursor.last_id);
+
+                let (fresh, pending) = watch_batch(tweets, previous.as_deref(), limit);
+
+                if let Some(newest) = fresh.last() {
+                    save_watch_cursor(
+                        &cursor_path,
+                        &WatchCursor {
+                            query: search_query.clone(),
+                            last_id: newest.id.clone(),
+                            updated_at: chrono::Utc::now().to_rfc3339(),
+                        },
+                    )?;
+                }
+
+                Self::retrieval_content(
+                    format!("{} new tweets since last check.", fresh.len()),
+                    fresh.len(),
+                    serde_json::json!({
+                        "query": search_query,
+                        "since": since,
+                        "previous_cursor": previous,
+                        "cursor": fresh.last().map(|t| t.id.clone()).or(previous.clone()),
+                        "pending": pending,
+                        "tweets": fresh
+                    }),
+                )
+            }
+            McpToolRoute::Mutuals => {
+                let first = args
+                    .get("username_a")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing username_a")?;
+                let second = args
+                    .get("username_b")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing username_b")?;
+                let first = MCPServer::normalize_username(name, first)?;
+                let second = MCPServer::normalize_username(name, second)?;
+                let pages = args
+                    .get("pages")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(2)
+                    .clamp(1, 10) as u32;
+                let (client, access_token) = oauth_runtime().await?;
+
+                let (first_id, first_following) = fetch_user_relationships(
+                    &client,
+                    &access_token,
+                    &first,
+                    "following",
+                    pages,
+                    usize::MAX,
+                )
+                .await?;
+                crate::client::rate_delay().await;
+                let (second_id, second_following) = fetch_user_relationships(
+                    &client,
+                    &access_token,
+                    &second,
+                    "following",
+                    pages,
+                    usize::MAX,
+                )
+                .await?;
+                costs::track_cost(
+                    &self.costs_path,
+                    "following",
+                    &format!("/2/users/{first_id}/following"),
+                    first_following.len() as u64,
+                );
+                costs::track_cost(
+                    &self.costs_path,
+                    "following",
+                    &format!("/2/users/{second_id}/following"),
+                    second_following.len() as u64,
                 );
 
+                let common = common_accounts(&first_following, &second_following);
+
                 json_content(serde_json::json!({
                     "type": "success",
-                    "message": "Watch probe completed.",
+                    "message": format!("@{first} and @{second} both follow {} accounts.", common.len()),
                     "data": {
-                        "query": search_query,
-                        "since": since,
-                        "continuous_mode": false,
-                        "hint": "Use CLI watch command for continuous polling and webhooks.",
-                        "tweets": shown
+                        "username_a": first,
+                        "username_b": second,
+                        "following_a_count": first_following.len(),
+                        "following_b_count": second_following.len(),
+                        "common_count": common.len(),
+                        "common": common,
+                        "pages": pages
                     }
                 }))
             }
-            McpToolRoute::Diff => {
+            McpToolRoute::Following | McpToolRoute::Followers => {
                 let (client, access_token) = oauth_runtime().await?;
                 let username = args
                     .get("username")
                     .and_then(|v| v.as_str())
                     .ok_or("Missing username")?;
-                let normalized = username.trim_start_matches('@');
-                let following = bool_arg(&args, "following", "following");
-                let snap_type = if following { "following" } else { "followers" };
-                let pages = args.get("pages").and_then(|v| v.as_u64()).unwrap_or(5) as u32;
-
-                let lookup_path =
-                    format!("users/by/username/{normalized}?user.fields=public_metrics");
-                let lookup = client
-                    .oauth_get(&lookup_path, &access_token)
-                    .await
-                    .map_err(|e| format!("Failed to resolve @{normalized}: {e}"))?;
-                let user_id = lookup
-                    .data
-                    .as_ref()
-                    .and_then(|d| d.get("id"))
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| format!("User @{normalized} not found"))?
-                    .to_string();
-
-                let mut users = Vec::<serde_json::Value>::new();
-                let mut next_token: Option<String> = None;
-                for page_idx in 0..pages {
-                    let pagination = match &next_token {
-                        Some(t) => format!("&pagination_token={t}"),
-                        None => String::new(),
-                    };
-                    let path = format!(
-                        "users/{user_id}/{snap_type}?max_results=1000&user.fields=public_metrics,username,name{pagination}"
-                    );
-                    let raw = client
-                        .oauth_get(&path, &access_token)
-                        .await
-                        .map_err(|e| format!("Failed to fetch {snap_type}: {e}"))?;
-                    if let Some(data) = &raw.data {
-                        if let Some(arr) = data.as_array() {
-                            users.extend(arr.iter().cloned());
-                        }
-                    }
-                    next_token = raw.meta.and_then(|m| m.next_token);
-                    if next_token.is_none() {
-                        break;
-                    }
-                    if page_idx + 1 < pages {
-                        crate::client::rate_delay().await;
-                    }
-                }
+                let normalized = &MCPServer::normalize_username(name, username)?;
+                let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(100) as usize;
+                let snap_type = if route == McpToolRoute::Following {
+                    "following"
+                } else {
+                    "followers"
+                };
 
+                let (user_id, users) = fetch_user_relationships(
+                    &client,
+                    &access_token,
+                    normalized,
+                    snap_type,
+                    relationship_pages(limit),
+                    limit.max(1),
+                )
+                .await?;
                 costs::track_cost(
                     &self.costs_path,
                     snap_type,
                     &format!("/2/users/{user_id}/{snap_type}"),
                     users.len() as u64,
                 );
-
-                json_content(serde_json::json!({
-                    "type": "success",
-                    "message": "Diff snapshot fetched.",
+                Self::relationships_content(normalized, snap_type, &users)
+            }
+            McpToolRoute::Diff => {
+                let (client, access_token) = oauth_runtime().await?;
+                let username = args
+                    .get("username")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing username")?;
+                let normalized = username.trim_start_matches('@');
+                let following = bool_arg(&args, "following", "following");
+                let snap_type = if following { "following" } else { "followers" };
+                let pages = args.get("pages").and_then(|v| v.as_u64()).unwrap_or(5) as u32;
+
+                let (user_id, users) = fetch_user_relationships(
+                    &client,
+                    &access_token,
+                    normalized,
+                    snap_type,
+                    pages,
+                    usize::MAX,
+                )
+                .await?;
+
+                costs::track_cost(
+                    &self.costs_path,
+                    snap_type,
+                    &format!("/2/users/{user_id}/{snap_type}"),
+                    users.len() as u64,
+                );
+
+                let users: Vec<_> = users.iter().map(diff::user_snapshot).collect();
+                let current = crate::models::Snapshot {
+                    username: normalized.to_string(),
+                    snap_type: snap_type.to_string(),
+                    timestamp: chrono::Utc::now().to_rfc3339(),
+                    count: users.len(),
+                    users,
+                };
+                let snapshots_dir = Config::load()
+                    .map_err(|e| format!("Failed to load config: {e}"))?
+                    .snapshots_dir();
+                let previous = diff::load_latest_snapshot(&snapshots_dir, normalized, snap_type);
+                let snapshot_path = diff::save_snapshot(&snapshots_dir, &current)
+                    .map_err(|e| format!("Failed to save {snap_type} snapshot: {e}"))?;
+
+                let Some(previous) = previous else {
+                    return json_content(serde_json::json!({
+                        "type": "success",
+                        "message": format!(
+                            "Baseline captured for @{normalized} ({} {snap_type}), no prior snapshot.",
+                            current.count
+                        ),
+                        "data": {
+                            "username": normalized,
+                            "mode": snap_type,
+                            "baseline": true,
+                            "count": current.count,
+                            "snapshot_path": snapshot_path
+                        }
+                    }));
+                };
+
+                let delta = diff::compute_diff(&previous, &current);
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": format!(
+                        "@{normalized} {snap_type}: +{} / -{} since {}.",
+                        delta.added.len(),
+                        delta.removed.len(),
+                        delta.previous.timestamp
+                    ),
                     "data": {
                         "username": normalized,
                         "mode": snap_type,
-                        "count": users.len(),
-                        "users": users,
-                        "note": "Use CLI diff command for snapshot history and delta computation."
+                        "baseline": false,
+                        "diff": delta,
+                        "snapshot_path": snapshot_path
+                    }
+                }))
+            }
+            McpToolRoute::Compare => {
+                let (client, token) = bearer_runtime()?;
+                let first = args
+                    .get("username_a")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing username_a")?;
+                let second = args
+                    .get("username_b")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing username_b")?;
+                let first = MCPServer::normalize_username(name, first)?;
+                let second = MCPServer::normalize_username(name, second)?;
+                let count = args.get("count").and_then(|v| v.as_u64()).unwrap_or(20) as u32;
+
+                let mut sides = Vec::new();
+                for username in [&first, &second] {
+                    if !sides.is_empty() {
+                        crate::client::rate_delay().await;
+                    }
+                    let (user, tweets) =
+                        twitter::get_profile(&client, &token, username, count, false)
+                            .await
+                            .map_err(|e| Self::tool_error("Profile lookup failed", e))?;
+                    costs::track_cost(
+                        &self.costs_path,
+                        "profile",
+                        &format!("/2/users/by/username/{username}"),
+                        tweets.len() as u64 + 1,
+                    );
+                    sides.push((user, tweets));
+                }
+                let (user_b, tweets_b) = sides.pop().expect("two profiles fetched");
+                let (user_a, tweets_a) = sides.pop().expect("two profiles fetched");
+
+                let lowercase_set = |tweets: &[Tweet], pick: fn(&Tweet) -> &Vec<String>| {
+                    tweets
+                        .iter()
+                        .flat_map(|t| pick(t).iter().map(|v| v.to_lowercase()))
+                        .collect::<std::collections::BTreeSet<_>>()
+                };
+                let shared_hashtags: Vec<_> = lowercase_set(&tweets_a, |t| &t.hashtags)
+                    .intersection(&lowercase_set(&tweets_b, |t| &t.hashtags))
+                    .cloned()
+                    .collect();
+                let shared_mentions: Vec<_> = lowercase_set(&tweets_a, |t| &t.mentions)
+                    .intersection(&lowercase_set(&tweets_b, |t| &t.mentions))
+                    .cloned()
+                    .collect();
+
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": format!("Compared @{first} and @{second}."),
+                    "data": {
+                        "a": {
+                            "username": first,
+                            "user": user_a,
+                            "activity": activity_summary(&tweets_a)
+                        },
+                        "b": {
+                            "username": second,
+                            "user": user_b,
+                            "activity": activity_summary(&tweets_b)
+                        },
+                        "shared_hashtags": shared_hashtags,
+                        "shared_mentions": shared_mentions
                     }
                 }))
             }
             McpToolRoute::Report => {
+                let api_key =
+                    xai_runtime().map_err(|e| format!("xint_report needs Grok access: {e}"))?;
                 let (client, token) = bearer_runtime()?;
                 let topic = args
                     .get("topic")
@@ -1607,6 +5866,16 @@ impl MCPServer {
                     .and_then(|v| v.as_str())
                     .ok_or("Missing topic or query")?;
                 let pages = args.get("pages").and_then(|v| v.as_u64()).unwrap_or(2) as u32;
+                let with_sentiment = args
+                    .get("sentiment")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let model = args
+                    .get("model")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("grok-3-mini")
+                    .to_string();
+                self.report_progress(10, "searching");
                 let tweets = twitter::search(
                     &client,
                     &token,
@@ -1618,82 +5887,312 @@ impl MCPServer {
                     false,
                 )
                 .await
-                .map_err(|e| format!("Report search failed: {e}"))?;
+                .map_err(|e| Self::tool_error("Report search failed", e))?;
 
-                costs::track_cost(
+                let search_cost = costs::track_cost(
                     &self.costs_path,
                     "search",
                     "/2/tweets/search/recent",
                     tweets.len() as u64,
                 );
+                if tweets.is_empty() {
+                    return Err(format!("No recent tweets found for \"{topic}\""));
+                }
 
                 let mut top_tweets = tweets.clone();
                 twitter::sort_by(&mut top_tweets, "likes");
-                top_tweets.truncate(10);
+                top_tweets.truncate(20);
+                let http = reqwest::Client::new();
+
+                let mut sentiment_cost = 0.0;
+                let sentiment_summary = if with_sentiment {
+                    self.report_progress(40, "scoring sentiment");
+                    let (results, usage) =
+                        sentiment::analyze_sentiment(&http, &api_key, &top_tweets, Some(&model))
+                            .await
+                            .map_err(|e| format!("Sentiment analysis failed: {e}"))?;
+                    for batch in usage {
+                        sentiment_cost += self.track_grok_usage("grok_sentiment", batch).cost_usd;
+                    }
+                    let stats = sentiment::compute_stats(&results);
+                    serde_json::json!({
+                        "tweets_scored": results.len(),
+                        "positive": stats.positive,
+                        "negative": stats.negative,
+                        "neutral": stats.neutral,
+                        "mixed": stats.mixed,
+                        "average_score": stats.average_score
+                    })
+                } else {
+                    serde_json::Value::Null
+                };
+
+                self.report_progress(70, "summarizing");
+                let context = &top_tweets[..top_tweets.len().min(15)];
+                let opts = crate::models::GrokOpts {
+                    model,
+                    ..Default::default()
+                };
+                let response = grok::report_brief(&http, &api_key, topic, context, &opts)
+                    .await
+                    .map_err(|e| format!("Report summary failed: {e}"))?;
+                let summary_cost = self
+                    .track_grok_usage("grok_report", response.token_usage())
+                    .cost_usd;
+                let brief = grok::parse_report_brief(&response.content);
 
                 json_content(serde_json::json!({
                     "type": "success",
-                    "message": "Report data prepared.",
+                    "message": format!("Report generated from {} tweets.", tweets.len()),
                     "data": {
                         "topic": topic,
                         "generated_at": chrono::Utc::now().to_rfc3339(),
+                        "model": response.model,
                         "tweet_count": tweets.len(),
-                        "top_tweets": top_tweets,
-                        "ai_summary": serde_json::Value::Null,
-                        "note": "MCP report currently returns source data only; use CLI report for AI narrative output."
+                        "headline": brief.headline,
+                        "key_claims": brief.key_claims,
+                        "notable_accounts": notable_accounts(&tweets, 5),
+                        "sentiment": sentiment_summary,
+                        "sources": context
+                            .iter()
+                            .enumerate()
+                            .map(|(i, t)| serde_json::json!({
+                                "ref": i + 1,
+                                "username": t.username,
+                                "tweet_url": t.tweet_url
+                            }))
+                            .collect::<Vec<_>>(),
+                        "cost_usd": {
+                            "search": search_cost.cost_usd,
+                            "sentiment": sentiment_cost,
+                            "summary": summary_cost,
+                            "total": search_cost.cost_usd + sentiment_cost + summary_cost
+                        }
                     }
                 }))
             }
-            McpToolRoute::Sentiment => Ok(vec![MCPContent {
-                content_type: "text".to_string(),
-                text: {
-                    let api_key = xai_runtime()?;
-                    let tweets_raw = args
-                        .get("tweets")
-                        .ok_or("Missing tweets array for sentiment analysis")?;
-                    let tweets: Vec<Tweet> = serde_json::from_value(tweets_raw.clone())
-                        .map_err(|e| format!("Invalid tweets payload for sentiment: {e}"))?;
-                    let model = args.get("model").and_then(|v| v.as_str());
-                    let http = reqwest::Client::new();
-                    let results = sentiment::analyze_sentiment(&http, &api_key, &tweets, model)
-                        .await
-                        .map_err(|e| format!("Sentiment analysis failed: {e}"))?;
-                    let stats = sentiment::compute_stats(&results);
-                    serde_json::to_string_pretty(&serde_json::json!({
-                        "type": "success",
-                        "message": "Sentiment analysis completed.",
-                        "data": {
-                            "results": results,
-                            "stats": {
-                                "positive": stats.positive,
-                                "negative": stats.negative,
-                                "neutral": stats.neutral,
-                                "mixed": stats.mixed,
-                                "average_score": stats.average_score
-                            }
+            McpToolRoute::Sentiment => {
+                let tweets_raw = args
+                    .get("tweets")
+                    .and_then(|v| v.as_array())
+                    .ok_or("Missing tweets array for sentiment analysis")?;
+                let tweets = sentiment_input_tweets(tweets_raw)?;
+                let model = args.get("model").and_then(|v| v.as_str());
+                let api_key = Config::load()
+                    .map_err(|e| format!("Failed to load config: {e}"))?
+                    .xai_api_key;
+
+                let (engine, results) = match api_key {
+                    Some(api_key) => {
+                        let http = reqwest::Client::new();
+                        let (results, usage) =
+                            sentiment::analyze_sentiment(&http, &api_key, &tweets, model)
+                                .await
+                                .map_err(|e| format!("Sentiment analysis failed: {e}"))?;
+                        for batch in usage {
+                            self.track_grok_usage("grok_sentiment", batch);
                         }
-                    }))
-                    .map_err(|e| format!("Failed to encode sentiment payload: {e}"))?
-                },
-            }]),
+                        ("grok", results)
+                    }
+                    None => ("lexicon", sentiment::analyze_lexicon(&tweets)),
+                };
+                let stats = sentiment::compute_stats(&results);
+                let share =
+                    |n: u32| (n as f64 / results.len().max(1) as f64 * 100.0).round() / 100.0;
+
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": format!("Sentiment analysis completed for {} tweets.", results.len()),
+                    "data": {
+                        "engine": engine,
+                        "results": results,
+                        "stats": {
+                            "positive": stats.positive,
+                            "negative": stats.negative,
+                            "neutral": stats.neutral,
+                            "mixed": stats.mixed,
+                            "average_score": stats.average_score
+                        },
+                        "distribution": {
+                            "positive": share(stats.positive),
+                            "negative": share(stats.negative),
+                            "neutral": share(stats.neutral),
+                            "mixed": share(stats.mixed)
+                        }
+                    }
+                }))
+            }
+            McpToolRoute::Estimate => {
+                let target = args
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Missing name")?;
+                if resolve_tool_route(target).data.is_none() {
+                    return Err(serde_json::json!({
+                        "code": "INVALID_ARGUMENT",
+                        "message": format!("Unknown tool: {target}"),
+                        "tool": name,
+                        "field": "name",
+                        "value": target,
+                    })
+                    .to_string());
+                }
+                let target_args = args
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_else(|| serde_json::json!({}));
+
+                let estimate = costs::estimate(target, &target_args);
+                let (low, high) = costs::estimate_range(target, &target_args);
+                let guarded = Self::tool_budget_guarded(target);
+                let budget = self.daily_budget();
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": format!("{target} would cost about ${estimate:.4} (${low:.4}-${high:.4})."),
+                    "data": {
+                        "tool": target,
+                        "estimate_usd": estimate,
+                        "low_usd": low,
+                        "high_usd": high,
+                        "budget_guarded": guarded,
+                        "daily_remaining_usd": budget.remaining,
+                        "fits_daily_budget": !guarded || !budget.enabled || high <= budget.remaining
+                    }
+                }))
+            }
+            McpToolRoute::BudgetStatus => {
+                let budget = self.daily_budget();
+                let windows = self.budget_windows();
+                let categories: Vec<serde_json::Value> = ["fetch", "ai", "engagement"]
+                    .into_iter()
+                    .filter_map(|category| {
+                        let limit = self.settings.budget.categories.limit_usd(category)?;
+                        let spent = costs::category_spent(&self.costs_path, category);
+                        Some(serde_json::json!({
+                            "category": category,
+                            "spent": spent,
+                            "limit": limit,
+                            "remaining": (limit - spent).max(0.0),
+                            "allowed": spent < limit
+                        }))
+                    })
+                    .collect();
+                let allowed = !self.enforce_budget || windows.iter().all(|w| w.allowed);
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": format!(
+                        "Spent ${:.2} of ${:.2} today ({}%).",
+                        budget.spent, budget.limit, budget.percent_used
+                    ),
+                    "data": {
+                        "enforced": self.enforce_budget,
+                        "allowed": allowed,
+                        "spent": budget.spent,
+                        "limit": budget.limit,
+                        "remaining": budget.remaining,
+                        "percent_used": budget.percent_used,
+                        "warn": budget.warning,
+                        "warn_threshold": budget.warn_threshold,
+                        "windows": windows,
+                        "categories": categories
+                    }
+                }))
+            }
+            McpToolRoute::BudgetReset => {
+                let period = args
+                    .get("period")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("today");
+                let confirm = args
+                    .get("confirm")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let cleared = costs::reset_period(&self.costs_path, period, confirm);
+                if confirm {
+                    tracing::warn!(
+                        period,
+                        cleared_usd = cleared.total_cost,
+                        "cost ledger reset"
+                    );
+                }
+
+                json_content(serde_json::json!({
+                    "type": "success",
+                    "message": if confirm {
+                        format!("Cleared ${:.4} of recorded spend ({period}).", cleared.total_cost)
+                    } else {
+                        format!(
+                            "Would clear ${:.4} of recorded spend ({period}); pass confirm: true to apply.",
+                            cleared.total_cost
+                        )
+                    },
+                    "data": {
+                        "applied": confirm,
+                        "cleared": cleared
+                    }
+                }))
+            }
             McpToolRoute::Costs => {
                 let period = args
                     .get("period")
                     .and_then(|v| v.as_str())
                     .unwrap_or("today");
+                let export = match args.get("export").and_then(|v| v.as_str()) {
+                    Some("csv") => {
+                        let path = args
+                            .get("path")
+                            .and_then(|v| v.as_str())
+                            .map(ToString::to_string)
+                            .unwrap_or_else(|| {
+                                format!(
+                                    "costs-{period}-{}.csv",
+                                    chrono::Utc::now().format("%Y%m%d-%H%M%S")
+                                )
+                            });
+                        let target = match self.allowed_write_dirs.first() {
+                            Some(base) if std::path::Path::new(&path).is_relative() => {
+                                base.join(&path).to_string_lossy().to_string()
+                            }
+                            _ => path,
+                        };
+                        let resolved = self.ensure_write_path_allowed(name, &target)?;
+                        let rows = costs::export_csv(&self.costs_path, period, &resolved)
+                            .map_err(|e| format!("Failed to export costs: {e:#}"))?;
+                        Some(serde_json::json!({
+                            "format": "csv",
+                            "path": resolved,
+                            "rows": rows
+                        }))
+                    }
+                    Some(other) => return Err(format!("Unsupported export format: {other}")),
+                    None => None,
+                };
                 let summary = costs::get_cost_summary(&self.costs_path, period);
-                let budget = costs::check_budget(&self.costs_path);
+                let totals = costs::period_costs(&self.costs_path, period);
+                let budget = self.daily_budget();
+                let message = match export.as_ref().and_then(|e| e["path"].as_str()) {
+                    Some(path) => format!("Cost summary generated; ledger exported to {path}."),
+                    None => "Cost summary generated.".to_string(),
+                };
                 json_content(serde_json::json!({
                     "type": "success",
-                    "message": "Cost summary generated.",
+                    "message": message,
                     "data": {
                         "period": period,
+                        "export": export,
                         "summary": summary,
+                        "total_usd": totals.total_cost,
+                        "calls": totals.calls,
+                        "tweets_read": totals.tweets_read,
+                        "by_operation": totals.by_operation,
+                        "by_tool": costs::breakdown(&self.costs_path, period),
+                        "forecast": costs::forecast(&self.costs_path, &budget),
                         "budget": {
                             "allowed": budget.allowed,
                             "spent": budget.spent,
                             "limit": budget.limit,
                             "remaining": budget.remaining,
+                            "percent_used": budget.percent_used,
                             "warning": budget.warning
                         }
                     }
@@ -1702,90 +6201,541 @@ impl MCPServer {
         }
     }
 
-    pub async fn run_stdio(&mut self) -> Result<(), String> {
-        let stdin = tokio::io::stdin();
-        let mut reader = BufReader::new(stdin).lines();
+    /// Serve MCP over stdin/stdout. `tools/call` requests run on their own
+    /// tasks (bounded by the concurrency limit) so independent calls overlap;
+    /// everything else is handled in order as it arrives.
+    ///
+    /// SIGTERM/SIGINT stop reading new lines, let in-flight calls finish (so
+    /// their reliability and cost records are written) and return `Ok`; a
+    /// second signal aborts calls still running.
+    pub async fn run_stdio(self: std::sync::Arc<Self>) -> Result<(), String> {
+        let mut reader = BufReader::new(tokio::io::stdin());
+        let max_line = max_line_bytes();
+
+        // One printer task keeps notifications and responses in send order
+        // while tools are still running.
+        let (out_tx, mut out_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        let printer = tokio::spawn(async move {
+            while let Some(line) = out_rx.recv().await {
+                println!("{line}");
+            }
+        });
 
-        while let Ok(Some(line)) = reader.next_line().await {
-            match self.handle_message(&line).await {
-                Ok(Some(response)) => println!("{response}"),
-                Ok(None) => {}
-                Err(err) => {
-                    let response = serde_json::json!({
-                        "jsonrpc": "2.0",
-                        "error": { "code": -32603, "message": err }
-                    });
-                    println!("{response}");
+        // Read stdin on its own task so `notifications/cancelled` is seen
+        // while a tool call is still running.
+        let (line_tx, mut line_rx) = tokio::sync::mpsc::unbounded_channel::<StdinLine>();
+        let cancellations = self.cancellations.clone();
+        tokio::spawn(async move {
+            while let Ok(Some(read)) = read_bounded_line(&mut reader, max_line).await {
+                if let StdinLine::Line(line) = &read {
+                    if apply_cancellation(&cancellations, None, line) {
+                        continue;
+                    }
+                }
+                if line_tx.send(read).is_err() {
+                    break;
+                }
+            }
+        });
+        *lock_state(&self.notifier) = Some(out_tx.clone());
+
+        let mut shutdown = spawn_shutdown_listener();
+        let mut tool_calls = tokio::task::JoinSet::new();
+        loop {
+            let line = tokio::select! {
+                biased;
+                Some(signal) = shutdown.recv() => {
+                    tracing::info!(signal, in_flight = tool_calls.len(), "shutting down");
+                    break;
+                }
+                line = line_rx.recv() => match line {
+                    Some(StdinLine::Line(line)) => line,
+                    Some(StdinLine::TooLong) => {
+                        tracing::warn!(max_bytes = max_line, "rejected oversized stdin message");
+                        let rejected = RequestError {
+                            id: serde_json::Value::Null,
+                            code: -32600,
+                            message: format!(
+                                "Invalid Request: message exceeds {max_line} bytes (XINT_MCP_MAX_LINE)"
+                            ),
+                        };
+                        let _ = out_tx.send(rejected.to_response());
+                        continue;
+                    }
+                    None => break,
+                },
+            };
+            if is_tool_call(&line) {
+                let server = std::sync::Arc::clone(&self);
+                let out_tx = out_tx.clone();
+                tool_calls.spawn(async move {
+                    server.respond_stdio(&line, &out_tx).await;
+                });
+            } else {
+                self.respond_stdio(&line, &out_tx).await;
+            }
+            while tool_calls.try_join_next().is_some() {}
+        }
+
+        loop {
+            tokio::select! {
+                joined = tool_calls.join_next() => {
+                    if joined.is_none() {
+                        break;
+                    }
+                }
+                Some(signal) = shutdown.recv() => {
+                    tracing::warn!(signal, aborted = tool_calls.len(), "aborting in-flight tool calls");
+                    tool_calls.abort_all();
                 }
             }
         }
 
+        *lock_state(&self.notifier) = None;
+        drop(out_tx);
+        let _ = printer.await;
+        let _ = std::io::Write::flush(&mut std::io::stdout());
         Ok(())
     }
-}
 
-// ============================================================================
-// CLI Command - using McpArgs from cli module
-// ============================================================================
+    async fn respond_stdio(&self, line: &str, out_tx: &tokio::sync::mpsc::UnboundedSender<String>) {
+        match self.handle_message(line).await {
+            Ok(Some(response)) => {
+                let _ = out_tx.send(response);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                let _ = out_tx.send(err.to_response());
+            }
+        }
+    }
 
-pub async fn run(args: McpArgs, config: &Config, global_policy: PolicyMode) -> anyhow::Result<()> {
-    let policy_mode = args.policy.unwrap_or(global_policy);
-    let enforce_budget = !args.no_budget_guard;
+    /// Serve MCP over HTTP + Server-Sent Events on `127.0.0.1:port`.
+    /// `GET /sse` opens a session stream whose first `endpoint` event names
+    /// the URL to `POST` JSON-RPC messages to; responses arrive on the stream.
+    pub async fn run_sse(self: std::sync::Arc<Self>, port: u16) -> Result<(), String> {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+            .await
+            .map_err(|e| format!("Failed to bind SSE port {port}: {e}"))?;
+        eprintln!("xint MCP SSE endpoint: http://127.0.0.1:{port}/sse");
+        self.serve_sse(listener).await
+    }
 
-    println!(
-        "Starting xint MCP server (sse: {}, port: {}, policy: {}, budget_guard: {})...",
-        args.sse,
-        args.port,
-        policy::as_str(policy_mode),
-        if enforce_budget {
-            "enabled"
-        } else {
-            "disabled"
+    async fn serve_sse(
+        self: std::sync::Arc<Self>,
+        listener: tokio::net::TcpListener,
+    ) -> Result<(), String> {
+        let (events_tx, mut events_rx) = tokio::sync::mpsc::unbounded_channel::<SseEvent>();
+        let cancellations = self.cancellations.clone();
+        let allowed_origins = std::sync::Arc::new(self.allowed_origins.clone());
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(serve_sse_connection(
+                    socket,
+                    events_tx.clone(),
+                    cancellations.clone(),
+                    allowed_origins.clone(),
+                ));
+            }
+        });
+
+        let mut streams =
+            std::collections::HashMap::<String, tokio::sync::mpsc::UnboundedSender<String>>::new();
+        while let Some(event) = events_rx.recv().await {
+            match event {
+                SseEvent::Open { stream, reply } => {
+                    let session_id = self.open_session();
+                    streams.insert(session_id.clone(), stream);
+                    let _ = reply.send(session_id);
+                }
+                SseEvent::Message {
+                    session_id,
+                    body,
+                    reply,
+                } => {
+                    let Some(stream) = streams.get(&session_id).cloned() else {
+                        let _ = reply.send(404);
+                        continue;
+                    };
+                    let _ = reply.send(202);
+                    // As on stdio, tools/call runs on its own task so a slow
+                    // call never holds up other requests or sessions;
+                    // everything else is answered in arrival order.
+                    let server = std::sync::Arc::clone(&self);
+                    let tool_call = is_tool_call(&body);
+                    let respond = async move {
+                        server.respond_sse(&session_id, &body, stream).await;
+                    };
+                    if tool_call {
+                        tokio::spawn(respond);
+                    } else {
+                        respond.await;
+                    }
+                }
+                SseEvent::Closed { session_id } => {
+                    streams.remove(&session_id);
+                    self.close_session(&session_id);
+                }
+            }
         }
-    );
 
-    let mut server = MCPServer::new(
-        policy_mode,
-        enforce_budget,
-        config.costs_path(),
-        config.reliability_path(),
-    );
-    server.run_stdio().await.map_err(|e| anyhow::anyhow!(e))?;
+        Ok(())
+    }
 
-    Ok(())
+    async fn respond_sse(
+        &self,
+        session_id: &str,
+        body: &str,
+        stream: tokio::sync::mpsc::UnboundedSender<String>,
+    ) {
+        let response = SESSION_STREAM
+            .scope(
+                stream.clone(),
+                self.handle_session_message(Some(session_id), body),
+            )
+            .await;
+        let response = match response {
+            Ok(response) => response,
+            Err(err) => Some(err.to_response()),
+        };
+        if let Some(response) = response {
+            let _ = stream.send(response);
+        }
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::env;
-    use std::sync::{Mutex, OnceLock};
-    use tokio::io::{AsyncReadExt, AsyncWriteExt};
-    use tokio::net::TcpListener;
-    use tokio::sync::oneshot;
+fn is_tool_call(msg: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(msg)
+        .ok()
+        .and_then(|v| v.get("method").and_then(|m| m.as_str()).map(str::to_string))
+        .is_some_and(|method| method == "tools/call")
+}
 
-    fn env_lock() -> &'static Mutex<()> {
-        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
-        LOCK.get_or_init(|| Mutex::new(()))
-    }
+/// `localhost`, `127.0.0.1` or `[::1]`, with or without a port.
+fn is_loopback_host(host: &str) -> bool {
+    let host = host.trim();
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    };
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "localhost" | "127.0.0.1" | "::1"
+    )
+}
 
-    fn save_env(key: &str) -> Option<String> {
-        env::var(key).ok()
+/// Browser origins may call the SSE server only from localhost or from an
+/// origin passed with `--allow-origin`; anything else could be a page the
+/// user merely visited.
+fn origin_allowed(origin: &str, allowed: &[String]) -> bool {
+    let origin = origin.trim().trim_end_matches('/');
+    if allowed
+        .iter()
+        .any(|a| a.trim().trim_end_matches('/').eq_ignore_ascii_case(origin))
+    {
+        return true;
     }
+    url::Url::parse(origin)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .and_then(|url| url.host_str().map(is_loopback_host))
+        .unwrap_or(false)
+}
 
-    fn restore_env(key: &str, value: Option<String>) {
-        if let Some(v) = value {
-            env::set_var(key, v);
-        } else {
-            env::remove_var(key);
+/// Handle one HTTP connection for the SSE transport.
+async fn serve_sse_connection(
+    mut socket: tokio::net::TcpStream,
+    events: tokio::sync::mpsc::UnboundedSender<SseEvent>,
+    cancellations: CancellationMap,
+    allowed_origins: std::sync::Arc<Vec<String>>,
+) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    const MAX_REQUEST_BYTES: usize = 4 * 1024 * 1024;
+
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let header_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        match socket.read(&mut chunk).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+        if buf.len() > MAX_REQUEST_BYTES {
+            let _ = socket
+                .write_all(
+                    b"HTTP/1.1 431 Request Header Fields Too Large\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .await;
+            return;
         }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut request_line = head.lines().next().unwrap_or("").split_whitespace();
+    let method = request_line.next().unwrap_or("").to_string();
+    let target = request_line.next().unwrap_or("").to_string();
+    let header = |name: &str| {
+        head.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case(name)
+                .then(|| value.trim().to_string())
+        })
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target.as_str(), ""));
+    // A loopback Host defeats DNS rebinding; the Origin check keeps other
+    // sites' pages from driving the server through the user's browser.
+    let origin = header("Origin");
+    let trusted = header("Host").is_some_and(|host| is_loopback_host(&host))
+        && origin
+            .as_deref()
+            .is_none_or(|origin| origin_allowed(origin, &allowed_origins));
+    let cors = match origin.as_deref() {
+        Some(origin) if trusted => format!(
+            "Access-Control-Allow-Origin: {origin}\r\nVary: Origin\r\nAccess-Control-Allow-Headers: Content-Type, Mcp-Session-Id\r\nAccess-Control-Allow-Methods: GET, POST, OPTIONS\r\n"
+        ),
+        _ => String::new(),
+    };
+    let respond = |status: &str, body: &str| {
+        format!(
+            "HTTP/1.1 {status}\r\n{cors}Content-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    };
+
+    if !trusted {
+        let _ = socket
+            .write_all(respond("403 Forbidden", "origin not allowed").as_bytes())
+            .await;
+        return;
     }
 
-    async fn spawn_mock_server(
-        status_code: u16,
-        response_body: &str,
-    ) -> (
-        String,
+    match (method.as_str(), path) {
+        ("OPTIONS", _) => {
+            let _ = socket
+                .write_all(respond("204 No Content", "").as_bytes())
+                .await;
+        }
+        ("GET", "/sse") => {
+            let (stream_tx, mut stream_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+            let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+            if events
+                .send(SseEvent::Open {
+                    stream: stream_tx,
+                    reply: reply_tx,
+                })
+                .is_err()
+            {
+                return;
+            }
+            let Ok(session_id) = reply_rx.await else {
+                return;
+            };
+            let preamble = format!(
+                "HTTP/1.1 200 OK\r\n{cors}Content-Type: text/event-stream\r\nCache-Control: no-cache\r\nMcp-Session-Id: {session_id}\r\nConnection: keep-alive\r\n\r\nevent: endpoint\ndata: /message?sessionId={session_id}\n\n"
+            );
+            if socket.write_all(preamble.as_bytes()).await.is_ok() {
+                while let Some(message) = stream_rx.recv().await {
+                    let frame = format!("event: message\ndata: {message}\n\n");
+                    if socket.write_all(frame.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            let _ = events.send(SseEvent::Closed { session_id });
+        }
+        ("POST", "/message") => {
+            let session_id = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("sessionId="))
+                .map(str::to_string)
+                .or_else(|| header("Mcp-Session-Id"));
+            let Some(session_id) = session_id else {
+                let _ = socket
+                    .write_all(respond("400 Bad Request", "missing sessionId").as_bytes())
+                    .await;
+                return;
+            };
+            let content_length = header("Content-Length")
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(0);
+            if content_length > MAX_REQUEST_BYTES {
+                let _ = socket
+                    .write_all(
+                        respond("413 Payload Too Large", "request body exceeds 4 MB").as_bytes(),
+                    )
+                    .await;
+                return;
+            }
+            while buf.len() < header_end + content_length {
+                match socket.read(&mut chunk).await {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                }
+            }
+            let body_end = buf.len().min(header_end + content_length);
+            let body = String::from_utf8_lossy(&buf[header_end..body_end]).to_string();
+            if apply_cancellation(&cancellations, Some(&session_id), &body) {
+                let _ = socket
+                    .write_all(respond("202 Accepted", "Accepted").as_bytes())
+                    .await;
+                return;
+            }
+
+            let (reply_tx, reply_rx) = tokio::sync::oneshot::channel();
+            let sent = events.send(SseEvent::Message {
+                session_id,
+                body,
+                reply: reply_tx,
+            });
+            let status = match (sent, reply_rx.await) {
+                (Ok(()), Ok(202)) => respond("202 Accepted", "Accepted"),
+                (Ok(()), Ok(404)) => respond("404 Not Found", "unknown session"),
+                _ => respond("503 Service Unavailable", "server shutting down"),
+            };
+            let _ = socket.write_all(status.as_bytes()).await;
+        }
+        _ => {
+            let _ = socket
+                .write_all(respond("404 Not Found", "not found").as_bytes())
+                .await;
+        }
+    }
+}
+
+// ============================================================================
+// CLI Command - using McpArgs from cli module
+// ============================================================================
+
+/// `XINT_LOG` verbosity (`error|warn|info|debug`); warnings by default.
+fn log_level(value: Option<&str>) -> tracing::level_filters::LevelFilter {
+    use tracing::level_filters::LevelFilter;
+    match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+        Some("error") => LevelFilter::ERROR,
+        Some("info") => LevelFilter::INFO,
+        Some("debug") => LevelFilter::DEBUG,
+        Some("off") => LevelFilter::OFF,
+        _ => LevelFilter::WARN,
+    }
+}
+
+/// Structured logs go to stderr only, so they never interleave with
+/// JSON-RPC frames on stdout.
+fn init_logging() {
+    let _ = tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_ansi(false)
+        .with_max_level(log_level(std::env::var("XINT_LOG").ok().as_deref()))
+        .try_init();
+}
+
+pub async fn run(args: McpArgs, config: &Config, global_policy: PolicyMode) -> anyhow::Result<()> {
+    let settings = match args.config.as_deref() {
+        Some(path) => McpConfig::from_file(path)?,
+        None => McpConfig::default(),
+    };
+    let policy_mode = args
+        .policy
+        .or(settings.policy.mode)
+        .unwrap_or(global_policy);
+    let enforce_budget = !args.no_budget_guard && settings.budget.enforce.unwrap_or(true);
+    let budget_override_usd = args.budget_override_usd.or_else(|| {
+        std::env::var("XINT_MCP_BUDGET_OVERRIDE_USD")
+            .ok()
+            .and_then(|v| v.trim().parse::<f64>().ok())
+    });
+
+    init_logging();
+
+    // stdout is the JSON-RPC channel; keep everything else on stderr.
+    eprintln!(
+        "Starting xint MCP server (sse: {}, port: {}, policy: {}, budget_guard: {})...",
+        args.sse,
+        args.port,
+        policy::as_str(policy_mode),
+        if enforce_budget {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+    if let Some(override_usd) = budget_override_usd {
+        tracing::warn!(
+            override_limit_usd = override_usd,
+            "daily budget override active for this process; it is not persisted"
+        );
+    }
+
+    let server = MCPServer::new(
+        policy_mode,
+        enforce_budget,
+        config.costs_path(),
+        config.reliability_path(),
+    )
+    .with_settings(settings)
+    .with_allowed_write_dirs(args.allow_write_dirs)
+    .with_allowed_origins(args.allow_origins)
+    .with_max_tweets(args.max_tweets)
+    .with_max_concurrency(args.max_concurrency)
+    .with_policy_audit_log(config.policy_audit_path())
+    .with_budget_override(budget_override_usd);
+    if args.sse {
+        std::sync::Arc::new(server)
+            .run_sse(args.port)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+    } else {
+        std::sync::Arc::new(server)
+            .run_stdio()
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+        // Everything is flushed; exit now rather than let runtime shutdown
+        // wait on the stdin read that is still blocked after a signal.
+        std::process::exit(0);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::sync::OnceLock;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::{oneshot, Mutex};
+
+    fn mark_initialized(server: &MCPServer) {
+        server
+            .initialized
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    fn env_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    fn save_env(key: &str) -> Option<String> {
+        env::var(key).ok()
+    }
+
+    fn restore_env(key: &str, value: Option<String>) {
+        if let Some(v) = value {
+            env::set_var(key, v);
+        } else {
+            env::remove_var(key);
+        }
+    }
+
+    async fn spawn_mock_server(
+        status_code: u16,
+        response_body: &str,
+    ) -> (
+        String,
         oneshot::Receiver<String>,
         tokio::task::JoinHandle<()>,
     ) {
@@ -1846,119 +6796,2961 @@ mod tests {
             socket
                 .write_all(response.as_bytes())
                 .await
-                .expect("write response");
-        });
+                .expect("write response");
+        });
+
+        (format!("http://{addr}/v1"), rx, handle)
+    }
+
+    #[tokio::test]
+    async fn package_validate_reports_problems_without_network() {
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+
+        let result = server
+            .execute_tool(
+                "xint_package_validate",
+                serde_json::json!({
+                    "name": "Bad package",
+                    "topic_query": "ai agents",
+                    "sources": ["x_api_v2", "web"],
+                    "time_window": {
+                        "from": "2026-01-03T00:00:00Z",
+                        "to": "2026-01-02T00:00:00Z"
+                    }
+                }),
+            )
+            .await
+            .expect("validate call");
+
+        let text = &result[0].text;
+        assert!(text.contains("\"valid\": false"));
+        assert!(text.contains("unknown source 'web'"));
+        assert!(text.contains("time_window.from must be before time_window.to"));
+        assert!(text.contains("\"field\": \"sources\""));
+    }
+
+    #[tokio::test]
+    async fn package_create_rejects_malformed_time_window() {
+        let _guard = env_lock().lock().await;
+        let prev_window = save_env("XINT_PACKAGE_MAX_WINDOW_DAYS");
+        env::set_var("XINT_PACKAGE_MAX_WINDOW_DAYS", "30");
+
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        let spec = |from: &str, to: &str| {
+            serde_json::json!({
+                "name": "Window package",
+                "topic_query": "ai agents",
+                "sources": ["x_api_v2"],
+                "time_window": { "from": from, "to": to }
+            })
+        };
+
+        let err = server
+            .execute_tool(
+                "xint_package_create",
+                spec("2026-01-01T00:00:00Z", "2026-01-01T00:00:00Z"),
+            )
+            .await
+            .expect_err("equal bounds rejected");
+        assert!(err.contains("VALIDATION_FAILED"));
+        assert!(err.contains("must be before"));
+
+        let err = server
+            .execute_tool(
+                "xint_package_create",
+                spec("yesterday", "2026-01-01T00:00:00Z"),
+            )
+            .await
+            .expect_err("unparseable bound rejected");
+        assert!(err.contains("\"field\":\"time_window.from\""));
+
+        let err = server
+            .execute_tool(
+                "xint_package_create",
+                spec("2026-01-01T00:00:00Z", "2026-03-01T00:00:00Z"),
+            )
+            .await
+            .expect_err("oversized window rejected");
+        assert!(err.contains("the maximum is 30"));
+
+        restore_env("XINT_PACKAGE_MAX_WINDOW_DAYS", prev_window);
+    }
+
+    #[tokio::test]
+    async fn package_create_contract_request_includes_headers_and_payload() {
+        let _guard = env_lock().lock().await;
+        let prev_base = save_env("XINT_PACKAGE_API_BASE_URL");
+        let prev_key = save_env("XINT_PACKAGE_API_KEY");
+        let prev_workspace = save_env("XINT_WORKSPACE_ID");
+
+        let (base_url, req_rx, server_task) =
+            spawn_mock_server(202, r#"{"package_id":"pkg_123","status":"queued"}"#).await;
+        env::set_var("XINT_PACKAGE_API_BASE_URL", base_url);
+        env::set_var("XINT_PACKAGE_API_KEY", "xck_contract");
+        env::set_var("XINT_WORKSPACE_ID", "ws_contract");
+
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+
+        let result = server
+            .execute_tool(
+                "xint_package_create",
+                serde_json::json!({
+                    "name": "Contract package",
+                    "topic_query": "ai agents",
+                    "sources": ["x_api_v2"],
+                    "time_window": {
+                        "from": "2026-01-01T00:00:00.000Z",
+                        "to": "2026-01-02T00:00:00.000Z"
+                    },
+                    "policy": "private",
+                    "analysis_profile": "summary"
+                }),
+            )
+            .await
+            .expect("package create call");
+
+        let request_raw = req_rx.await.expect("captured request");
+        server_task.await.expect("server task");
+
+        let lower = request_raw.to_lowercase();
+        assert!(lower.contains("post /v1/packages http/1.1"));
+        assert!(lower.contains("authorization: bearer xck_contract"));
+        assert!(lower.contains("x-workspace-id: ws_contract"));
+        assert!(request_raw.contains("\"name\":\"Contract package\""));
+        assert!(request_raw.contains("\"topic_query\":\"ai agents\""));
+        assert!(request_raw.contains("\"analysis_profile\":\"summary\""));
+        assert!(result[0].text.contains("\"package_id\": \"pkg_123\""));
+
+        restore_env("XINT_PACKAGE_API_BASE_URL", prev_base);
+        restore_env("XINT_PACKAGE_API_KEY", prev_key);
+        restore_env("XINT_WORKSPACE_ID", prev_workspace);
+    }
+
+    #[tokio::test]
+    async fn quota_error_includes_upgrade_url() {
+        let _guard = env_lock().lock().await;
+        let prev_base = save_env("XINT_PACKAGE_API_BASE_URL");
+        let prev_upgrade = save_env("XINT_BILLING_UPGRADE_URL");
+
+        let (base_url, _req_rx, server_task) = spawn_mock_server(
+            402,
+            r#"{"code":"QUOTA_EXCEEDED","error":"Package limit reached for current plan."}"#,
+        )
+        .await;
+        env::set_var("XINT_PACKAGE_API_BASE_URL", base_url);
+        env::set_var(
+            "XINT_BILLING_UPGRADE_URL",
+            "https://xint.dev/pricing?src=contract-test",
+        );
+
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+
+        let err = server
+            .call_package_api(
+                reqwest::Method::POST,
+                "/packages",
+                Some(serde_json::json!({})),
+            )
+            .await
+            .expect_err("expected quota error");
+        server_task.await.expect("server task");
+
+        assert!(err.contains("QUOTA_EXCEEDED"));
+        assert!(err.contains("Upgrade: https://xint.dev/pricing?src=contract-test"));
+
+        restore_env("XINT_PACKAGE_API_BASE_URL", prev_base);
+        restore_env("XINT_BILLING_UPGRADE_URL", prev_upgrade);
+    }
+
+    #[tokio::test]
+    async fn package_query_requires_citations_when_requested() {
+        let _guard = env_lock().lock().await;
+        let prev_base = save_env("XINT_PACKAGE_API_BASE_URL");
+
+        let (base_url, _req_rx, server_task) = spawn_mock_server(
+            200,
+            r#"{"answer":"No citations","claims":[{"claim_id":"claim_1","text":"example"}],"citations":[]}"#,
+        )
+        .await;
+        env::set_var("XINT_PACKAGE_API_BASE_URL", base_url);
+
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+
+        let err = server
+            .execute_tool(
+                "xint_package_query",
+                serde_json::json!({
+                    "query": "what changed?",
+                    "package_ids": ["pkg_123"],
+                    "require_citations": true
+                }),
+            )
+            .await
+            .expect_err("expected citation validation failure");
+        server_task.await.expect("server task");
+
+        assert!(err.contains("missing citations"));
+
+        restore_env("XINT_PACKAGE_API_BASE_URL", prev_base);
+    }
+
+    #[tokio::test]
+    async fn costs_tool_returns_success_payload_without_network() {
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+
+        let result = server
+            .execute_tool("xint_costs", serde_json::json!({ "period": "today" }))
+            .await
+            .expect("costs tool call");
+
+        assert!(result[0].text.contains("\"type\": \"success\""));
+        assert!(result[0].text.contains("\"period\": \"today\""));
+    }
+
+    #[tokio::test]
+    async fn costs_tool_breaks_down_period_spend_even_over_budget() {
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-costs-breakdown.json");
+        let _ = std::fs::remove_file(&costs_path);
+        costs::set_budget(&costs_path, 0.01);
+        costs::track_cost(&costs_path, "search", "/2/tweets/search/recent", 10);
+        costs::track_usd_cost(&costs_path, "grok_analyze", "/v1/chat/completions", 0.002);
+
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            true,
+            costs_path.clone(),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        mark_initialized(&server);
+        let response = server
+            .handle_message(
+                r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"xint_costs","arguments":{"period":"week"}}}"#,
+            )
+            .await
+            .expect("costs call")
+            .expect("response");
+        let response: serde_json::Value = serde_json::from_str(&response).expect("json");
+        let text = response["result"]["content"][0]["text"]
+            .as_str()
+            .expect("text");
+        let payload: serde_json::Value = serde_json::from_str(text).expect("payload");
+        let data = &payload["data"];
+
+        assert_eq!(data["total_usd"], 0.052);
+        assert_eq!(data["by_operation"]["search"]["tweets"], 10);
+        assert_eq!(data["by_operation"]["grok_analyze"]["calls"], 1);
+        assert_eq!(data["budget"]["allowed"], false);
+        assert_eq!(data["budget"]["remaining"], 0.0);
+        assert!(data["summary"].as_str().unwrap().contains("By operation"));
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[tokio::test]
+    async fn cache_clear_tool_returns_success_payload() {
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+
+        let result = server
+            .execute_tool("xint_cache_clear", serde_json::json!({}))
+            .await
+            .expect("cache clear tool call");
+
+        assert!(result[0].text.contains("\"type\": \"success\""));
+        assert!(result[0].text.contains("\"message\": \"Cache cleared.\""));
+        assert!(result[0].text.contains("\"cleared\""));
+    }
+
+    #[tokio::test]
+    async fn ratelimit_method_reports_remaining_calls_per_tool() {
+        let _guard = env_lock().lock().await;
+        let prev_limit = save_env("XINT_MCP_RATE_LIMIT");
+        env::set_var("XINT_MCP_RATE_LIMIT", "1");
+
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        mark_initialized(&server);
+        restore_env("XINT_MCP_RATE_LIMIT", prev_limit);
+
+        let call = r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"xint_costs","arguments":{}}}"#;
+        let first = server.handle_message(call).await.expect("first call");
+        assert!(first.expect("response").contains("\"result\""));
+        let second = server.handle_message(call).await.expect("second call");
+        assert!(second.expect("response").contains("RATE_LIMITED"));
+
+        let status = server
+            .handle_message(
+                r#"{"jsonrpc":"2.0","id":2,"method":"xint/ratelimit","params":{"tool":"xint_costs"}}"#,
+            )
+            .await
+            .expect("ratelimit call")
+            .expect("response");
+        let parsed: serde_json::Value = serde_json::from_str(&status).expect("json");
+        assert_eq!(parsed["result"]["enabled"], true);
+        assert_eq!(parsed["result"]["tools"][0]["tool"], "xint_costs");
+        assert_eq!(parsed["result"]["tools"][0]["remaining"], 0);
+    }
+
+    #[tokio::test]
+    async fn rate_limiter_keeps_limiting_after_a_poisoned_lock() {
+        let _guard = env_lock().lock().await;
+        let prev_limit = save_env("XINT_MCP_RATE_LIMIT");
+        env::set_var("XINT_MCP_RATE_LIMIT", "1");
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        restore_env("XINT_MCP_RATE_LIMIT", prev_limit);
+
+        let poisoned = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _held = server.rate_limiter.lock();
+            panic!("poison the rate limiter");
+        }));
+        assert!(poisoned.is_err());
+        assert!(server.rate_limiter.is_poisoned());
+
+        assert!(server.ensure_rate_allowed("xint_costs").is_ok());
+        let err = server
+            .ensure_rate_allowed("xint_costs")
+            .expect_err("second call is over the limit");
+        assert!(err.contains("RATE_LIMITED"));
+        assert_eq!(
+            server.rate_limit_status(Some("xint_costs"))["enabled"],
+            true
+        );
+    }
+
+    #[tokio::test]
+    async fn config_deny_list_hides_and_blocks_tools() {
+        let settings: McpConfig =
+            toml::from_str("[tools]\ndeny = [\"xint_costs\"]\n").expect("parse config");
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        )
+        .with_settings(settings);
+        mark_initialized(&server);
+
+        let list = server
+            .handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#)
+            .await
+            .expect("list call")
+            .expect("response");
+        assert!(!list.contains("\"xint_costs\""));
+        assert!(list.contains("\"xint_search\""));
+
+        let call = server
+            .handle_message(
+                r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"xint_costs","arguments":{}}}"#,
+            )
+            .await
+            .expect("tool call")
+            .expect("response");
+        assert!(call.contains("TOOL_DISABLED"));
+    }
+
+    #[tokio::test]
+    async fn artifact_results_round_trip_through_resources_read() {
+        let dir = std::env::temp_dir().join(format!(
+            "xint-rs-artifacts-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        )
+        .with_allowed_write_dirs(vec![dir.clone()]);
+        mark_initialized(&server);
+
+        let call = server
+            .handle_message(
+                r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"xint_costs","arguments":{"as_artifact":true}}}"#,
+            )
+            .await
+            .expect("tool call")
+            .expect("response");
+        let parsed: serde_json::Value = serde_json::from_str(&call).expect("json");
+        let text = parsed["result"]["content"][0]["text"]
+            .as_str()
+            .expect("content text");
+        let summary: serde_json::Value = serde_json::from_str(text).expect("summary json");
+        let uri = summary["data"]["uri"].as_str().expect("artifact uri");
+        assert!(uri.starts_with("xint://artifact/xint_costs-"));
+
+        let read = server
+            .handle_message(&format!(
+                r#"{{"jsonrpc":"2.0","id":2,"method":"resources/read","params":{{"uri":"{uri}"}}}}"#
+            ))
+            .await
+            .expect("resources/read")
+            .expect("response");
+        assert!(read.contains("Cost summary"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn export_writes_the_last_result_inside_allowed_dir() {
+        let dir = std::env::temp_dir().join(format!(
+            "xint-rs-export-{}",
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        )
+        .with_allowed_write_dirs(vec![dir.clone()]);
+        mark_initialized(&server);
+        let call = |id: u32, name: &str, arguments: serde_json::Value| {
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "tools/call",
+                "params": { "name": name, "arguments": arguments }
+            })
+            .to_string()
+        };
+
+        let early = server
+            .handle_message(&call(
+                1,
+                "xint_export",
+                serde_json::json!({ "format": "json", "path": "out.json" }),
+            ))
+            .await
+            .expect("tool call")
+            .expect("response");
+        assert!(early.contains("No tool result to export yet"));
+
+        server
+            .handle_message(&call(2, "xint_costs", serde_json::json!({})))
+            .await
+            .expect("tool call")
+            .expect("response");
+        let exported = server
+            .handle_message(&call(
+                3,
+                "xint_export",
+                serde_json::json!({ "format": "csv", "path": "costs/today.csv" }),
+            ))
+            .await
+            .expect("tool call")
+            .expect("response");
+        assert!(exported.contains("xint_costs"));
+        let written = std::fs::read_to_string(dir.join("costs/today.csv")).expect("export file");
+        assert!(written.lines().count() >= 2);
+
+        let escape = server
+            .handle_message(&call(
+                4,
+                "xint_export",
+                serde_json::json!({ "format": "json", "path": "../escape.json" }),
+            ))
+            .await
+            .expect("tool call")
+            .expect("response");
+        assert!(escape.contains("PATH_DENIED"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn export_csv_flattens_records_by_key() {
+        let records = export_records(&serde_json::json!({
+            "tweets": [
+                { "id": "1", "text": "hi, there", "metrics": { "likes": 2 } },
+                { "id": "2", "extra": null }
+            ]
+        }));
+        let csv = export_csv(&records).expect("csv");
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines[0], "id,metrics,text,extra");
+        assert_eq!(lines[1], r#"1,"{""likes"":2}","hi, there","#);
+        assert_eq!(lines[2], "2,,,");
+    }
+
+    #[tokio::test]
+    async fn artifact_request_without_write_dir_is_path_denied() {
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        mark_initialized(&server);
+        let call = server
+            .handle_message(
+                r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"xint_costs","arguments":{"as_artifact":true}}}"#,
+            )
+            .await
+            .expect("tool call")
+            .expect("response");
+        assert!(call.contains("PATH_DENIED"));
+    }
+
+    #[tokio::test]
+    async fn core_search_tool_requires_bearer_token() {
+        let _guard = env_lock().lock().await;
+        let prev_bearer = save_env("X_BEARER_TOKEN");
+        env::remove_var("X_BEARER_TOKEN");
+
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+
+        let err = server
+            .execute_tool("xint_search", serde_json::json!({ "query": "ai agents" }))
+            .await
+            .expect_err("expected bearer token error");
+
+        assert!(err.contains("X_BEARER_TOKEN"));
+        restore_env("X_BEARER_TOKEN", prev_bearer);
+    }
+
+    #[tokio::test]
+    async fn bookmarks_without_oauth_report_oauth_required() {
+        let _guard = env_lock().lock().await;
+        let prev_client_id = save_env("X_CLIENT_ID");
+        env::remove_var("X_CLIENT_ID");
+
+        let server = MCPServer::new(
+            PolicyMode::Engagement,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        let err = server
+            .execute_tool("xint_bookmarks", serde_json::json!({ "limit": 5 }))
+            .await
+            .expect_err("expected oauth error");
+        let err: serde_json::Value = serde_json::from_str(&err).expect("structured error");
+        assert_eq!(err["code"], "OAUTH_REQUIRED");
+        assert!(err["instructions"]
+            .as_str()
+            .unwrap()
+            .contains("xint auth setup"));
+        restore_env("X_CLIENT_ID", prev_client_id);
+    }
+
+    #[tokio::test]
+    async fn analyze_tool_requires_xai_api_key() {
+        let _guard = env_lock().lock().await;
+        let prev_key = save_env("XAI_API_KEY");
+        env::remove_var("XAI_API_KEY");
+
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+
+        let err = server
+            .execute_tool(
+                "xint_analyze",
+                serde_json::json!({ "query": "summarize this" }),
+            )
+            .await
+            .expect_err("expected xai key error");
+
+        assert!(err.contains("XAI_API_KEY"));
+        restore_env("XAI_API_KEY", prev_key);
+    }
+
+    #[test]
+    fn empty_retrieval_results_are_flagged_not_errors() {
+        let content =
+            MCPServer::retrieval_content("Search completed.".to_string(), 0, serde_json::json!([]))
+                .expect("content");
+        assert_eq!(content.len(), 2);
+        assert_eq!(content[0].text, "No matching tweets found.");
+        let payload: serde_json::Value = serde_json::from_str(&content[1].text).expect("json");
+        assert_eq!(payload["type"], "success");
+        assert_eq!(payload["count"], 0);
+        assert_eq!(payload["no_results"], true);
+        assert_eq!(payload["data"], serde_json::json!([]));
+
+        let content = MCPServer::retrieval_content(
+            "Search completed.".to_string(),
+            2,
+            serde_json::json!([{}, {}]),
+        )
+        .expect("content");
+        assert_eq!(content.len(), 1);
+        let payload: serde_json::Value = serde_json::from_str(&content[0].text).expect("json");
+        assert_eq!(payload["count"], 2);
+        assert!(payload.get("no_results").is_none());
+    }
+
+    #[test]
+    fn thread_content_puts_the_root_first_and_reports_partial_pages() {
+        let tweet = |id: &str, minute: u32| Tweet {
+            id: id.to_string(),
+            conversation_id: "1".to_string(),
+            username: "alice".to_string(),
+            text: format!("tweet {id}"),
+            created_at: format!("2026-03-01T10:{minute:02}:00Z"),
+            ..Default::default()
+        };
+        let thread = ThreadPage {
+            // Search returns newest first; the root's timestamp is later
+            // than a reply's to check it still leads.
+            tweets: vec![tweet("3", 5), tweet("1", 9), tweet("2", 2)],
+            fetched_pages: 2,
+            next_cursor: Some("cursor-2".to_string()),
+            complete: false,
+            estimated_total: Some(3),
+        };
+        let content = MCPServer::thread_content(thread).expect("content");
+        assert_eq!(content.len(), 2, "outline then payload");
+        let payload: serde_json::Value = serde_json::from_str(&content[1].text).expect("json");
+        assert_eq!(
+            payload["message"],
+            "Partial thread: fetched 2 pages; pass cursor to continue."
+        );
+        let ids: Vec<_> = payload["data"]["tweets"]
+            .as_array()
+            .expect("tweets")
+            .iter()
+            .map(|t| t["id"].as_str().unwrap_or_default().to_string())
+            .collect();
+        assert_eq!(ids, ["1", "2", "3"]);
+        assert_eq!(payload["data"]["complete"], false);
+        assert_eq!(payload["data"]["fetched_pages"], 2);
+        assert_eq!(payload["data"]["next_cursor"], "cursor-2");
+
+        let done = ThreadPage {
+            tweets: vec![tweet("1", 0)],
+            fetched_pages: 1,
+            next_cursor: None,
+            complete: true,
+            estimated_total: Some(1),
+        };
+        let content = MCPServer::thread_content(done).expect("content");
+        let payload: serde_json::Value =
+            serde_json::from_str(&content.last().expect("payload").text).expect("json");
+        assert_eq!(payload["message"], "Thread lookup completed.");
+        assert_eq!(payload["data"]["complete"], true);
+        assert!(payload["data"]["next_cursor"].is_null());
+    }
+
+    #[test]
+    fn quotes_fall_back_on_403_and_cite_only_the_commentary() {
+        let forbidden = anyhow::anyhow!("X API 403: quote_tweets is not available on this tier");
+        let content = MCPServer::quotes_unavailable_content("42", &forbidden)
+            .expect("403 is handled")
+            .expect("content");
+        let payload: serde_json::Value = serde_json::from_str(&content[1].text).expect("json");
+        assert_eq!(payload["type"], "success");
+        assert_eq!(payload["no_results"], true);
+        assert_eq!(payload["data"]["quotes_available"], false);
+        assert_eq!(payload["data"]["tweet_id"], "42");
+        assert!(payload["data"]["reason"]
+            .as_str()
+            .is_some_and(|r| r.starts_with("X API 403")));
+
+        for other in ["X API 404: gone", "Rate limited. Resets in 60s"] {
+            let err = anyhow::anyhow!(other);
+            assert!(MCPServer::quotes_unavailable_content("42", &err).is_none());
+        }
+
+        let quote = Tweet {
+            id: "7".to_string(),
+            username: "alice".to_string(),
+            text: "Hard agree https://t.co/quoted".to_string(),
+            tweet_url: "https://x.com/alice/status/7".to_string(),
+            ..Default::default()
+        };
+        let content =
+            MCPServer::quotes_content("42", &[quote], Some("next".to_string())).expect("content");
+        let payload: serde_json::Value = serde_json::from_str(&content[0].text).expect("json");
+        assert_eq!(payload["count"], 1);
+        assert_eq!(payload["data"]["quotes_available"], true);
+        assert_eq!(payload["data"]["next_cursor"], "next");
+        assert_eq!(
+            payload["data"]["citations"][0],
+            serde_json::json!({
+                "tweet_id": "7",
+                "username": "alice",
+                "url": "https://x.com/alice/status/7",
+                "commentary": "Hard agree"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn numeric_args_outside_bounds_are_rejected() {
+        let _guard = env_lock().lock().await;
+        let prev_limit = save_env("XINT_MCP_RATE_LIMIT");
+        env::set_var("XINT_MCP_RATE_LIMIT", "1");
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        restore_env("XINT_MCP_RATE_LIMIT", prev_limit);
+        mark_initialized(&server);
+
+        for (mut args, message) in [
+            (
+                serde_json::json!({ "query": "ai", "limit": 0 }),
+                "limit must be between 1 and 200",
+            ),
+            (
+                serde_json::json!({ "query": "ai", "limit": 201 }),
+                "limit must be between 1 and 200",
+            ),
+            (
+                serde_json::json!({ "query": "ai", "limit": -5 }),
+                "limit must be between 1 and 200",
+            ),
+            (
+                serde_json::json!({ "query": "ai", "limit": 2.5 }),
+                "limit must be between 1 and 200",
+            ),
+            (
+                serde_json::json!({ "query": "ai", "pages": 11 }),
+                "pages must be between 1 and 10",
+            ),
+        ] {
+            let err = server
+                .ensure_numeric_args_valid("xint_search", &mut args)
+                .expect_err("expected invalid argument");
+            let payload: serde_json::Value = serde_json::from_str(&err).expect("json error");
+            assert_eq!(payload["code"], "INVALID_ARGUMENT");
+            assert_eq!(payload["message"], message);
+        }
+
+        let mut whole = serde_json::json!({ "query": "ai", "limit": 15.0 });
+        assert!(server
+            .ensure_numeric_args_valid("xint_search", &mut whole)
+            .is_ok());
+        assert_eq!(whole["limit"].as_u64(), Some(15));
+
+        // A rejected call must not spend the tool's only rate-limit token.
+        let bad = r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"xint_costs","arguments":{"limit":0}}}"#;
+        let response = server.handle_message(bad).await.expect("handled");
+        assert!(response.expect("response").contains("INVALID_ARGUMENT"));
+        let good = r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"xint_costs","arguments":{}}}"#;
+        let response = server.handle_message(good).await.expect("handled");
+        assert!(!response.expect("response").contains("RATE_LIMITED"));
+
+        let capped = server.with_max_tweets(50);
+        assert!(capped
+            .ensure_numeric_args_valid("xint_profile", &mut serde_json::json!({ "count": 50 }))
+            .is_ok());
+        assert!(capped
+            .ensure_numeric_args_valid("xint_profile", &mut serde_json::json!({ "count": 51 }))
+            .is_err());
+        assert!(capped
+            .ensure_numeric_args_valid(
+                "xint_profile",
+                &mut serde_json::json!({ "count": 1, "pages": 10 })
+            )
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn guarded_tool_results_report_their_cost() {
+        let _guard = env_lock().lock().await;
+        let prev_key = save_env("XAI_API_KEY");
+        env::remove_var("XAI_API_KEY");
+
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-call-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        mark_initialized(&server);
+        let call = |name: &str, arguments: serde_json::Value| {
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": { "name": name, "arguments": arguments }
+            })
+            .to_string()
+        };
+
+        let response = server
+            .handle_message(&call(
+                "xint_sentiment",
+                serde_json::json!({ "tweets": ["great launch"] }),
+            ))
+            .await
+            .expect("tool call")
+            .expect("response");
+        let parsed: serde_json::Value = serde_json::from_str(&response).expect("json");
+        let content = parsed["result"]["content"].as_array().expect("content");
+        let cost: serde_json::Value = serde_json::from_str(
+            content.last().expect("cost block")["text"]
+                .as_str()
+                .unwrap(),
+        )
+        .expect("cost json");
+        assert_eq!(cost["type"], "cost");
+        assert_eq!(cost["data"]["tool"], "xint_sentiment");
+        assert_eq!(cost["data"]["estimated_usd"], 0.002);
+        assert_eq!(cost["data"]["actual_usd"], 0.0);
+        assert!(cost["data"]["daily_remaining_usd"].is_number());
+
+        // Unguarded tools carry no cost block.
+        let response = server
+            .handle_message(&call("xint_costs", serde_json::json!({})))
+            .await
+            .expect("tool call")
+            .expect("response");
+        assert!(!response.contains(r#"\"type\":\"cost\""#));
+
+        assert_eq!(
+            costs::estimate("xint_search", &serde_json::json!({ "limit": 40 })),
+            0.2
+        );
+        assert_eq!(costs::estimate("xint_costs", &serde_json::json!({})), 0.0);
+        restore_env("XAI_API_KEY", prev_key);
+    }
+
+    #[test]
+    fn forecast_projects_runway_from_recent_spend() {
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-forecast-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+
+        let idle = costs::forecast(&costs_path, &costs::budget_status(true, 0.0, 1.0, 0.8));
+        assert_eq!(idle.usd_per_hour, 0.0);
+        assert!(idle.hours_until_limit.is_none());
+        assert!(!idle.limit_before_reset);
+
+        costs::track_usd_cost(&costs_path, "grok_analyze", "/v1/chat/completions", 0.25);
+        let budget = costs::budget_status(true, 0.25, 1.0, 0.8);
+        let busy = costs::forecast(&costs_path, &budget);
+        // Measured over the one-hour floor: $0.25/h leaves three hours.
+        assert_eq!(busy.window_hours, 1.0);
+        assert_eq!(busy.usd_per_hour, 0.25);
+        assert_eq!(busy.hours_until_limit, Some(3.0));
+        assert_eq!(
+            busy.limit_before_reset,
+            busy.hours_until_reset > 3.0,
+            "{busy:?}"
+        );
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[tokio::test]
+    async fn cost_ledger_breaks_spend_down_by_tool() {
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-breakdown-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            costs_path.clone(),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        let budget = costs::check_budget(&costs_path);
+        costs::record(&costs_path, "xint_search", None, 0.1, 0.1, &[], &budget);
+        costs::record(&costs_path, "xint_search", None, 0.05, 0.05, &[], &budget);
+        // Priced from tokens: 0.5M in at $0.10/M plus 0.5M out at $0.40/M.
+        let cost = costs::record(
+            &costs_path,
+            "xint_report",
+            None,
+            0.2,
+            0.0,
+            &[crate::models::TokenUsage {
+                model: "grok-3-mini".to_string(),
+                input_tokens: 500_000,
+                output_tokens: 500_000,
+            }],
+            &budget,
+        );
+        assert_eq!(cost.model.as_deref(), Some("grok-3-mini"));
+        assert_eq!(cost.rate.map(|r| r.input_per_million), Some(0.1));
+        assert_eq!(cost.actual_usd, 0.25);
+        assert_eq!(cost.output_tokens, Some(500_000));
+
+        let by_tool = costs::breakdown(&costs_path, "today");
+        assert_eq!(by_tool.len(), 2);
+        assert!((by_tool["xint_search"] - 0.15).abs() < 1e-9);
+        assert_eq!(by_tool["xint_report"], 0.25);
+
+        let result = server
+            .execute_tool("xint_costs", serde_json::json!({ "period": "week" }))
+            .await
+            .expect("costs");
+        let payload: serde_json::Value = serde_json::from_str(&result[0].text).expect("json");
+        assert_eq!(payload["data"]["by_tool"]["xint_report"], 0.25);
+
+        let dir = std::env::temp_dir().join("xint-rs-test-costs-export");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("dir");
+        let server = server.with_allowed_write_dirs(vec![dir.clone()]);
+        let result = server
+            .execute_tool(
+                "xint_costs",
+                serde_json::json!({ "export": "csv", "path": "ledger.csv" }),
+            )
+            .await
+            .expect("export");
+        let payload: serde_json::Value = serde_json::from_str(&result[0].text).expect("json");
+        assert_eq!(payload["data"]["export"]["rows"], 3);
+        let csv = std::fs::read_to_string(dir.join("ledger.csv")).expect("csv");
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("timestamp,tool,model,tokens,usd,unpriced")
+        );
+        assert!(lines
+            .last()
+            .is_some_and(|row| row.ends_with(",xint_report,grok-3-mini,1000000,0.25,")));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        costs::reset_period(&costs_path, "today", true);
+        assert!(costs::breakdown(&costs_path, "all").is_empty());
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[test]
+    fn ledger_csv_export_quotes_fields_and_counts_period_rows() {
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-export-csv-costs.json");
+        let out = std::env::temp_dir()
+            .join("xint-rs-test-export-csv")
+            .join("ledger.csv");
+        let _ = std::fs::remove_file(&costs_path);
+        let _ = std::fs::remove_file(&out);
+        let budget = costs::check_budget(&costs_path);
+        costs::record(&costs_path, "xint_search", None, 0.1, 0.1, &[], &budget);
+        costs::record(
+            &costs_path,
+            "xint_report",
+            Some("grok \"beta\", eu"),
+            0.0,
+            0.02,
+            &[],
+            &budget,
+        );
+        costs::record(&costs_path, "xint_old", None, 0.0, 0.5, &[], &budget);
+        // Age the last record out of the export period.
+        let mut data: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&costs_path).expect("ledger"))
+                .expect("json");
+        data["calls"][2]["timestamp"] = serde_json::json!("2020-01-01T00:00:00+00:00");
+        std::fs::write(&costs_path, data.to_string()).expect("write ledger");
+
+        let rows = costs::export_csv(&costs_path, "today", &out).expect("export");
+        assert_eq!(rows, 2);
+        let csv = std::fs::read_to_string(&out).expect("csv");
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "timestamp,tool,model,tokens,usd,unpriced");
+        assert!(lines[1].ends_with(",xint_search,,,0.1,"), "{}", lines[1]);
+        assert!(
+            lines[2].ends_with(r#",xint_report,"grok ""beta"", eu",,0.02,"#),
+            "{}",
+            lines[2]
+        );
+
+        assert_eq!(
+            costs::export_csv(&costs_path, "all", &out).expect("export all"),
+            3
+        );
+        let _ = std::fs::remove_file(&costs_path);
+        let _ = std::fs::remove_file(&out);
+    }
+
+    #[test]
+    fn unpriced_completions_are_marked_not_charged_a_fallback_rate() {
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-unpriced-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+        let usage = [
+            crate::models::TokenUsage {
+                model: "grok-3-mini".to_string(),
+                input_tokens: 500_000,
+                output_tokens: 500_000,
+            },
+            crate::models::TokenUsage {
+                model: "grok-99-unpriced".to_string(),
+                input_tokens: 1_000_000,
+                output_tokens: 1_000_000,
+            },
+        ];
+        let budget = costs::check_budget(&costs_path);
+        let cost = costs::record(&costs_path, "xint_report", None, 0.0, 0.01, &usage, &budget);
+        // Only the fetch and the priced grok-3-mini completion are counted.
+        assert_eq!(cost.actual_usd, 0.26);
+        assert_eq!(cost.unpriced_models, ["grok-99-unpriced"]);
+
+        let entry = costs::track_token_cost(&costs_path, "grok_report", &usage[1]);
+        assert_eq!(entry.cost_usd, 0.0);
+
+        let data: crate::models::CostData =
+            serde_json::from_str(&std::fs::read_to_string(&costs_path).expect("ledger"))
+                .expect("json");
+        assert_eq!(data.calls[0].unpriced_models, ["grok-99-unpriced"]);
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[tokio::test]
+    async fn budget_warning_block_appears_past_threshold() {
+        let _guard = env_lock().lock().await;
+        let prev_key = save_env("XAI_API_KEY");
+        let prev_threshold = save_env("XINT_MCP_BUDGET_WARN_THRESHOLD");
+        env::remove_var("XAI_API_KEY");
+        env::remove_var("XINT_MCP_BUDGET_WARN_THRESHOLD");
+
+        let mut settings = McpConfig::default();
+        settings.budget.warn_threshold = Some(0.0);
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            true,
+            PathBuf::from("/tmp/xint-rs-test-warn-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        )
+        .with_settings(settings);
+        mark_initialized(&server);
+        let response = server
+            .handle_message(
+                &serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "method": "tools/call",
+                    "params": { "name": "xint_sentiment", "arguments": { "tweets": ["fine"] } }
+                })
+                .to_string(),
+            )
+            .await
+            .expect("tool call")
+            .expect("response");
+        assert!(response.contains("budget_warning"));
+        assert!(response.contains("calls are denied at 100%"));
+
+        let near = costs::budget_status(true, 0.85, 1.0, 0.8);
+        assert!(near.allowed && near.warning);
+        assert_eq!(near.percent_used, 85.0);
+        let below = costs::budget_status(true, 0.5, 1.0, 0.8);
+        assert!(below.allowed && !below.warning);
+        let over = costs::budget_status(true, 1.0, 1.0, 0.8);
+        assert!(!over.allowed && over.warning);
+
+        restore_env("XAI_API_KEY", prev_key);
+        restore_env("XINT_MCP_BUDGET_WARN_THRESHOLD", prev_threshold);
+    }
+
+    #[tokio::test]
+    async fn weekly_budget_denial_names_the_window() {
+        let _guard = env_lock().lock().await;
+        let prev_weekly = save_env("XINT_MCP_WEEKLY_BUDGET_USD");
+        let prev_monthly = save_env("XINT_MCP_MONTHLY_BUDGET_USD");
+        env::remove_var("XINT_MCP_WEEKLY_BUDGET_USD");
+        env::remove_var("XINT_MCP_MONTHLY_BUDGET_USD");
+
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-window-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+        costs::track_usd_cost(&costs_path, "grok_test", "/v1/chat/completions", 0.3);
+
+        let mut settings = McpConfig::default();
+        settings.budget.daily_limit_usd = Some(1.0);
+        settings.budget.weekly_limit_usd = Some(0.25);
+        settings.budget.monthly_limit_usd = Some(5.0);
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            true,
+            costs_path.clone(),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        )
+        .with_settings(settings);
+
+        let windows = server.budget_windows();
+        let names: Vec<_> = windows.iter().map(|w| w.window.as_str()).collect();
+        assert_eq!(names, ["daily", "weekly", "monthly"]);
+        let err = server
+            .ensure_budget_allowed("xint_search", None)
+            .expect_err("weekly cap reached");
+        let payload: serde_json::Value = serde_json::from_str(&err).expect("json");
+        assert_eq!(payload["window"], "weekly");
+        assert_eq!(payload["limit_usd"], 0.25);
+        assert_eq!(payload["spent_usd"], 0.3);
+        assert!(payload["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Weekly budget exceeded"));
+
+        env::set_var("XINT_MCP_WEEKLY_BUDGET_USD", "2");
+        assert!(server.ensure_budget_allowed("xint_search", None).is_ok());
+
+        restore_env("XINT_MCP_WEEKLY_BUDGET_USD", prev_weekly);
+        restore_env("XINT_MCP_MONTHLY_BUDGET_USD", prev_monthly);
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[test]
+    fn every_guarded_tool_has_a_budget_category() {
+        for tool in MCPServer::get_tools() {
+            assert_eq!(
+                MCPServer::tool_budget_guarded(&tool.name),
+                costs::tool_category(&tool.name).is_some(),
+                "{}",
+                tool.name
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_url_is_guarded_like_url_expand() {
+        for tool in ["xint_resolve_url", "xint_url_expand"] {
+            assert!(MCPServer::tool_budget_guarded(tool), "{tool}");
+            assert_eq!(costs::tool_category(tool), Some("fetch"), "{tool}");
+        }
+        let args = serde_json::json!({ "url": "https://t.co/abc" });
+        assert_eq!(costs::estimate("xint_resolve_url", &args), 0.0);
+        assert_eq!(costs::estimate_range("xint_resolve_url", &args), (0.0, 0.0));
+    }
+
+    #[test]
+    fn trends_for_counts_its_grok_ranking_as_ai_spend() {
+        assert_eq!(costs::tool_category("xint_trends_for"), Some("ai"));
+        assert_eq!(
+            costs::tool_model("xint_trends_for", &serde_json::Value::Null).as_deref(),
+            Some("grok-3-mini")
+        );
+        // The trends lookup plus one Grok completion allowance.
+        assert_eq!(
+            costs::estimate("xint_trends_for", &serde_json::json!({ "topic": "rust" })),
+            0.102
+        );
+    }
+
+    #[tokio::test]
+    async fn category_sub_limit_denies_only_that_category() {
+        let _guard = env_lock().lock().await;
+        let prev_weekly = save_env("XINT_MCP_WEEKLY_BUDGET_USD");
+        let prev_monthly = save_env("XINT_MCP_MONTHLY_BUDGET_USD");
+        env::remove_var("XINT_MCP_WEEKLY_BUDGET_USD");
+        env::remove_var("XINT_MCP_MONTHLY_BUDGET_USD");
+
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-category-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+        let mut settings = McpConfig::default();
+        settings.budget.daily_limit_usd = Some(5.0);
+        settings.budget.categories.ai = Some(0.1);
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            true,
+            costs_path.clone(),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        )
+        .with_settings(settings);
+
+        let budget = server.daily_budget();
+        costs::record(&costs_path, "xint_report", None, 0.1, 0.12, &[], &budget);
+        costs::record(&costs_path, "xint_search", None, 0.5, 0.5, &[], &budget);
+
+        let err = server
+            .ensure_budget_allowed("xint_sentiment", None)
+            .expect_err("ai cap reached");
+        let payload: serde_json::Value = serde_json::from_str(&err).expect("json");
+        assert_eq!(payload["scope"], "category");
+        assert_eq!(payload["category"], "ai");
+        assert_eq!(payload["spent_usd"], 0.12);
+        assert!(server.ensure_budget_allowed("xint_search", None).is_ok());
+
+        restore_env("XINT_MCP_WEEKLY_BUDGET_USD", prev_weekly);
+        restore_env("XINT_MCP_MONTHLY_BUDGET_USD", prev_monthly);
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[tokio::test]
+    async fn budget_status_reports_windows_without_spending() {
+        let _guard = env_lock().lock().await;
+        let prev_weekly = save_env("XINT_MCP_WEEKLY_BUDGET_USD");
+        let prev_monthly = save_env("XINT_MCP_MONTHLY_BUDGET_USD");
+        env::remove_var("XINT_MCP_WEEKLY_BUDGET_USD");
+        env::remove_var("XINT_MCP_MONTHLY_BUDGET_USD");
+
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-budget-status-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+        costs::track_usd_cost(&costs_path, "grok_test", "/v1/chat/completions", 0.9);
+        let mut settings = McpConfig::default();
+        settings.budget.daily_limit_usd = Some(1.0);
+        settings.budget.weekly_limit_usd = Some(5.0);
+        settings.budget.categories.ai = Some(0.5);
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            true,
+            costs_path.clone(),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        )
+        .with_settings(settings);
+        assert!(!MCPServer::tool_budget_guarded("xint_budget_status"));
+
+        let result = server
+            .execute_tool("xint_budget_status", serde_json::json!({}))
+            .await
+            .expect("status");
+        let payload: serde_json::Value = serde_json::from_str(&result[0].text).expect("json");
+        let data = &payload["data"];
+        assert_eq!(data["spent"], 0.9);
+        assert_eq!(data["remaining"], 0.1);
+        assert_eq!(data["percent_used"], 90.0);
+        assert_eq!(data["warn"], true);
+        assert_eq!(data["allowed"], true);
+        let windows: Vec<_> = data["windows"]
+            .as_array()
+            .expect("windows")
+            .iter()
+            .map(|w| w["window"].as_str().unwrap_or_default())
+            .collect();
+        assert_eq!(windows, ["daily", "weekly"]);
+        assert_eq!(data["categories"][0]["category"], "ai");
+        assert_eq!(costs::check_budget(&costs_path).spent, 0.9);
+
+        restore_env("XINT_MCP_WEEKLY_BUDGET_USD", prev_weekly);
+        restore_env("XINT_MCP_MONTHLY_BUDGET_USD", prev_monthly);
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[test]
+    fn concurrent_reservations_do_not_overshoot_the_daily_limit() {
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-reservation-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+        costs::track_usd_cost(&costs_path, "grok_test", "/v1/chat/completions", 0.8);
+
+        let barrier = std::sync::Arc::new(std::sync::Barrier::new(10));
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                let barrier = barrier.clone();
+                let costs_path = costs_path.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    let budget = costs::budget_status(true, 0.0, 1.0, 0.8);
+                    let Ok(Some(reservation)) = costs::reserve(&costs_path, 0.05, &budget) else {
+                        return false;
+                    };
+                    std::thread::sleep(std::time::Duration::from_millis(20));
+                    costs::track_usd_cost(&costs_path, "grok_test", "/v1/chat/completions", 0.05);
+                    reservation.settle();
+                    true
+                })
+            })
+            .collect();
+        let admitted = handles
+            .into_iter()
+            .map(|h| h.join().expect("thread"))
+            .filter(|admitted| *admitted)
+            .count();
+
+        let spent = costs::check_budget(&costs_path).spent;
+        assert!(admitted >= 1);
+        assert!(spent <= 1.0 + 1e-9, "spent {spent} after {admitted} calls");
+        let exhausted = costs::budget_status(true, 0.0, spent, 0.8);
+        assert!(costs::reserve(&costs_path, 0.05, &exhausted).is_err());
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[test]
+    fn estimate_over_the_remaining_budget_is_admitted_with_a_capped_hold() {
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-reservation-overshoot-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+        costs::track_usd_cost(&costs_path, "grok_test", "/v1/chat/completions", 0.9);
+        let budget = costs::budget_status(true, 0.0, 1.0, 0.8);
+
+        // $0.50 does not fit in the $0.10 left, but the call is let through.
+        let reservation = costs::reserve(&costs_path, 0.5, &budget)
+            .expect("admitted")
+            .expect("budget enabled");
+        // The hold takes the whole remainder, so nothing else starts meanwhile.
+        let denied = costs::reserve(&costs_path, 0.01, &budget).expect_err("nothing left");
+        assert!((denied.reserved - 0.1).abs() < 1e-9, "{denied:?}");
+
+        // Once charged, the overshoot keeps later calls out for the day.
+        costs::track_usd_cost(&costs_path, "grok_test", "/v1/chat/completions", 0.5);
+        reservation.settle();
+        let spent = costs::check_budget(&costs_path).spent;
+        assert!((spent - 1.4).abs() < 1e-9, "spent {spent}");
+        assert!(costs::reserve(&costs_path, 0.0, &budget).is_err());
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[tokio::test]
+    async fn default_report_is_not_refused_on_an_unspent_default_budget() {
+        let _guard = env_lock().lock().await;
+        let prev_bearer = save_env("X_BEARER_TOKEN");
+        let prev_xai = save_env("XAI_API_KEY");
+        env::remove_var("X_BEARER_TOKEN");
+        env::remove_var("XAI_API_KEY");
+        let costs_path = std::env::temp_dir().join("xint-rs-test-report-reservation-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+
+        // Worst case for the defaults (200 reads plus a completion) is over
+        // the $1.00 default limit, yet nothing has been spent.
+        let args = serde_json::json!({ "topic": "rust" });
+        assert!(costs::estimate("xint_report", &args) > 1.0);
+
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            true,
+            costs_path.clone(),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        mark_initialized(&server);
+        let call = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": { "name": "xint_report", "arguments": args }
+        });
+        let response = server
+            .handle_message(&call.to_string())
+            .await
+            .expect("handled")
+            .expect("response");
+        assert!(!response.contains("BUDGET_DENIED"), "{response}");
+        // Past the budget gate, the call fails only on missing credentials.
+        assert!(response.contains("XAI_API_KEY not found"), "{response}");
+
+        let disabled = costs::budget_status(false, 5.0, 1.0, 0.8);
+        assert!(costs::reserve(&costs_path, 2.0, &disabled)
+            .expect("disabled budget")
+            .is_none());
+
+        restore_env("X_BEARER_TOKEN", prev_bearer);
+        restore_env("XAI_API_KEY", prev_xai);
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[test]
+    fn exhausted_budget_surfaces_as_a_typed_error() {
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-exit-code-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+        assert!(costs::ensure_within_budget(&costs_path).is_ok());
+
+        costs::track_usd_cost(&costs_path, "grok_test", "/v1/chat/completions", 1.5);
+        let err: anyhow::Error = costs::ensure_within_budget(&costs_path)
+            .expect_err("over budget")
+            .into();
+        let exceeded = err
+            .downcast_ref::<costs::BudgetExceeded>()
+            .expect("budget error");
+        assert_eq!(exceeded.spent, 1.5);
+        assert_eq!(err.to_string(), "Daily budget exceeded ($1.50/$1.00)");
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[tokio::test]
+    async fn budget_override_raises_the_daily_limit_in_memory_only() {
+        let _guard = env_lock().lock().await;
+        let prev_weekly = save_env("XINT_MCP_WEEKLY_BUDGET_USD");
+        let prev_monthly = save_env("XINT_MCP_MONTHLY_BUDGET_USD");
+        env::remove_var("XINT_MCP_WEEKLY_BUDGET_USD");
+        env::remove_var("XINT_MCP_MONTHLY_BUDGET_USD");
+
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-override-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+        costs::track_usd_cost(&costs_path, "grok_test", "/v1/chat/completions", 1.2);
+        let server = || {
+            MCPServer::new(
+                PolicyMode::ReadOnly,
+                true,
+                costs_path.clone(),
+                PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+            )
+        };
+
+        assert!(server().ensure_budget_allowed("xint_search", None).is_err());
+        let raised = server().with_budget_override(Some(3.0));
+        assert!(raised.ensure_budget_allowed("xint_search", None).is_ok());
+        assert_eq!(raised.daily_budget().limit, 3.0);
+        // Lower than the configured limit: ignored.
+        assert_eq!(
+            server()
+                .with_budget_override(Some(0.5))
+                .daily_budget()
+                .limit,
+            1.0
+        );
+        assert_eq!(costs::check_budget(&costs_path).limit, 1.0);
+
+        restore_env("XINT_MCP_WEEKLY_BUDGET_USD", prev_weekly);
+        restore_env("XINT_MCP_MONTHLY_BUDGET_USD", prev_monthly);
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[test]
+    fn package_publish_requires_full_policy() {
+        let server = |mode| {
+            MCPServer::new(
+                mode,
+                false,
+                PathBuf::from("/tmp/xint-rs-test-costs.json"),
+                PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+            )
+        };
+        let err = server(PolicyMode::Moderation)
+            .ensure_tool_allowed("xint_package_publish")
+            .expect_err("needs full");
+        let payload: serde_json::Value = serde_json::from_str(&err).expect("json");
+        assert_eq!(payload["required_mode"], "full");
+        assert!(server(PolicyMode::Full)
+            .ensure_tool_allowed("xint_package_publish")
+            .is_ok());
+        assert!(server(PolicyMode::Full)
+            .ensure_tool_allowed("xint_bookmarks")
+            .is_ok());
+        assert!(server(PolicyMode::Engagement)
+            .ensure_tool_allowed("xint_budget_reset")
+            .is_ok());
+        assert_eq!(
+            <PolicyMode as clap::ValueEnum>::from_str("full", false),
+            Ok(PolicyMode::Full)
+        );
+    }
+
+    #[test]
+    fn policy_overrides_replace_built_in_requirements() {
+        let settings: McpConfig = toml::from_str(
+            "[policy.tools]\nxint_report = \"engagement\"\nxint_bookmarks = \"read_only\"\nxint_nope = \"full\"\n",
+        )
+        .expect("parse config");
+        let server = |mode| {
+            MCPServer::new(
+                mode,
+                false,
+                PathBuf::from("/tmp/xint-rs-test-costs.json"),
+                PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+            )
+            .with_settings(settings.clone())
+        };
+
+        let err = server(PolicyMode::ReadOnly)
+            .ensure_tool_allowed("xint_report")
+            .expect_err("raised to engagement");
+        assert!(err.contains("POLICY_DENIED"));
+        assert!(server(PolicyMode::Engagement)
+            .ensure_tool_allowed("xint_report")
+            .is_ok());
+        assert!(server(PolicyMode::ReadOnly)
+            .ensure_tool_allowed("xint_bookmarks")
+            .is_ok());
+        // Unlisted tools keep their defaults.
+        assert!(server(PolicyMode::ReadOnly)
+            .ensure_tool_allowed("xint_diff")
+            .is_err());
+    }
+
+    #[test]
+    fn policy_decisions_are_audited_without_blocking_calls() {
+        let audit_path = std::env::temp_dir().join("xint-rs-test-policy-audit.jsonl");
+        let _ = std::fs::remove_file(&audit_path);
+        let settings: McpConfig =
+            toml::from_str("[tools]\ndeny = [\"xint_costs\"]\n").expect("parse config");
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        )
+        .with_settings(settings)
+        .with_policy_audit_log(audit_path.clone());
+
+        assert!(server.ensure_tool_allowed("xint_search").is_ok());
+        assert!(server.ensure_tool_allowed("xint_bookmarks").is_err());
+        assert!(server.ensure_tool_allowed("xint_costs").is_err());
+
+        let entries: Vec<serde_json::Value> = std::fs::read_to_string(&audit_path)
+            .expect("audit log")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("json line"))
+            .collect();
+        let decisions: Vec<_> = entries
+            .iter()
+            .map(|e| (e["tool"].as_str().unwrap(), e["decision"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            decisions,
+            [
+                ("xint_search", "allowed"),
+                ("xint_bookmarks", "denied"),
+                ("xint_costs", "disabled")
+            ]
+        );
+        assert_eq!(entries[1]["policy_mode"], "read_only");
+        assert_eq!(entries[1]["required_mode"], "engagement");
+        assert!(entries[0]["timestamp"].is_string());
+
+        // An unwritable log (here, a directory) never blocks the call.
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        )
+        .with_policy_audit_log(std::env::temp_dir());
+        assert!(server.ensure_tool_allowed("xint_search").is_ok());
+        let _ = std::fs::remove_file(&audit_path);
+    }
+
+    #[tokio::test]
+    async fn concurrent_calls_are_charged_only_their_own_fetches() {
+        let costs_path = std::env::temp_dir().join("xint-rs-test-call-fetch-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+
+        let call = |reads: u64, tokens_model: &'static str| {
+            let costs_path = costs_path.clone();
+            let fetch_usd = std::sync::Arc::new(std::sync::Mutex::new(0.0));
+            let scoped = fetch_usd.clone();
+            async move {
+                costs::CALL_FETCH_USD
+                    .scope(scoped, async {
+                        for _ in 0..3 {
+                            costs::track_cost(
+                                &costs_path,
+                                "search",
+                                "/2/tweets/search/recent",
+                                reads,
+                            );
+                            costs::track_token_cost(
+                                &costs_path,
+                                "grok_analyze",
+                                &crate::models::TokenUsage {
+                                    model: tokens_model.to_string(),
+                                    input_tokens: 1_000,
+                                    output_tokens: 1_000,
+                                },
+                            );
+                            tokio::task::yield_now().await;
+                        }
+                    })
+                    .await;
+                *lock_state(&fetch_usd)
+            }
+        };
+        let (small, large) = tokio::join!(call(1, "grok-3-mini"), call(100, "grok-3"));
+
+        let (per_tweet, per_call) = costs::cost_rate("search");
+        let expected = |reads: u64| 3.0 * (per_call + per_tweet * reads as f64);
+        assert!((small - expected(1)).abs() < 1e-9);
+        assert!((large - expected(100)).abs() < 1e-9);
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[tokio::test]
+    async fn estimate_tool_projects_a_range_without_spending() {
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-estimate-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            true,
+            costs_path.clone(),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        assert!(server.ensure_budget_allowed("xint_estimate", None).is_ok());
+
+        let result = server
+            .execute_tool(
+                "xint_estimate",
+                serde_json::json!({
+                    "name": "xint_report",
+                    "arguments": { "topic": "rust", "sentiment": true }
+                }),
+            )
+            .await
+            .expect("estimate");
+        let payload: serde_json::Value = serde_json::from_str(&result[0].text).expect("json");
+        let data = &payload["data"];
+        assert_eq!(data["estimate_usd"], 1.004);
+        assert_eq!(data["low_usd"], 0.001);
+        assert_eq!(data["high_usd"], 1.02);
+        assert_eq!(data["budget_guarded"], true);
+        assert_eq!(costs::check_budget(&costs_path).spent, 0.0);
+
+        let err = server
+            .execute_tool("xint_estimate", serde_json::json!({ "name": "xint_nope" }))
+            .await
+            .expect_err("unknown tool");
+        assert!(err.contains("INVALID_ARGUMENT"));
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[tokio::test]
+    async fn budget_reset_previews_then_clears_the_ledger() {
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-reset-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
+        costs::track_cost(&costs_path, "search", "/2/tweets/search/recent", 20);
+
+        let server = MCPServer::new(
+            PolicyMode::Engagement,
+            false,
+            costs_path.clone(),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        let preview = server
+            .execute_tool("xint_budget_reset", serde_json::json!({}))
+            .await
+            .expect("preview");
+        let payload: serde_json::Value = serde_json::from_str(&preview[0].text).expect("json");
+        assert_eq!(payload["data"]["applied"], false);
+        assert_eq!(costs::check_budget(&costs_path).spent, 0.1);
+
+        let cleared = server
+            .execute_tool("xint_budget_reset", serde_json::json!({ "confirm": true }))
+            .await
+            .expect("reset");
+        let payload: serde_json::Value = serde_json::from_str(&cleared[0].text).expect("json");
+        assert_eq!(payload["data"]["cleared"]["total_cost"], 0.1);
+        assert_eq!(costs::check_budget(&costs_path).spent, 0.0);
+
+        let read_only = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            costs_path.clone(),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        assert!(read_only
+            .ensure_tool_allowed("xint_budget_reset")
+            .expect_err("engagement only")
+            .contains("POLICY_DENIED"));
+        let _ = std::fs::remove_file(&costs_path);
+    }
+
+    #[tokio::test]
+    async fn session_budget_is_tracked_per_session() {
+        let _guard = env_lock().lock().await;
+        let prev_limit = save_env("XINT_MCP_SESSION_BUDGET_USD");
+        env::remove_var("XINT_MCP_SESSION_BUDGET_USD");
+
+        let mut settings = McpConfig::default();
+        settings.budget.session_limit_usd = Some(0.5);
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            true,
+            PathBuf::from("/tmp/xint-rs-test-session-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        )
+        .with_settings(settings);
+
+        let heavy = server.open_session();
+        let light = server.open_session();
+        assert_ne!(heavy, light);
+        server.record_session_spend(&heavy, 0.6);
+
+        let err = server
+            .ensure_budget_allowed("xint_search", Some(&heavy))
+            .expect_err("session over budget");
+        assert!(err.contains("\"scope\":\"session\""));
+        assert!(err.contains(&heavy));
+        assert!(server
+            .ensure_budget_allowed("xint_search", Some(&light))
+            .is_ok());
+        assert!(server.ensure_budget_allowed("xint_search", None).is_ok());
+        // Unguarded tools never consult the session budget.
+        assert!(server
+            .ensure_budget_allowed("xint_costs", Some(&heavy))
+            .is_ok());
+
+        server.close_session(&heavy);
+        assert!(server
+            .ensure_budget_allowed("xint_search", Some(&heavy))
+            .is_ok());
+        restore_env("XINT_MCP_SESSION_BUDGET_USD", prev_limit);
+    }
+
+    #[tokio::test]
+    async fn oversized_stdin_line_is_rejected_and_reader_resyncs() {
+        let oversized = tokio::io::repeat(b'a').take(20 * 1024 * 1024);
+        let rest: &[u8] = b"\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"tools/list\"}\r\n";
+        let mut reader = BufReader::new(oversized.chain(rest));
+
+        // A buffer bounded by the limit, not by the 20 MB line.
+        let first = read_bounded_line(&mut reader, DEFAULT_MAX_LINE_BYTES)
+            .await
+            .expect("read");
+        assert!(matches!(first, Some(StdinLine::TooLong)));
+
+        let Some(StdinLine::Line(next)) = read_bounded_line(&mut reader, DEFAULT_MAX_LINE_BYTES)
+            .await
+            .expect("read")
+        else {
+            panic!("expected the following message to be read intact");
+        };
+        assert_eq!(next, r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#);
+        assert!(read_bounded_line(&mut reader, DEFAULT_MAX_LINE_BYTES)
+            .await
+            .expect("read")
+            .is_none());
+    }
+
+    #[test]
+    fn activity_summary_reports_cadence_and_averages() {
+        let tweet = |day: u32, likes: u64, tag: &str| Tweet {
+            created_at: format!("2026-02-{day:02}T12:00:00Z"),
+            metrics: crate::models::TweetMetrics {
+                likes,
+                ..Default::default()
+            },
+            hashtags: vec![tag.to_string()],
+            ..Default::default()
+        };
+        let summary = activity_summary(&[
+            tweet(1, 10, "Rust"),
+            tweet(3, 20, "rust"),
+            tweet(5, 30, "ai"),
+        ]);
+        assert_eq!(summary["tweets_analyzed"], 3);
+        assert_eq!(summary["span_days"], 4.0);
+        assert_eq!(summary["posts_per_day"], 0.8);
+        assert_eq!(summary["avg_likes"], 20.0);
+        assert_eq!(summary["top_hashtags"][0]["tag"], "rust");
+        assert_eq!(summary["top_hashtags"][0]["count"], 2);
+        assert_eq!(activity_summary(&[])["posts_per_day"], 0.0);
+    }
+
+    #[test]
+    fn engagement_rate_normalizes_by_followers_and_reports_trend() {
+        let tweet = |day: u32, likes: u64, retweets: u64, replies: u64| Tweet {
+            created_at: format!("2026-03-{day:02}T09:00:00Z"),
+            metrics: crate::models::TweetMetrics {
+                likes,
+                retweets,
+                replies,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let tweets = [
+            tweet(4, 30, 5, 5),
+            tweet(1, 8, 1, 1),
+            tweet(3, 25, 10, 5),
+            tweet(2, 10, 0, 0),
+        ];
+        let rate = engagement_rate(1_000, &tweets);
+        assert_eq!(rate["posts"], 4);
+        assert_eq!(rate["avg_engagement_per_post"], 25.0);
+        assert_eq!(rate["engagement_rate_pct"], 2.5);
+        assert_eq!(rate["older_half_rate_pct"], 1.0);
+        assert_eq!(rate["newer_half_rate_pct"], 4.0);
+        assert_eq!(rate["trend"], "rising");
+
+        let empty = engagement_rate(0, &[]);
+        assert_eq!(empty["engagement_rate_pct"], 0.0);
+        assert_eq!(empty["trend"], "flat");
+    }
+
+    #[test]
+    fn co_occurring_accounts_rank_retweets_and_mentions() {
+        let tweet = |text: &str, mentions: &[&str]| Tweet {
+            text: text.to_string(),
+            mentions: mentions.iter().map(|m| m.to_string()).collect(),
+            ..Default::default()
+        };
+        let accounts = co_occurring_accounts(
+            "Seed",
+            &[
+                tweet("RT @alice: shipped", &["alice"]),
+                tweet("thanks @Alice and @bob", &["Alice", "bob", "alice"]),
+                tweet("talking to myself @seed", &["seed"]),
+                tweet("no mentions", &[]),
+            ],
+            5,
+        );
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0]["username"], "alice");
+        assert_eq!(accounts[0]["retweets"], 1);
+        assert_eq!(accounts[0]["mentions"], 1);
+        assert_eq!(accounts[0]["score"], 0.5);
+        assert_eq!(accounts[1]["username"], "bob");
+        assert_eq!(accounts[1]["score"], 0.25);
+    }
+
+    #[test]
+    fn quote_commentary_drops_the_quoted_link() {
+        assert_eq!(
+            quote_commentary("This aged well https://t.co/abc123"),
+            "This aged well"
+        );
+        assert_eq!(
+            quote_commentary("See https://t.co/x1 and more https://t.co/x2 "),
+            "See https://t.co/x1 and more"
+        );
+        assert_eq!(quote_commentary("https://t.co/only"), "");
+    }
+
+    #[test]
+    fn profile_usernames_are_normalized_or_rejected() {
+        assert_eq!(
+            MCPServer::normalize_username("xint_profile", " @jack ").expect("valid"),
+            "jack"
+        );
+        assert_eq!(
+            MCPServer::normalize_username("xint_profile", "Some_User_42").expect("valid"),
+            "Some_User_42"
+        );
+        for raw in ["@", "", "has space", "way_too_long_username", "x.com/jack"] {
+            let err = MCPServer::normalize_username("xint_profile", raw).expect_err(raw);
+            let payload: serde_json::Value = serde_json::from_str(&err).expect("json");
+            assert_eq!(payload["code"], "INVALID_ARGUMENT");
+            assert_eq!(payload["field"], "username");
+        }
+    }
+
+    #[tokio::test]
+    async fn pinned_validates_username_and_reports_a_missing_pin() {
+        let _guard = env_lock().lock().await;
+        let prev_token = save_env("X_BEARER_TOKEN");
+        env::set_var("X_BEARER_TOKEN", "test-token");
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        let err = server
+            .execute_tool(
+                "xint_pinned",
+                serde_json::json!({ "username": "jack/../admin" }),
+            )
+            .await
+            .expect_err("invalid username");
+        restore_env("X_BEARER_TOKEN", prev_token);
+        let payload: serde_json::Value = serde_json::from_str(&err).expect("json error");
+        assert_eq!(payload["code"], "INVALID_ARGUMENT");
+        assert_eq!(payload["tool"], "xint_pinned");
+        assert_eq!(payload["field"], "username");
+
+        assert_eq!(pinned_message("jack", None), "@jack has no pinned tweet.");
+        assert_eq!(
+            pinned_message("jack", Some(&Tweet::default())),
+            "Pinned tweet lookup completed."
+        );
+    }
+
+    #[tokio::test]
+    async fn user_brief_rejects_invalid_usernames_before_fetching() {
+        let _guard = env_lock().lock().await;
+        let prev_token = save_env("X_BEARER_TOKEN");
+        let prev_key = save_env("XAI_API_KEY");
+        env::set_var("X_BEARER_TOKEN", "test-token");
+        env::set_var("XAI_API_KEY", "test-key");
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        let err = server
+            .execute_tool(
+                "xint_user_brief",
+                serde_json::json!({ "username": "@not a handle" }),
+            )
+            .await
+            .expect_err("invalid username");
+        restore_env("X_BEARER_TOKEN", prev_token);
+        restore_env("XAI_API_KEY", prev_key);
+        let payload: serde_json::Value = serde_json::from_str(&err).expect("json error");
+        assert_eq!(payload["code"], "INVALID_ARGUMENT");
+        assert_eq!(payload["tool"], "xint_user_brief");
+        assert_eq!(payload["value"], "@not a handle");
+    }
+
+    #[tokio::test]
+    async fn mutuals_validates_both_usernames_and_intersects_by_id() {
+        let server = MCPServer::new(
+            PolicyMode::Engagement,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        for args in [
+            serde_json::json!({ "username_a": "bad handle", "username_b": "jack" }),
+            serde_json::json!({ "username_a": "jack", "username_b": "x/../y" }),
+        ] {
+            let err = server
+                .execute_tool("xint_mutuals", args)
+                .await
+                .expect_err("invalid username");
+            let payload: serde_json::Value = serde_json::from_str(&err).expect("json error");
+            assert_eq!(payload["code"], "INVALID_ARGUMENT");
+            assert_eq!(payload["tool"], "xint_mutuals");
+        }
+
+        let user = |id: &str, username: &str| serde_json::json!({ "id": id, "username": username });
+        let first = [
+            user("1", "alice"),
+            user("2", "bob"),
+            serde_json::json!({ "username": "no_id" }),
+            user("3", "carol"),
+        ];
+        let second = [
+            user("3", "carol"),
+            serde_json::json!({ "username": "no_id" }),
+            user("1", "alice_renamed"),
+        ];
+        let common = common_accounts(&first, &second);
+        let names: Vec<_> = common.iter().map(|u| u["username"].clone()).collect();
+        assert_eq!(names, ["alice", "carol"]);
+        assert!(common_accounts(&first, &[]).is_empty());
+    }
+
+    #[test]
+    fn watch_cursor_resumes_after_a_capped_poll_without_gaps() {
+        assert!(tweet_id_newer("100", "99"));
+        assert!(tweet_id_newer("1235", "1234"));
+        assert!(!tweet_id_newer("1234", "1234"));
+        assert!(!tweet_id_newer("999", "1000"));
+
+        let tweets = |ids: &[&str]| -> Vec<Tweet> {
+            ids.iter()
+                .map(|id| Tweet {
+                    id: id.to_string(),
+                    ..Default::default()
+                })
+                .collect()
+        };
+        let ids = |batch: &[Tweet]| batch.iter().map(|t| t.id.clone()).collect::<Vec<_>>();
+        // Search returns newest first, with a duplicate.
+        let polled = tweets(&["103", "102", "102", "101", "99", "100"]);
+
+        let (first, pending) = watch_batch(polled.clone(), None, 2);
+        assert_eq!(ids(&first), ["99", "100"]);
+        assert_eq!(pending, 3);
+
+        let (second, pending) = watch_batch(polled.clone(), Some("100"), 2);
+        assert_eq!(ids(&second), ["101", "102"]);
+        assert_eq!(pending, 1);
+
+        let (third, pending) = watch_batch(polled.clone(), Some("102"), 2);
+        assert_eq!(ids(&third), ["103"]);
+        assert_eq!(pending, 0);
+        let (caught_up, pending) = watch_batch(polled, Some("103"), 2);
+        assert!(caught_up.is_empty());
+        assert_eq!(pending, 0);
+
+        let dir = std::env::temp_dir().join("xint-rs-test-watch-cursor");
+        let _ = std::fs::remove_dir_all(&dir);
+        let path = watch_cursor_path(&dir, "rust -is:retweet");
+        assert_eq!(path, watch_cursor_path(&dir, " Rust  -is:retweet"));
+        assert_ne!(path, watch_cursor_path(&dir, "go -is:retweet"));
+        assert!(load_watch_cursor(&path).is_none());
+        let cursor = WatchCursor {
+            query: "rust -is:retweet".to_string(),
+            last_id: "102".to_string(),
+            updated_at: "2026-03-01T10:00:00Z".to_string(),
+        };
+        save_watch_cursor(&path, &cursor).expect("save");
+        assert!(!path.with_extension("json.tmp").exists());
+        let loaded = load_watch_cursor(&path).expect("load");
+        assert_eq!(loaded.query, cursor.query);
+        assert_eq!(loaded.last_id, "102");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn media_requires_exactly_one_of_username_or_query() {
+        let _guard = env_lock().lock().await;
+        let prev_token = save_env("X_BEARER_TOKEN");
+        env::set_var("X_BEARER_TOKEN", "test-token");
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        for args in [
+            serde_json::json!({}),
+            serde_json::json!({ "username": "jack", "query": "rust" }),
+        ] {
+            let err = server
+                .execute_tool("xint_media", args)
+                .await
+                .expect_err("ambiguous source");
+            assert_eq!(err, "Provide exactly one of username or query");
+        }
+        restore_env("X_BEARER_TOKEN", prev_token);
+    }
+
+    #[test]
+    fn search_request_adds_filters_and_sizes_pages_from_limit() {
+        let none = serde_json::json!({});
+        assert_eq!(search_request("rust", 15, &none), ("rust".to_string(), 1));
+        assert_eq!(search_request("rust", 20, &none).1, 1);
+        assert_eq!(search_request("rust", 21, &none).1, 2);
+        assert_eq!(search_request("rust", 100, &none).1, 5);
+        assert_eq!(search_request("rust", 200, &none).1, 5);
+        assert_eq!(search_request("rust", 0, &none).1, 1);
+
+        let camel = serde_json::json!({ "noReplies": true, "noRetweets": true });
+        assert_eq!(
+            search_request("rust lang", 15, &camel).0,
+            "rust lang -is:reply -is:retweet"
+        );
+        let snake = serde_json::json!({ "no_replies": false, "no_retweets": true });
+        assert_eq!(search_request("rust", 15, &snake).0, "rust -is:retweet");
+    }
+
+    #[test]
+    fn retweeters_clamp_the_limit_and_sum_follower_reach() {
+        let limit = |args: serde_json::Value| retweeters_limit(&args);
+        assert_eq!(limit(serde_json::json!({})), 100);
+        assert_eq!(limit(serde_json::json!({ "limit": 0 })), 1);
+        assert_eq!(limit(serde_json::json!({ "limit": 250 })), 250);
+        assert_eq!(limit(serde_json::json!({ "limit": 5000 })), 1000);
+
+        let user = |username: &str, followers: u64| crate::models::UserInfo {
+            id: username.to_string(),
+            username: username.to_string(),
+            name: username.to_string(),
+            description: String::new(),
+            location: None,
+            url: None,
+            created_at: None,
+            verified: false,
+            verified_type: None,
+            protected: false,
+            followers,
+            following: 0,
+            tweet_count: 0,
+            listed_count: 0,
+            profile_image_url: None,
+            pinned_tweet_id: None,
+            profile_url: format!("https://x.com/{username}"),
+        };
+        let content = MCPServer::retweeters_content("42", &[user("alice", 1_200), user("bob", 34)])
+            .expect("content");
+        let payload: serde_json::Value = serde_json::from_str(&content[0].text).expect("json");
+        assert_eq!(payload["message"], "Found 2 retweeters.");
+        assert_eq!(payload["data"]["total_followers"], 1_234);
+        assert_eq!(payload["data"]["users"][1]["username"], "bob");
+
+        let content = MCPServer::retweeters_content("42", &[]).expect("content");
+        let payload: serde_json::Value = serde_json::from_str(&content[1].text).expect("json");
+        assert_eq!(payload["no_results"], true);
+        assert_eq!(payload["data"]["total_followers"], 0);
+    }
+
+    #[test]
+    fn top_ranks_a_wider_window_by_the_chosen_metric() {
+        assert_eq!(top_window(1), 50);
+        assert_eq!(top_window(10), 50);
+        assert_eq!(top_window(20), 100);
+        assert_eq!(top_window(50), 200);
+        assert_eq!(top_window(100), 200);
+
+        let tweet = |id: &str, likes: u64, retweets: u64| Tweet {
+            id: id.to_string(),
+            metrics: crate::models::TweetMetrics {
+                likes,
+                retweets,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let tweets = vec![tweet("1", 5, 9), tweet("2", 50, 1), tweet("3", 20, 30)];
+        let ids = |ranked: Vec<Tweet>| ranked.into_iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids(top_by_metric(tweets.clone(), "likes", 2)), ["2", "3"]);
+        assert_eq!(
+            ids(top_by_metric(tweets.clone(), "retweets", 3)),
+            ["3", "1", "2"]
+        );
+        assert_eq!(ids(top_by_metric(tweets, "likes", 0)), ["2"]);
+    }
+
+    #[test]
+    fn mentions_drop_the_accounts_own_posts() {
+        let tweet = |id: &str, username: &str| Tweet {
+            id: id.to_string(),
+            username: username.to_string(),
+            ..Default::default()
+        };
+        let fetched = vec![
+            tweet("1", "alice"),
+            tweet("2", "Jack"),
+            tweet("3", "bob"),
+            tweet("3", "bob"),
+            tweet("4", "jack"),
+            tweet("5", "carol"),
+        ];
+        let ids = |tweets: Vec<Tweet>| tweets.into_iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(
+            ids(mentions_of(fetched.clone(), "jack", 10)),
+            ["1", "3", "5"]
+        );
+        assert_eq!(ids(mentions_of(fetched, "jack", 2)), ["1", "3"]);
+    }
+
+    #[test]
+    fn poll_message_distinguishes_open_closed_and_missing_polls() {
+        let poll = |open: bool, total_votes: u64| crate::models::Poll {
+            id: "p1".to_string(),
+            open,
+            voting_status: if open { "open" } else { "closed" }.to_string(),
+            end_datetime: None,
+            duration_minutes: Some(60),
+            total_votes,
+            options: Vec::new(),
+        };
+        assert_eq!(
+            poll_message(Some(&poll(true, 12))),
+            "Poll is open with 12 votes so far."
+        );
+        assert_eq!(
+            poll_message(Some(&poll(false, 340))),
+            "Poll closed with 340 votes."
+        );
+        assert_eq!(poll_message(None), "No poll on this tweet.");
+    }
+
+    #[test]
+    fn spaces_access_errors_map_to_capability_unavailable() {
+        for status in ["401", "403", "404"] {
+            let err = anyhow::anyhow!("X API {status}: not permitted");
+            let payload: serde_json::Value =
+                serde_json::from_str(&MCPServer::spaces_error("xint_spaces", err)).expect("json");
+            assert_eq!(payload["code"], "CAPABILITY_UNAVAILABLE", "{status}");
+            assert_eq!(payload["tool"], "xint_spaces");
+        }
+        let err = anyhow::anyhow!("X API 500: boom");
+        assert_eq!(
+            MCPServer::spaces_error("xint_spaces", err),
+            "Spaces search failed: X API 500: boom"
+        );
+
+        let path = PathBuf::from("/tmp/xint-rs-test-spaces-costs.json");
+        let _ = std::fs::remove_file(&path);
+        let entry = costs::track_cost(&path, "spaces", "/2/spaces/search", 7);
+        assert_eq!(entry.tweets_read, 7);
+        assert_eq!(entry.cost_usd, 0.01, "priced per request, not per Space");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn relationships_page_by_thousands_and_map_to_snapshots() {
+        assert_eq!(relationship_pages(0), 1);
+        assert_eq!(relationship_pages(100), 1);
+        assert_eq!(relationship_pages(1000), 1);
+        assert_eq!(relationship_pages(1001), 2);
+        assert_eq!(relationship_pages(5000), 5);
+
+        let users = [
+            serde_json::json!({
+                "id": "1",
+                "username": "alice",
+                "name": "Alice",
+                "public_metrics": { "followers_count": 10, "following_count": 2 }
+            }),
+            serde_json::json!({ "id": "2" }),
+        ];
+        let content =
+            MCPServer::relationships_content("jack", "followers", &users).expect("content");
+        let payload: serde_json::Value = serde_json::from_str(&content[0].text).expect("json");
+        assert_eq!(payload["message"], "@jack has 2 listed follower(s).");
+        assert_eq!(payload["data"]["relationship"], "followers");
+        assert_eq!(
+            payload["data"]["users"][0],
+            serde_json::json!({
+                "id": "1",
+                "username": "alice",
+                "name": "Alice",
+                "followers_count": 10,
+                "following_count": 2
+            })
+        );
+        assert_eq!(payload["data"]["users"][1]["username"], "?");
+        assert!(payload["data"]["users"][1]["followers_count"].is_null());
+
+        let content =
+            MCPServer::relationships_content("jack", "following", &users[..1]).expect("content");
+        let payload: serde_json::Value = serde_json::from_str(&content[0].text).expect("json");
+        assert_eq!(payload["message"], "@jack follows 1 listed account(s).");
+    }
+
+    #[test]
+    fn list_ids_are_extracted_from_ids_and_urls() {
+        for (input, expected) in [
+            ("1234567890", Some("1234567890")),
+            ("  1234567890 ", Some("1234567890")),
+            ("https://x.com/i/lists/1234567890", Some("1234567890")),
+            ("https://x.com/i/lists/1234567890/", Some("1234567890")),
+            (
+                "https://x.com/i/lists/1234567890/members",
+                Some("1234567890"),
+            ),
+            ("https://x.com/i/lists/1234567890?s=20", Some("1234567890")),
+            (
+                "https://twitter.com/i/lists/1234567890#top",
+                Some("1234567890"),
+            ),
+            ("https://x.com/i/lists/1234567890abc", None),
+            ("1234567890abc", None),
+            ("https://x.com/i/lists/", None),
+            ("https://x.com/i/lists/abc", None),
+            ("rust-lang", None),
+            ("", None),
+        ] {
+            assert_eq!(extract_list_id(input).as_deref(), expected, "{input:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn user_info_rejects_invalid_usernames_with_a_typed_error() {
+        let _guard = env_lock().lock().await;
+        let prev_token = save_env("X_BEARER_TOKEN");
+        env::set_var("X_BEARER_TOKEN", "test-token");
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        let err = server
+            .execute_tool(
+                "xint_user_info",
+                serde_json::json!({ "username": "way_too_long_for_x_handles" }),
+            )
+            .await
+            .expect_err("invalid username");
+        restore_env("X_BEARER_TOKEN", prev_token);
+        let payload: serde_json::Value = serde_json::from_str(&err).expect("json error");
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "code": "INVALID_ARGUMENT",
+                "message": "'way_too_long_for_x_handles' is not a valid X username",
+                "tool": "xint_user_info",
+                "field": "username",
+                "value": "way_too_long_for_x_handles",
+            })
+        );
+    }
+
+    #[test]
+    fn hashtags_are_normalized_or_rejected() {
+        assert_eq!(
+            MCPServer::normalize_hashtag("xint_hashtag", " #RustLang ").expect("valid"),
+            "RustLang"
+        );
+        assert_eq!(
+            MCPServer::normalize_hashtag("xint_hashtag", "web3_dev").expect("valid"),
+            "web3_dev"
+        );
+        for raw in ["#", "", "   ", "two words", "#a-b"] {
+            let err = MCPServer::normalize_hashtag("xint_hashtag", raw).expect_err(raw);
+            let payload: serde_json::Value = serde_json::from_str(&err).expect("json");
+            assert_eq!(payload["code"], "INVALID_ARGUMENT");
+            assert_eq!(payload["field"], "tag");
+        }
+    }
+
+    #[test]
+    fn xint_log_selects_level_and_defaults_to_warn() {
+        use tracing::level_filters::LevelFilter;
+        assert_eq!(log_level(Some("debug")), LevelFilter::DEBUG);
+        assert_eq!(log_level(Some(" INFO ")), LevelFilter::INFO);
+        assert_eq!(log_level(Some("error")), LevelFilter::ERROR);
+        assert_eq!(log_level(Some("verbose")), LevelFilter::WARN);
+        assert_eq!(log_level(None), LevelFilter::WARN);
+    }
+
+    #[test]
+    fn trend_search_urls_escape_hashtags_and_spaces() {
+        assert_eq!(
+            MCPServer::trend_search_url("#AI Agents"),
+            "https://x.com/search?q=%23AI+Agents"
+        );
+        assert_eq!(
+            MCPServer::trend_search_url("Rust"),
+            "https://x.com/search?q=Rust"
+        );
+    }
+
+    #[test]
+    fn trend_relevance_scores_keyword_overlap() {
+        assert_eq!(MCPServer::trend_relevance("OpenAI", "#OpenAI"), 1.0);
+        assert_eq!(MCPServer::trend_relevance("AI agents", "#AIAgents"), 1.0);
+        assert_eq!(MCPServer::trend_relevance("rust lang", "Rust"), 0.5);
+        assert_eq!(MCPServer::trend_relevance("bitcoin", "$BTC"), 0.0);
+        assert_eq!(MCPServer::trend_relevance("blockchain", "AI"), 0.0);
+        assert_eq!(MCPServer::trend_relevance("", "#Anything"), 0.0);
+    }
+
+    #[test]
+    fn unavailable_resources_map_to_stable_error_codes() {
+        let body = r#"{"errors":[{"value":"locked","detail":"Sorry, you are not authorized to see the Tweet with id: [42].","title":"Authorization Error","resource_type":"tweet","resource_id":"42","type":"https://api.twitter.com/2/problems/not-authorized-for-resource"}]}"#;
+        let unavailable = availability::classify_body(body).expect("classified");
+        let err = MCPServer::tool_error("Tweet lookup failed", unavailable.into());
+        let payload: serde_json::Value = serde_json::from_str(&err).expect("json error");
+        assert_eq!(payload["code"], "USER_PROTECTED");
+        assert_eq!(payload["resource_id"], "42");
+
+        let plain = MCPServer::tool_error("Search failed", anyhow::anyhow!("X API 500: boom"));
+        assert_eq!(plain, "Search failed: X API 500: boom");
+    }
+
+    /// Read SSE frames until one contains `needle`, returning its data line.
+    async fn read_sse_until(
+        stream: &mut tokio::net::TcpStream,
+        buf: &mut String,
+        needle: &str,
+    ) -> String {
+        loop {
+            if let Some(line) = buf
+                .lines()
+                .find(|line| line.starts_with("data: ") && line.contains(needle))
+            {
+                let line = line.trim_start_matches("data: ").to_string();
+                buf.clear();
+                return line;
+            }
+            let mut chunk = [0u8; 8192];
+            let n = stream.read(&mut chunk).await.expect("read sse");
+            assert!(n > 0, "sse stream closed");
+            buf.push_str(&String::from_utf8_lossy(&chunk[..n]));
+        }
+    }
+
+    async fn post_sse_message(addr: std::net::SocketAddr, endpoint: &str, body: &str) -> String {
+        let mut conn = tokio::net::TcpStream::connect(addr)
+            .await
+            .expect("connect post");
+        let request = format!(
+            "POST {endpoint} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        conn.write_all(request.as_bytes())
+            .await
+            .expect("write post");
+        let mut response = String::new();
+        conn.read_to_string(&mut response).await.expect("read post");
+        response
+    }
+
+    #[tokio::test]
+    async fn sse_transport_round_trips_json_rpc() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+
+        let client = async {
+            let mut sse = tokio::net::TcpStream::connect(addr)
+                .await
+                .expect("connect sse");
+            sse.write_all(
+                b"GET /sse HTTP/1.1\r\nHost: localhost\r\nAccept: text/event-stream\r\n\r\n",
+            )
+            .await
+            .expect("write sse");
+            let mut buf = String::new();
+            let endpoint = read_sse_until(&mut sse, &mut buf, "/message?sessionId=").await;
+
+            let ack = post_sse_message(
+                addr,
+                &endpoint,
+                r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            )
+            .await;
+            assert!(ack.starts_with("HTTP/1.1 202"));
+            let init = read_sse_until(&mut sse, &mut buf, "protocolVersion").await;
+            assert!(init.contains("\"id\":1"));
+
+            post_sse_message(
+                addr,
+                &endpoint,
+                r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+            )
+            .await;
+            let tools = read_sse_until(&mut sse, &mut buf, "\"id\":2").await;
+            assert!(tools.contains("xint_search"));
+
+            let unknown = post_sse_message(
+                addr,
+                "/message?sessionId=nope",
+                r#"{"jsonrpc":"2.0","id":3,"method":"tools/list"}"#,
+            )
+            .await;
+            assert!(unknown.starts_with("HTTP/1.1 404"));
+        };
+
+        tokio::select! {
+            result = std::sync::Arc::new(server).serve_sse(listener) => {
+                panic!("server exited early: {result:?}")
+            }
+            _ = client => {}
+        }
+    }
+
+    async fn send_raw_http(addr: std::net::SocketAddr, request: &str) -> String {
+        let mut conn = tokio::net::TcpStream::connect(addr).await.expect("connect");
+        conn.write_all(request.as_bytes()).await.expect("write");
+        let mut response = String::new();
+        conn.read_to_string(&mut response).await.expect("read");
+        response
+    }
+
+    #[tokio::test]
+    async fn sse_rejects_foreign_origins_hosts_and_oversized_bodies() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        )
+        .with_allowed_origins(vec!["https://app.example.com".to_string()]);
+
+        let client = async {
+            let foreign = send_raw_http(
+                addr,
+                "GET /sse HTTP/1.1\r\nHost: localhost\r\nOrigin: https://evil.example\r\n\r\n",
+            )
+            .await;
+            assert!(foreign.starts_with("HTTP/1.1 403"));
+            assert!(!foreign.contains("Access-Control-Allow-Origin"));
+
+            let rebound = send_raw_http(
+                addr,
+                "POST /message?sessionId=x HTTP/1.1\r\nHost: evil.example:3000\r\nContent-Length: 2\r\n\r\n{}",
+            )
+            .await;
+            assert!(rebound.starts_with("HTTP/1.1 403"));
+
+            for origin in ["http://localhost:5173", "https://app.example.com"] {
+                let preflight = send_raw_http(
+                    addr,
+                    &format!(
+                        "OPTIONS /message HTTP/1.1\r\nHost: 127.0.0.1\r\nOrigin: {origin}\r\n\r\n"
+                    ),
+                )
+                .await;
+                assert!(preflight.starts_with("HTTP/1.1 204"));
+                assert!(preflight.contains(&format!("Access-Control-Allow-Origin: {origin}\r\n")));
+            }
+
+            let oversized = send_raw_http(
+                addr,
+                "POST /message?sessionId=x HTTP/1.1\r\nHost: localhost\r\nContent-Length: 5000000\r\n\r\n{}",
+            )
+            .await;
+            assert!(oversized.starts_with("HTTP/1.1 413"));
+        };
+
+        tokio::select! {
+            result = std::sync::Arc::new(server).serve_sse(listener) => {
+                panic!("server exited early: {result:?}")
+            }
+            _ = client => {}
+        }
+    }
+
+    #[tokio::test]
+    async fn sse_slow_tool_call_does_not_block_other_sessions() {
+        let _guard = env_lock().lock().await;
+        let prev_base = save_env("XINT_PACKAGE_API_BASE_URL");
+        let prev_key = save_env("XINT_PACKAGE_API_KEY");
+
+        // Accept connections but never answer, so the tool call hangs.
+        let api = TcpListener::bind("127.0.0.1:0").await.expect("bind api");
+        let api_addr = api.local_addr().expect("api addr");
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = api.accept().await {
+                held.push(socket);
+            }
+        });
+        env::set_var("XINT_PACKAGE_API_BASE_URL", format!("http://{api_addr}/v1"));
+        env::set_var("XINT_PACKAGE_API_KEY", "xck_test");
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+
+        let client = async {
+            let mut sessions = Vec::new();
+            for _ in 0..2 {
+                let mut sse = tokio::net::TcpStream::connect(addr)
+                    .await
+                    .expect("connect sse");
+                sse.write_all(b"GET /sse HTTP/1.1\r\nHost: localhost\r\n\r\n")
+                    .await
+                    .expect("write sse");
+                let mut buf = String::new();
+                let endpoint = read_sse_until(&mut sse, &mut buf, "/message?sessionId=").await;
+                post_sse_message(
+                    addr,
+                    &endpoint,
+                    r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+                )
+                .await;
+                read_sse_until(&mut sse, &mut buf, "protocolVersion").await;
+                sessions.push((sse, buf, endpoint));
+            }
+
+            post_sse_message(
+                addr,
+                &sessions[0].2,
+                r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"xint_package_status","arguments":{"package_id":"pkg_slow"}}}"#,
+            )
+            .await;
+            let (sse, buf, endpoint) = &mut sessions[1];
+            post_sse_message(
+                addr,
+                endpoint,
+                r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+            )
+            .await;
+            let tools = tokio::time::timeout(
+                std::time::Duration::from_secs(5),
+                read_sse_until(sse, buf, "\"id\":2"),
+            )
+            .await
+            .expect("other session answered while the slow call runs");
+            assert!(tools.contains("xint_search"));
+        };
+
+        tokio::select! {
+            result = std::sync::Arc::new(server).serve_sse(listener) => {
+                panic!("server exited early: {result:?}")
+            }
+            _ = client => {}
+        }
+
+        restore_env("XINT_PACKAGE_API_BASE_URL", prev_base);
+        restore_env("XINT_PACKAGE_API_KEY", prev_key);
+    }
+
+    #[tokio::test]
+    async fn initialize_negotiates_protocol_version() {
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        for (requested, negotiated) in [
+            ("2024-11-05", "2024-11-05"),
+            ("2025-03-26", "2025-03-26"),
+            ("1999-01-01", SUPPORTED_PROTOCOL_VERSIONS[0]),
+        ] {
+            let request = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": requested,
+                    "capabilities": {},
+                    "clientInfo": { "name": "test", "version": "0" }
+                }
+            });
+            let response: serde_json::Value = serde_json::from_str(
+                &server
+                    .handle_message(&request.to_string())
+                    .await
+                    .expect("initialize")
+                    .expect("response"),
+            )
+            .expect("json");
+            assert_eq!(response["result"]["protocolVersion"], negotiated);
+            assert_eq!(response["result"]["serverInfo"]["name"], "xint");
+            assert!(response.get("error").is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn tools_list_pages_through_catalog_with_cursor() {
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        mark_initialized(&server);
+        let list = |cursor: Option<&str>| {
+            let mut request =
+                serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" });
+            if let Some(cursor) = cursor {
+                request["params"] = serde_json::json!({ "cursor": cursor });
+            }
+            request.to_string()
+        };
+        let send = |msg: String| {
+            let server = &server;
+            async move {
+                let response = server
+                    .handle_message(&msg)
+                    .await
+                    .expect("handled")
+                    .expect("response");
+                serde_json::from_str::<serde_json::Value>(&response).expect("json")
+            }
+        };
+
+        let all = send(list(None)).await;
+        let all_names: Vec<_> = all["result"]["tools"]
+            .as_array()
+            .expect("tools")
+            .iter()
+            .map(|t| t["name"].clone())
+            .collect();
+        assert!(all_names.len() > TOOLS_PAGE_SIZE);
+        assert!(all["result"].get("nextCursor").is_none());
+
+        let mut paged = Vec::new();
+        let mut cursor = Some(String::new());
+        while let Some(current) = cursor {
+            let page = send(list(Some(&current))).await;
+            let tools = page["result"]["tools"].as_array().expect("tools");
+            assert!(tools.len() <= TOOLS_PAGE_SIZE);
+            paged.extend(tools.iter().map(|t| t["name"].clone()));
+            cursor = page["result"]["nextCursor"].as_str().map(str::to_string);
+        }
+        assert_eq!(paged, all_names);
+
+        let invalid = send(list(Some("not-a-cursor"))).await;
+        assert_eq!(invalid["error"]["code"], -32602);
+    }
+
+    #[tokio::test]
+    async fn tools_methods_are_rejected_before_initialize() {
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        let call = r#"{"jsonrpc":"2.0","id":7,"method":"tools/call","params":{"name":"xint_costs","arguments":{}}}"#;
+        let rejected: serde_json::Value = serde_json::from_str(
+            &server
+                .handle_message(call)
+                .await
+                .expect("call")
+                .expect("response"),
+        )
+        .expect("json");
+        assert_eq!(rejected["id"], 7);
+        assert_eq!(rejected["error"]["code"], -32002);
+        assert_eq!(rejected["error"]["message"], "Server not initialized");
+
+        let session = server.open_session();
+        server
+            .handle_session_message(
+                Some(&session),
+                r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            )
+            .await
+            .expect("initialize");
+        let listed = server
+            .handle_session_message(
+                Some(&session),
+                r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+            )
+            .await
+            .expect("list")
+            .expect("response");
+        assert!(listed.contains("\"tools\""));
+        // Initializing one SSE session does not initialize stdio.
+        let still_rejected = server
+            .handle_message(call)
+            .await
+            .expect("call")
+            .expect("response");
+        assert!(still_rejected.contains("-32002"));
 
-        (format!("http://{addr}/v1"), rx, handle)
+        server
+            .handle_message(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#)
+            .await
+            .expect("initialize");
+        let accepted = server
+            .handle_message(call)
+            .await
+            .expect("call")
+            .expect("response");
+        assert!(accepted.contains("\"result\""));
     }
 
     #[tokio::test]
-    async fn package_create_contract_request_includes_headers_and_payload() {
-        let _guard = env_lock().lock().expect("env lock");
-        let prev_base = save_env("XINT_PACKAGE_API_BASE_URL");
-        let prev_key = save_env("XINT_PACKAGE_API_KEY");
-        let prev_workspace = save_env("XINT_WORKSPACE_ID");
-
-        let (base_url, req_rx, server_task) =
-            spawn_mock_server(202, r#"{"package_id":"pkg_123","status":"queued"}"#).await;
-        env::set_var("XINT_PACKAGE_API_BASE_URL", base_url);
-        env::set_var("XINT_PACKAGE_API_KEY", "xck_contract");
-        env::set_var("XINT_WORKSPACE_ID", "ws_contract");
-
+    async fn tool_arguments_are_checked_against_input_schema() {
         let server = MCPServer::new(
             PolicyMode::ReadOnly,
             false,
             PathBuf::from("/tmp/xint-rs-test-costs.json"),
             PathBuf::from("/tmp/xint-rs-test-reliability.json"),
         );
+        mark_initialized(&server);
 
-        let result = server
-            .execute_tool(
-                "xint_package_create",
-                serde_json::json!({
-                    "name": "Contract package",
-                    "topic_query": "ai agents",
-                    "sources": ["x_api_v2"],
-                    "time_window": {
-                        "from": "2026-01-01T00:00:00.000Z",
-                        "to": "2026-01-02T00:00:00.000Z"
-                    },
-                    "policy": "private",
-                    "analysis_profile": "summary"
-                }),
+        for (arguments, field, message) in [
+            (
+                serde_json::json!({}),
+                "query",
+                "Missing required field 'query'",
+            ),
+            (
+                serde_json::json!({ "query": 42 }),
+                "query",
+                "Field 'query' must be a string",
+            ),
+            (
+                serde_json::json!({ "query": "ai", "sort": "oldest" }),
+                "sort",
+                "Field 'sort' must be one of: likes, retweets, recent",
+            ),
+        ] {
+            let call = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 3,
+                "method": "tools/call",
+                "params": { "name": "xint_search", "arguments": arguments }
+            });
+            let response: serde_json::Value = serde_json::from_str(
+                &server
+                    .handle_message(&call.to_string())
+                    .await
+                    .expect("call")
+                    .expect("response"),
             )
-            .await
-            .expect("package create call");
+            .expect("json");
+            assert_eq!(response["id"], 3);
+            assert_eq!(response["error"]["code"], -32602);
+            assert_eq!(response["error"]["data"]["field"], field);
+            assert_eq!(
+                response["error"]["message"],
+                format!("Invalid params: {message}")
+            );
+        }
 
-        let request_raw = req_rx.await.expect("captured request");
-        server_task.await.expect("server task");
+        assert_eq!(
+            MCPServer::validate_tool_arguments(
+                "xint_package_refresh",
+                &serde_json::json!({ "package_id": "pkg_1", "reason": "whenever" })
+            )
+            .expect_err("enum")
+            .0,
+            "reason"
+        );
+        assert!(MCPServer::validate_tool_arguments(
+            "xint_costs",
+            &serde_json::json!({ "period": "week" })
+        )
+        .is_ok());
+
+        for (tweets, field) in [
+            (serde_json::json!([]), "tweets"),
+            (serde_json::json!("just text"), "tweets"),
+            (serde_json::json!(["ok", 7]), "tweets[1]"),
+        ] {
+            assert_eq!(
+                MCPServer::validate_tool_arguments(
+                    "xint_sentiment",
+                    &serde_json::json!({ "tweets": tweets })
+                )
+                .expect_err("invalid tweets")
+                .0,
+                field
+            );
+        }
+        for arguments in [
+            serde_json::json!({ "username": "jack", "query": "rust" }),
+            serde_json::json!({ "limit": 5 }),
+        ] {
+            let (_, message) = MCPServer::validate_tool_arguments("xint_media", &arguments)
+                .expect_err("exactly one source");
+            assert_eq!(message, "Provide exactly one of 'username', 'query'");
+        }
+        assert!(MCPServer::validate_tool_arguments(
+            "xint_media",
+            &serde_json::json!({ "query": "rust" })
+        )
+        .is_ok());
 
-        let lower = request_raw.to_lowercase();
-        assert!(lower.contains("post /v1/packages http/1.1"));
-        assert!(lower.contains("authorization: bearer xck_contract"));
-        assert!(lower.contains("x-workspace-id: ws_contract"));
-        assert!(request_raw.contains("\"name\":\"Contract package\""));
-        assert!(request_raw.contains("\"topic_query\":\"ai agents\""));
-        assert!(request_raw.contains("\"analysis_profile\":\"summary\""));
-        assert!(result[0].text.contains("\"package_id\": \"pkg_123\""));
+        assert!(MCPServer::validate_tool_arguments(
+            "xint_sentiment",
+            &serde_json::json!({ "tweets": ["great launch", { "id": "1", "text": "meh" }] })
+        )
+        .is_ok());
 
-        restore_env("XINT_PACKAGE_API_BASE_URL", prev_base);
-        restore_env("XINT_PACKAGE_API_KEY", prev_key);
-        restore_env("XINT_WORKSPACE_ID", prev_workspace);
+        let (field, message) = MCPServer::validate_tool_arguments(
+            "xint_analyze",
+            &serde_json::json!({ "query": "rust", "model": "grok-99-unpriced" }),
+        )
+        .expect_err("unpriced model");
+        assert_eq!(field, "model");
+        assert!(message.contains("grok-99-unpriced"));
+        assert!(MCPServer::validate_tool_arguments(
+            "xint_analyze",
+            &serde_json::json!({ "query": "rust", "model": "grok-3" })
+        )
+        .is_ok());
     }
 
     #[tokio::test]
-    async fn quota_error_includes_upgrade_url() {
-        let _guard = env_lock().lock().expect("env lock");
-        let prev_base = save_env("XINT_PACKAGE_API_BASE_URL");
-        let prev_upgrade = save_env("XINT_BILLING_UPGRADE_URL");
-
-        let (base_url, _req_rx, server_task) = spawn_mock_server(
-            402,
-            r#"{"code":"QUOTA_EXCEEDED","error":"Package limit reached for current plan."}"#,
-        )
-        .await;
-        env::set_var("XINT_PACKAGE_API_BASE_URL", base_url);
-        env::set_var(
-            "XINT_BILLING_UPGRADE_URL",
-            "https://xint.dev/pricing?src=contract-test",
+    async fn failed_requests_echo_their_id() {
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
         );
+        let err = server
+            .handle_message(r#"{"jsonrpc":"2.0","id":"req-5"}"#)
+            .await
+            .expect_err("missing method");
+        let response: serde_json::Value = serde_json::from_str(&err.to_response()).expect("json");
+        assert_eq!(response["id"], "req-5");
+        assert_eq!(response["error"]["code"], -32603);
+        assert_eq!(response["error"]["message"], "Missing method field");
+
+        let err = server
+            .handle_message("{not json")
+            .await
+            .expect_err("parse error");
+        let response: serde_json::Value = serde_json::from_str(&err.to_response()).expect("json");
+        assert!(response.get("id").is_some_and(|id| id.is_null()));
+        assert_eq!(response["error"]["code"], -32700);
+    }
 
+    #[tokio::test]
+    async fn batch_requests_return_one_array_in_order() {
         let server = MCPServer::new(
             PolicyMode::ReadOnly,
             false,
             PathBuf::from("/tmp/xint-rs-test-costs.json"),
             PathBuf::from("/tmp/xint-rs-test-reliability.json"),
         );
+        mark_initialized(&server);
 
-        let err = server
-            .call_package_api(
-                reqwest::Method::POST,
-                "/packages",
-                Some(serde_json::json!({})),
+        let response = server
+            .handle_message(
+                r#"[{"jsonrpc":"2.0","id":1,"method":"tools/list"},{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"xint_costs","arguments":{"period":"today"}}},{"jsonrpc":"2.0","method":"initialized"}]"#,
             )
             .await
-            .expect_err("expected quota error");
-        server_task.await.expect("server task");
+            .expect("batch handled")
+            .expect("batch response");
+        let parsed: serde_json::Value = serde_json::from_str(&response).expect("json array");
+        let items = parsed.as_array().expect("array");
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0]["id"], 1);
+        assert!(items[0]["result"]["tools"].is_array());
+        assert_eq!(items[1]["id"], 2);
+        assert!(items[1]["result"]["content"].is_array());
+
+        let notifications = server
+            .handle_message(r#"[{"jsonrpc":"2.0","method":"initialized"}]"#)
+            .await
+            .expect("notification batch");
+        assert!(notifications.is_none());
+    }
 
-        assert!(err.contains("QUOTA_EXCEEDED"));
-        assert!(err.contains("Upgrade: https://xint.dev/pricing?src=contract-test"));
+    #[tokio::test]
+    async fn tools_call_emits_start_and_completion_progress() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            false,
+            PathBuf::from("/tmp/xint-rs-test-costs.json"),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        mark_initialized(&server);
+        *lock_state(&server.notifier) = Some(tx);
 
-        restore_env("XINT_PACKAGE_API_BASE_URL", prev_base);
-        restore_env("XINT_BILLING_UPGRADE_URL", prev_upgrade);
+        server
+            .handle_message(
+                r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"xint_costs","arguments":{},"_meta":{"progressToken":"tok-1"}}}"#,
+            )
+            .await
+            .expect("call handled");
+
+        let start: serde_json::Value =
+            serde_json::from_str(&rx.try_recv().expect("start event")).expect("json");
+        let done: serde_json::Value =
+            serde_json::from_str(&rx.try_recv().expect("done event")).expect("json");
+        assert_eq!(start["method"], "notifications/progress");
+        assert_eq!(start["params"]["progressToken"], "tok-1");
+        assert_eq!(start["params"]["progress"], 0);
+        assert_eq!(done["params"]["progress"], 100);
+        assert!(rx.try_recv().is_err());
+
+        // No progressToken, no notifications.
+        server
+            .handle_message(
+                r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"xint_costs","arguments":{}}}"#,
+            )
+            .await
+            .expect("call handled");
+        assert!(rx.try_recv().is_err());
     }
 
     #[tokio::test]
-    async fn package_query_requires_citations_when_requested() {
-        let _guard = env_lock().lock().expect("env lock");
+    async fn cancelled_notification_aborts_in_flight_call() {
+        let _guard = env_lock().lock().await;
         let prev_base = save_env("XINT_PACKAGE_API_BASE_URL");
+        let prev_key = save_env("XINT_PACKAGE_API_KEY");
 
-        let (base_url, _req_rx, server_task) = spawn_mock_server(
-            200,
-            r#"{"answer":"No citations","claims":[{"claim_id":"claim_1","text":"example"}],"citations":[]}"#,
-        )
-        .await;
-        env::set_var("XINT_PACKAGE_API_BASE_URL", base_url);
+        // Accept connections but never answer, so the tool call hangs.
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        env::set_var("XINT_PACKAGE_API_BASE_URL", format!("http://{addr}/v1"));
+        env::set_var("XINT_PACKAGE_API_KEY", "xck_test");
 
         let server = MCPServer::new(
             PolicyMode::ReadOnly,
@@ -1966,89 +9758,350 @@ mod tests {
             PathBuf::from("/tmp/xint-rs-test-costs.json"),
             PathBuf::from("/tmp/xint-rs-test-reliability.json"),
         );
+        mark_initialized(&server);
+        let cancellations = server.cancellations.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            assert!(apply_cancellation(
+                &cancellations,
+                None,
+                r#"{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":7,"reason":"user"}}"#,
+            ));
+        });
 
-        let err = server
-            .execute_tool(
-                "xint_package_query",
-                serde_json::json!({
-                    "query": "what changed?",
-                    "package_ids": ["pkg_123"],
-                    "require_citations": true
-                }),
-            )
-            .await
-            .expect_err("expected citation validation failure");
-        server_task.await.expect("server task");
-
-        assert!(err.contains("missing citations"));
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            server.handle_message(
+                r#"{"jsonrpc":"2.0","id":7,"method":"tools/call","params":{"name":"xint_package_status","arguments":{"package_id":"pkg_slow"}}}"#,
+            ),
+        )
+        .await
+        .expect("cancellation should stop the call")
+        .expect("handled");
+        assert!(response.is_none());
+        assert!(server.cancellations.lock().expect("map").is_empty());
 
         restore_env("XINT_PACKAGE_API_BASE_URL", prev_base);
+        restore_env("XINT_PACKAGE_API_KEY", prev_key);
     }
 
     #[tokio::test]
-    async fn costs_tool_returns_success_payload_without_network() {
+    async fn cancelled_guarded_call_is_still_recorded_in_the_ledger() {
+        let _guard = env_lock().lock().await;
+        let prev_base = save_env("XINT_PACKAGE_API_BASE_URL");
+        let prev_key = save_env("XINT_PACKAGE_API_KEY");
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        env::set_var("XINT_PACKAGE_API_BASE_URL", format!("http://{addr}/v1"));
+        env::set_var("XINT_PACKAGE_API_KEY", "xck_test");
+
+        let costs_path = PathBuf::from("/tmp/xint-rs-test-cancelled-costs.json");
+        let _ = std::fs::remove_file(&costs_path);
         let server = MCPServer::new(
+            PolicyMode::ReadOnly,
+            true,
+            costs_path.clone(),
+            PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        );
+        mark_initialized(&server);
+        let cancellations = server.cancellations.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            assert!(apply_cancellation(
+                &cancellations,
+                None,
+                r#"{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":8}}"#,
+            ));
+        });
+
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            server.handle_message(
+                r#"{"jsonrpc":"2.0","id":8,"method":"tools/call","params":{"name":"xint_package_search","arguments":{"query":"rust"}}}"#,
+            ),
+        )
+        .await
+        .expect("cancellation should stop the call")
+        .expect("handled");
+        assert!(response.is_none());
+        let by_tool = costs::breakdown(&costs_path, "today");
+        assert!(by_tool.contains_key("xint_package_search"), "{by_tool:?}");
+
+        let _ = std::fs::remove_file(&costs_path);
+        restore_env("XINT_PACKAGE_API_BASE_URL", prev_base);
+        restore_env("XINT_PACKAGE_API_KEY", prev_key);
+    }
+
+    #[tokio::test]
+    async fn cancellations_are_scoped_to_the_sending_session() {
+        let _guard = env_lock().lock().await;
+        let prev_base = save_env("XINT_PACKAGE_API_BASE_URL");
+        let prev_key = save_env("XINT_PACKAGE_API_KEY");
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        env::set_var("XINT_PACKAGE_API_BASE_URL", format!("http://{addr}/v1"));
+        env::set_var("XINT_PACKAGE_API_KEY", "xck_test");
+
+        let server = std::sync::Arc::new(MCPServer::new(
             PolicyMode::ReadOnly,
             false,
             PathBuf::from("/tmp/xint-rs-test-costs.json"),
             PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        ));
+        let call = r#"{"jsonrpc":"2.0","id":7,"method":"tools/call","params":{"name":"xint_package_status","arguments":{"package_id":"pkg_slow"}}}"#;
+        let mut calls = Vec::new();
+        for session in ["a", "b"] {
+            server
+                .handle_session_message(
+                    Some(session),
+                    r#"{"jsonrpc":"2.0","id":0,"method":"initialize","params":{}}"#,
+                )
+                .await
+                .expect("initialize");
+            let server = std::sync::Arc::clone(&server);
+            calls.push(tokio::spawn(async move {
+                server.handle_session_message(Some(session), call).await
+            }));
+        }
+        while server.cancellations.lock().expect("map").len() < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        // The same id from the same session is refused while the first runs.
+        let duplicate = server
+            .handle_session_message(Some("a"), call)
+            .await
+            .expect("handled")
+            .expect("response");
+        assert!(duplicate.contains("already in flight"));
+
+        assert!(apply_cancellation(
+            &server.cancellations,
+            Some("b"),
+            r#"{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":7}}"#,
+        ));
+        let b = calls.pop().expect("b");
+        assert!(b.await.expect("join").expect("handled").is_none());
+        let a = calls.pop().expect("a");
+        assert!(!a.is_finished());
+        assert_eq!(
+            server
+                .cancellations
+                .lock()
+                .expect("map")
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>(),
+            [("a".to_string(), "7".to_string())]
         );
+        a.abort();
 
-        let result = server
-            .execute_tool("xint_costs", serde_json::json!({ "period": "today" }))
+        restore_env("XINT_PACKAGE_API_BASE_URL", prev_base);
+        restore_env("XINT_PACKAGE_API_KEY", prev_key);
+    }
+
+    #[tokio::test]
+    async fn tool_calls_beyond_the_concurrency_limit_wait_for_a_permit() {
+        let _guard = env_lock().lock().await;
+        let prev_base = save_env("XINT_PACKAGE_API_BASE_URL");
+        let prev_key = save_env("XINT_PACKAGE_API_KEY");
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        env::set_var("XINT_PACKAGE_API_BASE_URL", format!("http://{addr}/v1"));
+        env::set_var("XINT_PACKAGE_API_KEY", "xck_test");
+
+        let server = std::sync::Arc::new(
+            MCPServer::new(
+                PolicyMode::ReadOnly,
+                false,
+                PathBuf::from("/tmp/xint-rs-test-costs.json"),
+                PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+            )
+            .with_max_concurrency(1),
+        );
+        mark_initialized(&server);
+
+        let slow = tokio::spawn({
+            let server = std::sync::Arc::clone(&server);
+            async move {
+                server
+                    .handle_message(
+                        r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"xint_package_status","arguments":{"package_id":"pkg_slow"}}}"#,
+                    )
+                    .await
+            }
+        });
+        while server.tool_permits.available_permits() > 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let fast = r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"xint_costs","arguments":{}}}"#;
+        assert!(
+            tokio::time::timeout(
+                std::time::Duration::from_millis(200),
+                server.handle_message(fast)
+            )
             .await
-            .expect("costs tool call");
+            .is_err(),
+            "second call should wait while the only permit is held"
+        );
 
-        assert!(result[0].text.contains("\"type\": \"success\""));
-        assert!(result[0].text.contains("\"period\": \"today\""));
+        assert!(apply_cancellation(
+            &server.cancellations,
+            None,
+            r#"{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":1}}"#,
+        ));
+        assert!(slow.await.expect("join").expect("handled").is_none());
+        let response = server
+            .handle_message(fast)
+            .await
+            .expect("handled")
+            .expect("response");
+        assert!(response.contains("\"result\""));
+
+        restore_env("XINT_PACKAGE_API_BASE_URL", prev_base);
+        restore_env("XINT_PACKAGE_API_KEY", prev_key);
     }
 
     #[tokio::test]
-    async fn cache_clear_tool_returns_success_payload() {
-        let server = MCPServer::new(
+    async fn package_api_calls_beyond_the_limit_wait_their_turn() {
+        let _guard = env_lock().lock().await;
+        let prev_base = save_env("XINT_PACKAGE_API_BASE_URL");
+        let prev_key = save_env("XINT_PACKAGE_API_KEY");
+        let prev_limit = save_env("XINT_PACKAGE_API_MAX_CONCURRENCY");
+
+        // Count connections but never answer, so each call holds its permit.
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        let accepted = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        tokio::spawn({
+            let accepted = std::sync::Arc::clone(&accepted);
+            async move {
+                let mut held = Vec::new();
+                while let Ok((socket, _)) = listener.accept().await {
+                    accepted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    held.push(socket);
+                }
+            }
+        });
+        env::set_var("XINT_PACKAGE_API_BASE_URL", format!("http://{addr}/v1"));
+        env::set_var("XINT_PACKAGE_API_KEY", "xck_test");
+        env::set_var("XINT_PACKAGE_API_MAX_CONCURRENCY", "1");
+
+        let server = std::sync::Arc::new(MCPServer::new(
             PolicyMode::ReadOnly,
             false,
             PathBuf::from("/tmp/xint-rs-test-costs.json"),
             PathBuf::from("/tmp/xint-rs-test-reliability.json"),
+        ));
+        restore_env("XINT_PACKAGE_API_MAX_CONCURRENCY", prev_limit);
+        let status = |package_id: &'static str| {
+            let server = std::sync::Arc::clone(&server);
+            tokio::spawn(async move {
+                server
+                    .execute_tool(
+                        "xint_package_status",
+                        serde_json::json!({ "package_id": package_id }),
+                    )
+                    .await
+            })
+        };
+        let connections = |expected: usize| {
+            let accepted = std::sync::Arc::clone(&accepted);
+            async move {
+                while accepted.load(std::sync::atomic::Ordering::SeqCst) < expected {
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                }
+            }
+        };
+
+        let first = status("pkg_first");
+        tokio::time::timeout(std::time::Duration::from_secs(5), connections(1))
+            .await
+            .expect("first call connects");
+        let second = status("pkg_second");
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert_eq!(
+            accepted.load(std::sync::atomic::Ordering::SeqCst),
+            1,
+            "second call must wait for the only permit"
         );
+        assert_eq!(server.package_api_permits.available_permits(), 0);
 
-        let result = server
-            .execute_tool("xint_cache_clear", serde_json::json!({}))
+        first.abort();
+        tokio::time::timeout(std::time::Duration::from_secs(5), connections(2))
             .await
-            .expect("cache clear tool call");
+            .expect("second call proceeds once the permit is released");
+        second.abort();
 
-        assert!(result[0].text.contains("\"type\": \"success\""));
-        assert!(result[0].text.contains("\"message\": \"Cache cleared.\""));
-        assert!(result[0].text.contains("\"cleared\""));
+        restore_env("XINT_PACKAGE_API_BASE_URL", prev_base);
+        restore_env("XINT_PACKAGE_API_KEY", prev_key);
     }
 
     #[tokio::test]
-    async fn core_search_tool_requires_bearer_token() {
-        let _guard = env_lock().lock().expect("env lock");
-        let prev_bearer = save_env("X_BEARER_TOKEN");
-        env::remove_var("X_BEARER_TOKEN");
-
+    async fn article_results_are_listed_and_readable_as_resources() {
         let server = MCPServer::new(
             PolicyMode::ReadOnly,
             false,
             PathBuf::from("/tmp/xint-rs-test-costs.json"),
             PathBuf::from("/tmp/xint-rs-test-reliability.json"),
         );
-
-        let err = server
-            .execute_tool("xint_search", serde_json::json!({ "query": "ai agents" }))
+        mark_initialized(&server);
+        let args = serde_json::json!({ "url": "http://127.0.0.1:9/post" });
+        let result = vec![MCPContent {
+            content_type: "text".to_string(),
+            text: r#"{"data":{"url":"http://127.0.0.1:9/post"}}"#.to_string(),
+        }];
+        server.cache_result("xint_article", &args, &result);
+        server.cache_result("xint_article", &args, &result);
+
+        let listed = server
+            .handle_message(r#"{"jsonrpc":"2.0","id":2,"method":"resources/list"}"#)
             .await
-            .expect_err("expected bearer token error");
-
-        assert!(err.contains("X_BEARER_TOKEN"));
-        restore_env("X_BEARER_TOKEN", prev_bearer);
+            .expect("resources/list")
+            .expect("response");
+        let listed: serde_json::Value = serde_json::from_str(&listed).expect("json");
+        let resources = listed["result"]["resources"].as_array().expect("array");
+        assert_eq!(resources.len(), 1);
+        let uri = resources[0]["uri"].as_str().expect("uri");
+        assert!(uri.starts_with("xint://article/"));
+
+        let read = server
+            .handle_message(&format!(
+                r#"{{"jsonrpc":"2.0","id":3,"method":"resources/read","params":{{"uri":"{uri}"}}}}"#
+            ))
+            .await
+            .expect("resources/read")
+            .expect("response");
+        assert!(read.contains("http://127.0.0.1:9/post"));
     }
 
     #[tokio::test]
-    async fn analyze_tool_requires_xai_api_key() {
-        let _guard = env_lock().lock().expect("env lock");
+    async fn article_reports_unreachable_urls() {
+        let _guard = env_lock().lock().await;
         let prev_key = save_env("XAI_API_KEY");
-        env::remove_var("XAI_API_KEY");
+        env::set_var("XAI_API_KEY", "test-key");
 
         let server = MCPServer::new(
             PolicyMode::ReadOnly,
@@ -2056,15 +10109,23 @@ mod tests {
             PathBuf::from("/tmp/xint-rs-test-costs.json"),
             PathBuf::from("/tmp/xint-rs-test-reliability.json"),
         );
-
         let err = server
             .execute_tool(
-                "xint_analyze",
-                serde_json::json!({ "query": "summarize this" }),
+                "xint_article",
+                serde_json::json!({ "url": "http://127.0.0.1:9/post" }),
             )
             .await
-            .expect_err("expected xai key error");
+            .expect_err("unreachable url");
+        assert!(err.contains("Article URL is unreachable: http://127.0.0.1:9/post"));
 
+        env::remove_var("XAI_API_KEY");
+        let err = server
+            .execute_tool(
+                "xint_article",
+                serde_json::json!({ "url": "http://127.0.0.1:9/post" }),
+            )
+            .await
+            .expect_err("missing key");
         assert!(err.contains("XAI_API_KEY"));
         restore_env("XAI_API_KEY", prev_key);
     }
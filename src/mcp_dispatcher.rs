@@ -3,43 +3,94 @@ use crate::action_result::{action_error, action_success, ActionExecutionResult};
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum McpToolRoute {
     Search,
+    Hashtag,
     Profile,
+    Top,
+    Mentions,
+    Similar,
+    EngagementRate,
+    Pinned,
+    UserInfo,
+    Verify,
+    UserBrief,
     Thread,
+    Replies,
+    Retweeters,
+    Quotes,
+    Poll,
+    Media,
     Tweet,
+    Spaces,
+    List,
     Trends,
+    TrendsFor,
     XSearch,
     CollectionsList,
     Analyze,
+    Translate,
     Article,
+    ResolveUrl,
+    UrlExpand,
     CollectionsSearch,
     Bookmarks,
     PackageCreate,
+    PackageValidate,
     PackageStatus,
     PackageQuery,
     PackageRefresh,
     PackageSearch,
     PackagePublish,
     CacheClear,
+    Export,
     Watch,
+    Following,
+    Followers,
     Diff,
+    Mutuals,
+    Compare,
     Report,
     Sentiment,
     Costs,
+    Estimate,
+    BudgetStatus,
+    BudgetReset,
 }
 
 pub fn resolve_tool_route(name: &str) -> ActionExecutionResult<McpToolRoute> {
     match name {
         "xint_search" => action_success("tool route resolved", Some(McpToolRoute::Search)),
+        "xint_hashtag" => action_success("tool route resolved", Some(McpToolRoute::Hashtag)),
         "xint_profile" => action_success("tool route resolved", Some(McpToolRoute::Profile)),
+        "xint_top" => action_success("tool route resolved", Some(McpToolRoute::Top)),
+        "xint_mentions" => action_success("tool route resolved", Some(McpToolRoute::Mentions)),
+        "xint_similar" => action_success("tool route resolved", Some(McpToolRoute::Similar)),
+        "xint_engagement_rate" => {
+            action_success("tool route resolved", Some(McpToolRoute::EngagementRate))
+        }
+        "xint_pinned" => action_success("tool route resolved", Some(McpToolRoute::Pinned)),
+        "xint_user_info" => action_success("tool route resolved", Some(McpToolRoute::UserInfo)),
+        "xint_verify" => action_success("tool route resolved", Some(McpToolRoute::Verify)),
+        "xint_user_brief" => action_success("tool route resolved", Some(McpToolRoute::UserBrief)),
         "xint_thread" => action_success("tool route resolved", Some(McpToolRoute::Thread)),
+        "xint_replies" => action_success("tool route resolved", Some(McpToolRoute::Replies)),
+        "xint_retweeters" => action_success("tool route resolved", Some(McpToolRoute::Retweeters)),
+        "xint_poll" => action_success("tool route resolved", Some(McpToolRoute::Poll)),
+        "xint_quotes" => action_success("tool route resolved", Some(McpToolRoute::Quotes)),
+        "xint_media" => action_success("tool route resolved", Some(McpToolRoute::Media)),
         "xint_tweet" => action_success("tool route resolved", Some(McpToolRoute::Tweet)),
+        "xint_spaces" => action_success("tool route resolved", Some(McpToolRoute::Spaces)),
+        "xint_list" => action_success("tool route resolved", Some(McpToolRoute::List)),
         "xint_trends" => action_success("tool route resolved", Some(McpToolRoute::Trends)),
+        "xint_trends_for" => action_success("tool route resolved", Some(McpToolRoute::TrendsFor)),
         "xint_xsearch" => action_success("tool route resolved", Some(McpToolRoute::XSearch)),
         "xint_collections_list" => {
             action_success("tool route resolved", Some(McpToolRoute::CollectionsList))
         }
         "xint_analyze" => action_success("tool route resolved", Some(McpToolRoute::Analyze)),
+        "xint_translate" => action_success("tool route resolved", Some(McpToolRoute::Translate)),
         "xint_article" => action_success("tool route resolved", Some(McpToolRoute::Article)),
+        "xint_resolve_url" => action_success("tool route resolved", Some(McpToolRoute::ResolveUrl)),
+        "xint_url_expand" => action_success("tool route resolved", Some(McpToolRoute::UrlExpand)),
         "xint_collections_search" => {
             action_success("tool route resolved", Some(McpToolRoute::CollectionsSearch))
         }
@@ -47,6 +98,9 @@ pub fn resolve_tool_route(name: &str) -> ActionExecutionResult<McpToolRoute> {
         "xint_package_create" => {
             action_success("tool route resolved", Some(McpToolRoute::PackageCreate))
         }
+        "xint_package_validate" => {
+            action_success("tool route resolved", Some(McpToolRoute::PackageValidate))
+        }
         "xint_package_status" => {
             action_success("tool route resolved", Some(McpToolRoute::PackageStatus))
         }
@@ -63,11 +117,23 @@ pub fn resolve_tool_route(name: &str) -> ActionExecutionResult<McpToolRoute> {
             action_success("tool route resolved", Some(McpToolRoute::PackagePublish))
         }
         "xint_cache_clear" => action_success("tool route resolved", Some(McpToolRoute::CacheClear)),
+        "xint_export" => action_success("tool route resolved", Some(McpToolRoute::Export)),
         "xint_watch" => action_success("tool route resolved", Some(McpToolRoute::Watch)),
+        "xint_following" => action_success("tool route resolved", Some(McpToolRoute::Following)),
+        "xint_followers" => action_success("tool route resolved", Some(McpToolRoute::Followers)),
         "xint_diff" => action_success("tool route resolved", Some(McpToolRoute::Diff)),
+        "xint_mutuals" => action_success("tool route resolved", Some(McpToolRoute::Mutuals)),
+        "xint_compare" => action_success("tool route resolved", Some(McpToolRoute::Compare)),
         "xint_report" => action_success("tool route resolved", Some(McpToolRoute::Report)),
         "xint_sentiment" => action_success("tool route resolved", Some(McpToolRoute::Sentiment)),
         "xint_costs" => action_success("tool route resolved", Some(McpToolRoute::Costs)),
+        "xint_estimate" => action_success("tool route resolved", Some(McpToolRoute::Estimate)),
+        "xint_budget_status" => {
+            action_success("tool route resolved", Some(McpToolRoute::BudgetStatus))
+        }
+        "xint_budget_reset" => {
+            action_success("tool route resolved", Some(McpToolRoute::BudgetReset))
+        }
         _ => action_error(format!("Unknown tool: {name}")),
     }
 }
@@ -7,7 +7,7 @@ use std::collections::HashMap;
 // Tweet
 // ---------------------------------------------------------------------------
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TweetMetrics {
     pub likes: u64,
     pub retweets: u64,
@@ -30,7 +30,7 @@ pub struct UrlEntity {
     pub images: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Tweet {
     pub id: String,
     pub text: String,
@@ -39,6 +39,9 @@ pub struct Tweet {
     pub name: String,
     pub created_at: String,
     pub conversation_id: String,
+    /// Id of the tweet this one replies to, if it is a reply.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_reply_to_id: Option<String>,
     pub metrics: TweetMetrics,
     pub urls: Vec<UrlEntity>,
     pub mentions: Vec<String>,
@@ -46,6 +49,87 @@ pub struct Tweet {
     pub tweet_url: String,
 }
 
+/// One fetch of a conversation thread, with enough metadata to tell whether
+/// more pages remain.
+#[derive(Debug, Clone, Serialize)]
+pub struct ThreadPage {
+    pub tweets: Vec<Tweet>,
+    pub fetched_pages: u32,
+    pub next_cursor: Option<String>,
+    pub complete: bool,
+    pub estimated_total: Option<u64>,
+}
+
+/// Profile metadata for one account, without its tweets.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserInfo {
+    pub id: String,
+    pub username: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    pub verified: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified_type: Option<String>,
+    pub protected: bool,
+    pub followers: u64,
+    pub following: u64,
+    pub tweet_count: u64,
+    pub listed_count: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile_image_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_tweet_id: Option<String>,
+    pub profile_url: String,
+}
+
+/// One option of a tweet poll with its share of the vote.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollOption {
+    pub position: u64,
+    pub label: String,
+    pub votes: u64,
+    pub percentage: f64,
+}
+
+/// A poll attached to a tweet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Poll {
+    pub id: String,
+    pub open: bool,
+    pub voting_status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_datetime: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_minutes: Option<u64>,
+    pub total_votes: u64,
+    pub options: Vec<PollOption>,
+}
+
+/// A Twitter Space with its host and audience size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Space {
+    pub id: String,
+    pub title: String,
+    /// `live`, `scheduled` or `ended`.
+    pub state: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    pub participant_count: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduled_start: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<String>,
+    pub space_url: String,
+}
+
 // ---------------------------------------------------------------------------
 // Raw API response
 // ---------------------------------------------------------------------------
@@ -124,6 +208,7 @@ pub struct RawTweet {
 pub struct RawIncludes {
     pub users: Option<Vec<RawUser>>,
     pub media: Option<Vec<serde_json::Value>>,
+    pub polls: Option<Vec<serde_json::Value>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -163,6 +248,34 @@ pub struct OAuthTokens {
 // Costs
 // ---------------------------------------------------------------------------
 
+/// One MCP tool call in the cost ledger.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallRecord {
+    pub timestamp: String,
+    pub tool: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Grok prompt tokens, when the call made completions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<u64>,
+    /// Grok completion tokens, when the call made completions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_tokens: Option<u64>,
+    pub usd: f64,
+    /// Models whose completions had no configured rate; their tokens are
+    /// not included in `usd`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unpriced_models: Vec<String>,
+}
+
+/// Token usage of one Grok completion, as reported by the API.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CostEntry {
     pub timestamp: String,
@@ -188,9 +301,24 @@ pub struct DailyAggregate {
     pub by_operation: HashMap<String, OperationStats>,
 }
 
+/// Spend summed over a reporting period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodCosts {
+    pub period: String,
+    pub start_date: String,
+    pub total_cost: f64,
+    pub calls: u64,
+    pub tweets_read: u64,
+    pub by_operation: HashMap<String, OperationStats>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BudgetConfig {
     pub daily_limit_usd: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weekly_limit_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_limit_usd: Option<f64>,
     pub warn_threshold: f64,
     pub enabled: bool,
 }
@@ -199,6 +327,8 @@ impl Default for BudgetConfig {
     fn default() -> Self {
         Self {
             daily_limit_usd: 1.0,
+            weekly_limit_usd: None,
+            monthly_limit_usd: None,
             warn_threshold: 0.8,
             enabled: true,
         }
@@ -208,6 +338,9 @@ impl Default for BudgetConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CostData {
     pub entries: Vec<CostEntry>,
+    /// Per-call records from MCP `tools/call`; absent in older files.
+    #[serde(default)]
+    pub calls: Vec<CallRecord>,
     pub daily: Vec<DailyAggregate>,
     pub budget: BudgetConfig,
     pub total_lifetime_usd: f64,
@@ -217,6 +350,7 @@ impl Default for CostData {
     fn default() -> Self {
         Self {
             entries: Vec::new(),
+            calls: Vec::new(),
             daily: Vec::new(),
             budget: BudgetConfig::default(),
             total_lifetime_usd: 0.0,
@@ -225,11 +359,76 @@ impl Default for CostData {
 }
 
 pub struct BudgetStatus {
+    pub enabled: bool,
     pub allowed: bool,
     pub spent: f64,
     pub limit: f64,
     pub remaining: f64,
+    /// Spend has reached `warn_threshold` of the limit.
     pub warning: bool,
+    pub percent_used: f64,
+    /// Fraction of the limit (0-1) at which `warning` turns on.
+    pub warn_threshold: f64,
+}
+
+/// Spend against the limit for one budget window (`daily`, `weekly` or
+/// `monthly`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetWindow {
+    pub window: String,
+    pub spent: f64,
+    pub limit: f64,
+    pub remaining: f64,
+    pub percent_used: f64,
+    pub allowed: bool,
+}
+
+/// Projected time until the daily limit at the current burn rate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetForecast {
+    /// Hours of recent spend the rate was measured over.
+    pub window_hours: f64,
+    pub window_spent_usd: f64,
+    pub usd_per_hour: f64,
+    pub remaining_usd: f64,
+    /// `None` when nothing was spent in the window: the runway is unbounded.
+    pub hours_until_limit: Option<f64>,
+    pub hours_until_reset: f64,
+    /// Whether the limit is reached before the daily budget resets at UTC
+    /// midnight.
+    pub limit_before_reset: bool,
+}
+
+/// Grok pricing for one model, in USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelRate {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+/// What one MCP tool call cost, next to the daily budget it drew from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallCost {
+    pub tool: String,
+    /// Grok model the call used, for AI tools.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Configured rate for `model`; absent when the model is unpriced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate: Option<ModelRate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_tokens: Option<u64>,
+    pub estimated_usd: f64,
+    pub actual_usd: f64,
+    /// Models the call used that have no configured rate; `actual_usd`
+    /// leaves their completions out.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unpriced_models: Vec<String>,
+    pub daily_spent_usd: f64,
+    pub daily_limit_usd: f64,
+    pub daily_remaining_usd: f64,
 }
 
 // ---------------------------------------------------------------------------
@@ -288,6 +487,16 @@ pub struct GrokResponse {
     pub usage: GrokUsage,
 }
 
+impl GrokResponse {
+    pub fn token_usage(&self) -> TokenUsage {
+        TokenUsage {
+            model: self.model.clone(),
+            input_tokens: self.usage.prompt_tokens,
+            output_tokens: self.usage.completion_tokens,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GrokUsage {
     pub prompt_tokens: u64,
@@ -295,6 +504,15 @@ pub struct GrokUsage {
     pub total_tokens: u64,
 }
 
+/// Headline and key claims Grok extracted for an intelligence report.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportBrief {
+    #[serde(default)]
+    pub headline: String,
+    #[serde(default)]
+    pub key_claims: Vec<String>,
+}
+
 // ---------------------------------------------------------------------------
 // Article
 // ---------------------------------------------------------------------------
@@ -321,6 +539,8 @@ pub struct SentimentResult {
     pub id: String,
     pub sentiment: String, // positive, negative, neutral, mixed
     pub score: f64,        // -1.0 to 1.0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>, // 0.0 to 1.0
     #[serde(skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
 }
@@ -1,10 +1,14 @@
 use crate::cli::{Commands, PolicyMode};
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
 
 pub fn as_str(mode: PolicyMode) -> &'static str {
     match mode {
         PolicyMode::ReadOnly => "read_only",
         PolicyMode::Engagement => "engagement",
         PolicyMode::Moderation => "moderation",
+        PolicyMode::Full => "full",
     }
 }
 
@@ -13,6 +17,7 @@ fn rank(mode: PolicyMode) -> u8 {
         PolicyMode::ReadOnly => 1,
         PolicyMode::Engagement => 2,
         PolicyMode::Moderation => 3,
+        PolicyMode::Full => 4,
     }
 }
 
@@ -90,3 +95,28 @@ pub fn emit_policy_denied(cmd: &Commands, policy: PolicyMode, required: PolicyMo
     });
     eprintln!("{payload}");
 }
+
+/// One policy decision for an MCP tool call, as written to the audit log.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry<'a> {
+    pub timestamp: String,
+    pub tool: &'a str,
+    pub policy_mode: &'static str,
+    pub required_mode: &'static str,
+    /// `allowed`, `denied` or `disabled` (turned off by server config).
+    pub decision: &'static str,
+}
+
+/// Append `entry` to the JSONL audit log at `path`, creating it if needed.
+pub fn append_audit(path: &Path, entry: &AuditEntry) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
@@ -0,0 +1,175 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const DEFAULT_CALLS_PER_MINUTE: u32 = 60;
+
+#[derive(Debug, Clone)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Token-bucket limiter keyed by MCP tool name. Each tool gets `capacity`
+/// calls per `window`, refilled continuously.
+#[derive(Debug)]
+pub struct ToolRateLimiter {
+    capacity: u32,
+    window: Duration,
+    buckets: HashMap<String, Bucket>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimitState {
+    pub tool: String,
+    pub limit: u32,
+    pub remaining: u32,
+    pub window_seconds: u64,
+    pub retry_after_ms: u64,
+    pub reset_at: String,
+}
+
+impl ToolRateLimiter {
+    pub fn new(capacity: u32, window: Duration) -> Self {
+        Self {
+            capacity,
+            window,
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Build from `XINT_MCP_RATE_LIMIT` (calls per minute per tool, 0 disables).
+    pub fn from_env() -> Self {
+        Self::from_env_or(DEFAULT_CALLS_PER_MINUTE)
+    }
+
+    /// Like [`Self::from_env`], but falls back to `calls_per_minute` when the
+    /// env var is unset or invalid.
+    pub fn from_env_or(calls_per_minute: u32) -> Self {
+        let capacity = std::env::var("XINT_MCP_RATE_LIMIT")
+            .ok()
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .unwrap_or(calls_per_minute);
+        Self::new(capacity, Duration::from_secs(60))
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.capacity > 0 && !self.window.is_zero()
+    }
+
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    fn refill_rate_per_ms(&self) -> f64 {
+        self.capacity as f64 / self.window.as_millis().max(1) as f64
+    }
+
+    fn bucket(&mut self, tool: &str, now: Instant) -> &mut Bucket {
+        let capacity = self.capacity as f64;
+        let rate = self.refill_rate_per_ms();
+        let bucket = self.buckets.entry(tool.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+        let elapsed_ms = now.saturating_duration_since(bucket.updated_at).as_millis() as f64;
+        bucket.tokens = (bucket.tokens + elapsed_ms * rate).min(capacity);
+        bucket.updated_at = now;
+        bucket
+    }
+
+    fn state_at(&mut self, tool: &str, now: Instant) -> RateLimitState {
+        let capacity = self.capacity;
+        let window_seconds = self.window.as_secs();
+        let rate = self.refill_rate_per_ms();
+        let enabled = self.enabled();
+
+        let tokens = if enabled {
+            self.bucket(tool, now).tokens
+        } else {
+            capacity as f64
+        };
+        let retry_after_ms = if !enabled || tokens >= 1.0 {
+            0
+        } else {
+            ((1.0 - tokens) / rate).ceil() as u64
+        };
+        let full_in_ms = if enabled {
+            ((capacity as f64 - tokens) / rate).ceil() as u64
+        } else {
+            0
+        };
+
+        RateLimitState {
+            tool: tool.to_string(),
+            limit: capacity,
+            remaining: tokens.floor() as u32,
+            window_seconds,
+            retry_after_ms,
+            reset_at: (chrono::Utc::now() + chrono::Duration::milliseconds(full_in_ms as i64))
+                .to_rfc3339(),
+        }
+    }
+
+    /// Consume one call for `tool`, or return the limiter state when exhausted.
+    pub fn try_acquire(&mut self, tool: &str) -> Result<(), RateLimitState> {
+        if !self.enabled() {
+            return Ok(());
+        }
+        let now = Instant::now();
+        let bucket = self.bucket(tool, now);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        Err(self.state_at(tool, now))
+    }
+
+    /// Current limiter state for `tool` without consuming a call.
+    pub fn state(&mut self, tool: &str) -> RateLimitState {
+        self.state_at(tool, Instant::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exhausts_after_capacity_calls() {
+        let mut limiter = ToolRateLimiter::new(2, Duration::from_secs(60));
+        assert!(limiter.try_acquire("xint_search").is_ok());
+        assert!(limiter.try_acquire("xint_search").is_ok());
+
+        let state = limiter
+            .try_acquire("xint_search")
+            .expect_err("expected limiter to be exhausted");
+        assert_eq!(state.remaining, 0);
+        assert!(state.retry_after_ms > 0);
+
+        // Other tools keep their own bucket.
+        assert!(limiter.try_acquire("xint_profile").is_ok());
+    }
+
+    #[test]
+    fn state_does_not_consume_tokens() {
+        let mut limiter = ToolRateLimiter::new(3, Duration::from_secs(60));
+        assert_eq!(limiter.state("xint_trends").remaining, 3);
+        assert_eq!(limiter.state("xint_trends").remaining, 3);
+        limiter.try_acquire("xint_trends").expect("acquire");
+        assert_eq!(limiter.state("xint_trends").remaining, 2);
+    }
+
+    #[test]
+    fn zero_capacity_disables_limiter() {
+        let mut limiter = ToolRateLimiter::new(0, Duration::from_secs(60));
+        assert!(!limiter.enabled());
+        for _ in 0..10 {
+            assert!(limiter.try_acquire("xint_search").is_ok());
+        }
+    }
+}
@@ -57,6 +57,16 @@ fn round(value: f64, places: i32) -> f64 {
     (value * p).round() / p
 }
 
+/// Serializes read-modify-write cycles on the reliability file; concurrent MCP
+/// tool calls would otherwise overwrite each other's entries.
+static WRITE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+fn write_lock() -> std::sync::MutexGuard<'static, ()> {
+    WRITE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
 fn load_data(path: &Path) -> ReliabilityData {
     if !path.exists() {
         return ReliabilityData::default();
@@ -136,6 +146,7 @@ pub fn record_command_result(
         fallback,
     };
 
+    let _lock = write_lock();
     let mut data = load_data(reliability_path);
     data.entries.push(entry.clone());
     prune(&mut data);
@@ -251,6 +262,34 @@ mod tests {
         let _ = fs::remove_file(path);
     }
 
+    #[test]
+    fn concurrent_records_are_not_lost() {
+        let path = PathBuf::from("/tmp/xint-rs-test-reliability-concurrent.json");
+        let _ = fs::remove_file(&path);
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..5 {
+                        let _ = record_command_result(
+                            &path,
+                            "mcp:xint_search",
+                            true,
+                            10,
+                            ReliabilityMode::Mcp,
+                            false,
+                        );
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("writer thread");
+        }
+        assert_eq!(load_data(&path).entries.len(), 40);
+        let _ = fs::remove_file(path);
+    }
+
     #[test]
     fn fallback_markers_are_consumed_once() {
         mark_command_fallback("trends");
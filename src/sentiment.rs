@@ -8,26 +8,29 @@ For each tweet, provide:
 - id: the tweet ID
 - sentiment: one of "positive", "negative", "neutral", "mixed"
 - score: a number from -1.0 (very negative) to 1.0 (very positive)
+- confidence: a number from 0.0 to 1.0 for how certain the classification is
 - label: a 2-5 word reason (e.g., "excited about launch", "frustrated with bugs", "neutral observation")
 
 Detect sarcasm, irony, and context. Crypto/tech enthusiasm is positive. Complaints/frustration are negative. Questions and factual statements are neutral.
 
 Return ONLY valid JSON array, no markdown fences, no explanation."#;
 
-/// Analyze sentiment for a batch of tweets using Grok.
+/// Analyze sentiment for a batch of tweets using Grok, returning the results
+/// and the token usage of each completion.
 /// Processes in batches of 20 to stay within token limits.
 pub async fn analyze_sentiment(
     http: &reqwest::Client,
     api_key: &str,
     tweets: &[Tweet],
     model: Option<&str>,
-) -> Result<Vec<SentimentResult>> {
+) -> Result<(Vec<SentimentResult>, Vec<TokenUsage>)> {
     if tweets.is_empty() {
-        return Ok(Vec::new());
+        return Ok((Vec::new(), Vec::new()));
     }
 
     let batch_size = 20;
     let mut results = Vec::new();
+    let mut usage = Vec::new();
 
     for chunk in tweets.chunks(batch_size) {
         let tweet_context: String = chunk
@@ -66,6 +69,7 @@ pub async fn analyze_sentiment(
 
         match grok::grok_chat(http, api_key, &messages, &opts).await {
             Ok(response) => {
+                usage.push(response.token_usage());
                 let parsed = parse_json_response(&response.content, chunk);
                 results.extend(parsed);
             }
@@ -76,6 +80,7 @@ pub async fn analyze_sentiment(
                         id: t.id.clone(),
                         sentiment: "neutral".to_string(),
                         score: 0.0,
+                        confidence: None,
                         label: Some("analysis failed".to_string()),
                     });
                 }
@@ -83,7 +88,7 @@ pub async fn analyze_sentiment(
         }
     }
 
-    Ok(results)
+    Ok((results, usage))
 }
 
 /// Parse Grok's JSON response, with fallback handling for malformed output.
@@ -122,6 +127,7 @@ fn parse_json_response(content: &str, tweets: &[Tweet]) -> Vec<SentimentResult>
             id: t.id.clone(),
             sentiment: "neutral".to_string(),
             score: 0.0,
+            confidence: None,
             label: Some("parse failed".to_string()),
         })
         .collect()
@@ -151,6 +157,11 @@ fn parse_sentiment_item(item: &serde_json::Value) -> SentimentResult {
         .map(|s| (s * 100.0).round() / 100.0)
         .unwrap_or(0.0);
 
+    let confidence = item
+        .get("confidence")
+        .and_then(|v| v.as_f64())
+        .map(|c| (c.clamp(0.0, 1.0) * 100.0).round() / 100.0);
+
     let label = item
         .get("label")
         .and_then(|v| v.as_str())
@@ -160,6 +171,7 @@ fn parse_sentiment_item(item: &serde_json::Value) -> SentimentResult {
         id,
         sentiment,
         score,
+        confidence,
         label,
     }
 }
@@ -171,6 +183,112 @@ fn validate_sentiment(s: &str) -> &str {
     }
 }
 
+const POSITIVE_WORDS: &[&str] = &[
+    "amazing",
+    "awesome",
+    "best",
+    "bullish",
+    "excellent",
+    "excited",
+    "fantastic",
+    "good",
+    "great",
+    "happy",
+    "impressive",
+    "incredible",
+    "love",
+    "loving",
+    "nice",
+    "perfect",
+    "thanks",
+    "win",
+    "wins",
+    "wonderful",
+    "wow",
+];
+
+const NEGATIVE_WORDS: &[&str] = &[
+    "angry",
+    "awful",
+    "bad",
+    "bearish",
+    "broken",
+    "bug",
+    "bugs",
+    "disappointed",
+    "disappointing",
+    "fail",
+    "fails",
+    "hate",
+    "horrible",
+    "scam",
+    "slow",
+    "terrible",
+    "ugly",
+    "worse",
+    "worst",
+    "wrong",
+];
+
+const NEGATORS: &[&str] = &["not", "no", "never", "isn't", "don't", "doesn't", "can't"];
+
+/// Score tweets against a small word list when Grok is unavailable. A
+/// negator directly before a word flips its polarity.
+pub fn analyze_lexicon(tweets: &[Tweet]) -> Vec<SentimentResult> {
+    tweets
+        .iter()
+        .map(|t| {
+            let words: Vec<String> = t
+                .text
+                .split_whitespace()
+                .map(|w| {
+                    w.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+                        .to_lowercase()
+                })
+                .collect();
+
+            let (mut positive, mut negative) = (0i32, 0i32);
+            for (i, word) in words.iter().enumerate() {
+                let polarity = if POSITIVE_WORDS.contains(&word.as_str()) {
+                    1
+                } else if NEGATIVE_WORDS.contains(&word.as_str()) {
+                    -1
+                } else {
+                    continue;
+                };
+                let negated = i > 0 && NEGATORS.contains(&words[i - 1].as_str());
+                if (polarity > 0) != negated {
+                    positive += 1;
+                } else {
+                    negative += 1;
+                }
+            }
+
+            let hits = positive + negative;
+            let (sentiment, score, confidence) = if hits == 0 {
+                ("neutral", 0.0, 0.5)
+            } else {
+                let score = f64::from(positive - negative) / f64::from(hits);
+                let sentiment = match positive.cmp(&negative) {
+                    std::cmp::Ordering::Greater => "positive",
+                    std::cmp::Ordering::Less => "negative",
+                    std::cmp::Ordering::Equal => "neutral",
+                };
+                let confidence = (0.5 + 0.1 * f64::from((positive - negative).abs())).min(0.9);
+                (sentiment, (score * 100.0).round() / 100.0, confidence)
+            };
+
+            SentimentResult {
+                id: t.id.clone(),
+                sentiment: sentiment.to_string(),
+                score,
+                confidence: Some(confidence),
+                label: Some(format!("lexicon: {positive} positive, {negative} negative")),
+            }
+        })
+        .collect()
+}
+
 /// Compute aggregate sentiment stats.
 pub fn compute_stats(sentiments: &[SentimentResult]) -> SentimentStats {
     let mut positive = 0u32;
@@ -250,3 +368,32 @@ pub fn format_stats(stats: &SentimentStats, total: usize) -> String {
 
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tweet(id: &str, text: &str) -> Tweet {
+        Tweet {
+            id: id.to_string(),
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lexicon_labels_polarity_and_handles_negation() {
+        let results = analyze_lexicon(&[
+            tweet("1", "This launch is amazing, love it!"),
+            tweet("2", "Worst update ever. So many bugs."),
+            tweet("3", "Shipping the release on Tuesday."),
+            tweet("4", "Honestly not good."),
+        ]);
+        let labels: Vec<_> = results.iter().map(|r| r.sentiment.as_str()).collect();
+        assert_eq!(labels, ["positive", "negative", "neutral", "negative"]);
+        assert_eq!(results[0].score, 1.0);
+        assert!(results
+            .iter()
+            .all(|r| r.confidence.is_some_and(|c| (0.0..=1.0).contains(&c))));
+    }
+}
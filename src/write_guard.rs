@@ -0,0 +1,110 @@
+use std::path::{Component, Path, PathBuf};
+
+/// Lexically resolve `.` and `..` components of an absolute path.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// Resolve `path` to an absolute path. The longest existing ancestor is
+/// canonicalized as written, so a `..` after a symlink climbs out of the
+/// link's target just as the OS would; only the components past it, which
+/// do not exist yet and so cannot be links, are resolved lexically.
+fn resolve(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .unwrap_or_else(|_| PathBuf::from("/"))
+            .join(path)
+    };
+
+    let mut existing = absolute.as_path();
+    let mut remainder = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            let rest: PathBuf = remainder.iter().rev().collect();
+            return normalize(&canonical.join(rest));
+        }
+        match (existing.parent(), existing.components().next_back()) {
+            (Some(parent), Some(last)) => {
+                remainder.push(last.as_os_str().to_os_string());
+                existing = parent;
+            }
+            _ => return normalize(&absolute),
+        }
+    }
+}
+
+/// Return the resolved target when it falls inside one of `allowed_dirs`.
+/// An empty allowlist denies every write.
+pub fn resolve_write_path(target: &Path, allowed_dirs: &[PathBuf]) -> Option<PathBuf> {
+    let resolved = resolve(target);
+    allowed_dirs
+        .iter()
+        .map(|dir| resolve(dir))
+        .any(|dir| resolved.starts_with(&dir))
+        .then_some(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_allowlist_denies_writes() {
+        assert!(resolve_write_path(Path::new("/tmp/out.json"), &[]).is_none());
+    }
+
+    #[test]
+    fn allows_paths_inside_allowed_dir() {
+        let dir = std::env::temp_dir().join("xint-write-guard-test");
+        let allowed = vec![dir.clone()];
+        let resolved = resolve_write_path(&dir.join("exports/out.json"), &allowed)
+            .expect("path inside allowed dir");
+        assert!(resolved.ends_with("exports/out.json"));
+    }
+
+    #[test]
+    fn rejects_parent_dir_escapes() {
+        let dir = std::env::temp_dir().join("xint-write-guard-test");
+        let allowed = vec![dir.clone()];
+        assert!(resolve_write_path(&dir.join("../escape.json"), &allowed).is_none());
+        assert!(resolve_write_path(Path::new("/etc/passwd"), &allowed).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parent_dir_after_a_symlink_follows_the_link_target() {
+        let root = std::env::temp_dir().join("xint-write-guard-symlink-test");
+        let _ = std::fs::remove_dir_all(&root);
+        let allowed = root.join("allowed");
+        let outside = root.join("outside/inner");
+        std::fs::create_dir_all(&allowed).expect("allowed dir");
+        std::fs::create_dir_all(&outside).expect("outside dir");
+        std::os::unix::fs::symlink(&outside, allowed.join("link")).expect("symlink");
+
+        // Lexically this is `allowed/x`, but the OS writes `outside/x`.
+        let escape = allowed.join("link/../x.json");
+        assert!(resolve_write_path(&escape, std::slice::from_ref(&allowed)).is_none());
+        let resolved = resolve_write_path(&escape, &[root.join("outside")])
+            .expect("inside the link target's parent");
+        assert!(resolved.ends_with("outside/x.json"));
+
+        // Components past the existing ancestor still resolve lexically.
+        let fresh = allowed.join("new/../y.json");
+        let resolved = resolve_write_path(&fresh, std::slice::from_ref(&allowed))
+            .expect("stays in allowed dir");
+        assert!(resolved.ends_with("allowed/y.json"));
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}